@@ -1,8 +1,708 @@
-use reqwest::{header::HeaderMap, Client, Error, Response};
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
+use reqwest::{Client, Response, StatusCode};
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-pub async fn fetch<T: DeserializeOwned>(url: &str, headers: HeaderMap) -> Result<T, Error> {
-    let client = Client::new();
-    let resp: Response = client.get(url).headers(headers).send().await?;
-    return resp.json::<T>().await;
+use crate::log;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        backoff_delay, fetch_with, geocode_with, reverse_geocode_with, timezone_with, FetchError,
+        FetchOptions, GeocodeCache, MAX_BACKOFF,
+    };
+    use reqwest::header::HeaderMap;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Serves one canned HTTP response per request on a local port, repeating the last one.
+    /// Returns the base URL and the number of requests served so far.
+    fn serve(responses: Vec<(u16, &'static str)>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Error!");
+        let url = format!("http://{}", listener.local_addr().expect("Error!"));
+        let count = Arc::new(AtomicUsize::new(0));
+        let served = Arc::clone(&count);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(s) => s,
+                    Err(_) => return,
+                };
+                // Read the request head before responding
+                let mut request = Vec::new();
+                let mut buf = [0_u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let index = served
+                    .fetch_add(1, Ordering::SeqCst)
+                    .min(responses.len() - 1);
+                let (status, body) = responses[index];
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        return (url, count);
+    }
+
+    fn quick() -> FetchOptions {
+        return FetchOptions {
+            timeout: Duration::from_secs(5),
+            retries: 3,
+            backoff: Duration::from_millis(10),
+        };
+    }
+
+    #[tokio::test]
+    async fn test_fetch_retries_transient_errors() {
+        let (url, count) = serve(vec![(503, ""), (500, ""), (200, r#"{"lat":"43.6"}"#)]);
+        let value: serde_json::Value = fetch_with(&url, HeaderMap::new(), &quick())
+            .await
+            .expect("Error!");
+        assert_eq!(value["lat"], "43.6");
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        // Gives up after the configured retries
+        let (url, count) = serve(vec![(503, "")]);
+        let options = FetchOptions {
+            retries: 1,
+            ..quick()
+        };
+        let result = fetch_with::<serde_json::Value>(&url, HeaderMap::new(), &options).await;
+        assert!(matches!(result, Err(FetchError::Network(_))));
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_not_found() {
+        // Client errors are not retried
+        let (url, count) = serve(vec![(404, "")]);
+        let result = fetch_with::<serde_json::Value>(&url, HeaderMap::new(), &quick()).await;
+        assert!(matches!(result, Err(FetchError::NotFound)));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        let (url, _) = serve(vec![(200, "not json")]);
+        let result = fetch_with::<serde_json::Value>(&url, HeaderMap::new(), &quick()).await;
+        assert!(matches!(result, Err(FetchError::Decode(_))));
+    }
+
+    #[tokio::test]
+    async fn test_reverse_geocode() {
+        // Recorded from https://nominatim.openstreetmap.org/reverse?lat=43.6534817&lon=-79.3839347&format=jsonv2
+        let (url, _) = serve(vec![(
+            200,
+            r#"{"place_id":332071366,"lat":"43.6533","lon":"-79.3841","category":"amenity","type":"townhall","display_name":"Toronto City Hall, 100, Queen Street West, Toronto, Golden Horseshoe, Ontario, M5H 2N2, Canada","address":{"amenity":"Toronto City Hall","house_number":"100","road":"Queen Street West","city":"Toronto","state":"Ontario","ISO3166-2-lvl4":"CA-ON","postcode":"M5H 2N2","country":"Canada","country_code":"ca"}}"#,
+        )]);
+        let place = reverse_geocode_with(&url, 43.6534817, -79.3839347)
+            .await
+            .expect("Error!");
+        assert_eq!(place, "Toronto, Ontario, Canada");
+
+        // Villages and towns are used when there is no city
+        let (url, _) = serve(vec![(
+            200,
+            r#"{"display_name":"Whitby, Durham, Ontario, Canada","address":{"town":"Whitby","country":"Canada"}}"#,
+        )]);
+        let place = reverse_geocode_with(&url, 43.87, -78.94)
+            .await
+            .expect("Error!");
+        assert_eq!(place, "Whitby, Canada");
+
+        // Coordinates in the ocean have no address
+        let (url, _) = serve(vec![(200, r#"{"error":"Unable to geocode"}"#)]);
+        assert!(reverse_geocode_with(&url, 0.0, -30.0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_geocode() {
+        // Recorded from https://nominatim.openstreetmap.org/search?city=Springfield&country=United%20States&format=jsonv2
+        let (url, _) = serve(vec![(
+            200,
+            r#"[{"place_id":312542594,"lat":"39.7990175","lon":"-89.6439575","category":"boundary","type":"administrative","display_name":"Springfield, Sangamon County, Illinois, United States"},{"place_id":312720425,"lat":"37.2081729","lon":"-93.2922715","category":"boundary","type":"administrative","display_name":"Springfield, Greene County, Missouri, United States"},{"place_id":312266543,"lat":"42.1018764","lon":"-72.5886727","category":"boundary","type":"administrative","display_name":"Springfield, Hampden County, Massachusetts, United States"}]"#,
+        )]);
+        let matches = geocode_with(&url, "Springfield", "United States")
+            .await
+            .expect("Error!");
+        assert_eq!(matches.len(), 3);
+        assert_eq!(
+            matches[1].display_name,
+            "Springfield, Greene County, Missouri, United States"
+        );
+        assert_eq!((matches[1].lat, matches[1].lng), (37.2081729, -93.2922715));
+
+        let (url, _) = serve(vec![(200, "[]")]);
+        assert!(geocode_with(&url, "Nowhere", "Canada").await.is_err());
+
+        let (url, _) = serve(vec![(200, r#"[{"lat":"north","lon":"-79.38"}]"#)]);
+        assert!(geocode_with(&url, "Toronto", "Canada").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_timezone() {
+        // Shaped like https://timeapi.io/api/timezone/coordinate?latitude=33.6844&longitude=73.0479
+        let (url, _) = serve(vec![(
+            200,
+            r#"{"timeZone":"Asia/Karachi","currentLocalTime":"2024-02-11T12:00:00.0000000","currentUtcOffset":{"seconds":18000,"milliseconds":18000000,"ticks":180000000000,"nanoseconds":18000000000000},"standardUtcOffset":{"seconds":18000,"milliseconds":18000000,"ticks":180000000000,"nanoseconds":18000000000000},"hasDayLightSaving":false,"isDayLightSavingActive":false,"dstInterval":null}"#,
+        )]);
+        let timezone = timezone_with(&url, 33.6844, 73.0479).await.expect("Error!");
+        assert_eq!(timezone, "Asia/Karachi");
+
+        let (url, _) = serve(vec![(400, r#""Invalid coordinates""#)]);
+        assert!(timezone_with(&url, 95.0, 73.0479).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_timeout() {
+        // Accepts connections but never responds
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Error!");
+        let url = format!("http://{}", listener.local_addr().expect("Error!"));
+        std::thread::spawn(move || {
+            let _streams: Vec<_> = listener.incoming().collect();
+        });
+        let options = FetchOptions {
+            timeout: Duration::from_millis(100),
+            retries: 0,
+            ..quick()
+        };
+        match fetch_with::<serde_json::Value>(&url, HeaderMap::new(), &options).await {
+            Err(FetchError::Network(e)) => assert!(e.is_timeout()),
+            other => panic!("Expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_retries_body_timeout() {
+        // The first response stalls half way through its body, the second one is complete
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Error!");
+        let url = format!("http://{}", listener.local_addr().expect("Error!"));
+        std::thread::spawn(move || {
+            let mut held = vec![];
+            for (i, stream) in listener.incoming().enumerate() {
+                let mut stream = match stream {
+                    Ok(s) => s,
+                    Err(_) => return,
+                };
+                let mut buf = [0_u8; 1024];
+                let _ = stream.read(&mut buf);
+                let body = r#"{"lat":"43.6"}"#;
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(head.as_bytes());
+                if i == 0 {
+                    let _ = stream.write_all(&body.as_bytes()[..5]);
+                    held.push(stream);
+                } else {
+                    let _ = stream.write_all(body.as_bytes());
+                }
+            }
+        });
+        let options = FetchOptions {
+            timeout: Duration::from_millis(200),
+            ..quick()
+        };
+        let value: serde_json::Value = fetch_with(&url, HeaderMap::new(), &options)
+            .await
+            .expect("Error!");
+        assert_eq!(value["lat"], "43.6");
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let backoff = Duration::from_millis(500);
+        assert_eq!(backoff_delay(backoff, 0), backoff);
+        assert_eq!(backoff_delay(backoff, 3), Duration::from_secs(4));
+        // Large attempts are capped instead of overflowing
+        assert_eq!(backoff_delay(backoff, 10), MAX_BACKOFF);
+        assert_eq!(backoff_delay(backoff, 40), MAX_BACKOFF);
+        assert_eq!(backoff_delay(Duration::MAX, 1), MAX_BACKOFF);
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("salah-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        return dir.join("geocode.json");
+    }
+
+    #[test]
+    fn test_geocode_cache() {
+        let path = temp_path("hit-miss");
+        let cache = GeocodeCache::new(&path);
+        assert_eq!(cache.get("Toronto", "Canada"), None);
+
+        cache
+            .insert("Toronto", "Canada", 43.6534817, -79.3839347)
+            .expect("Error!");
+        assert!(path.exists());
+        assert_eq!(
+            cache.get("Toronto", "Canada"),
+            Some((43.6534817, -79.3839347))
+        );
+        // Keys ignore case and surrounding whitespace
+        assert_eq!(
+            cache.get(" toronto", "CANADA "),
+            Some((43.6534817, -79.3839347))
+        );
+        assert_eq!(cache.get("Toronto", "USA"), None);
+
+        // Existing entries are kept when another is added
+        cache
+            .insert("Makkah", "Saudi Arabia", 21.4225, 39.8262)
+            .expect("Error!");
+        assert!(cache.get("Toronto", "Canada").is_some());
+        assert!(cache.get("Makkah", "Saudi Arabia").is_some());
+
+        // Expired entries are a miss
+        let expired = GeocodeCache::new(&path).with_ttl(&Duration::ZERO);
+        assert_eq!(expired.get("Toronto", "Canada"), None);
+    }
+
+    #[test]
+    fn test_geocode_cache_corrupt() {
+        let path = temp_path("corrupt");
+        std::fs::create_dir_all(path.parent().expect("Error!")).expect("Error!");
+        std::fs::write(&path, "{not json").expect("Error!");
+
+        // A corrupt file is a miss and is replaced on the next insert
+        let cache = GeocodeCache::new(&path);
+        assert_eq!(cache.get("Toronto", "Canada"), None);
+        cache
+            .insert("Toronto", "Canada", 43.6534817, -79.3839347)
+            .expect("Error!");
+        assert!(cache.get("Toronto", "Canada").is_some());
+    }
+}
+
+/// Timeout and retry settings for `fetch_with`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchOptions {
+    /// Time allowed for each attempt, from connecting to reading the body
+    pub timeout: Duration,
+
+    /// Number of times a transient failure (timeout, connection error or 5xx) is retried
+    pub retries: u32,
+
+    /// Delay before the first retry, doubled on every following one
+    pub backoff: Duration,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        return FetchOptions {
+            timeout: Duration::from_secs(10),
+            retries: 3,
+            backoff: Duration::from_millis(500),
+        };
+    }
+}
+
+/// Why an API request failed
+#[derive(Debug)]
+pub enum FetchError {
+    /// The server responded with 404 Not Found
+    NotFound,
+
+    /// The request could not be completed, or the server still failed after every retry
+    Network(reqwest::Error),
+
+    /// The response body was not the expected JSON
+    Decode(serde_json::Error),
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "the requested resource was not found"),
+            Self::Network(e) => write!(f, "network error: {}", e),
+            Self::Decode(e) => write!(f, "unexpected response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NotFound => None,
+            Self::Network(e) => Some(e),
+            Self::Decode(e) => Some(e),
+        }
+    }
+}
+
+/// Fetches and parses JSON from `url` with the default timeout and retries (see `FetchOptions`)
+pub async fn fetch<T: DeserializeOwned>(url: &str, headers: HeaderMap) -> Result<T, FetchError> {
+    return fetch_with(url, headers, &FetchOptions::default()).await;
+}
+
+/// Fetches and parses JSON from `url`, retrying transient failures with exponential backoff
+///
+/// ### Arguments
+/// * `url` - The URL to request
+/// * `headers` - Headers sent with every attempt
+/// * `options` - Timeout and retry settings
+pub async fn fetch_with<T: DeserializeOwned>(
+    url: &str,
+    headers: HeaderMap,
+    options: &FetchOptions,
+) -> Result<T, FetchError> {
+    let client = Client::builder()
+        .timeout(options.timeout)
+        .build()
+        .map_err(FetchError::Network)?;
+
+    let mut attempt = 0;
+    loop {
+        // Reading the body can time out or be cut off like the request itself, only parsing it is a decode error
+        let body = match client
+            .get(url)
+            .headers(headers.clone())
+            .send()
+            .await
+            .and_then(Response::error_for_status)
+        {
+            Ok(resp) => resp.bytes().await,
+            Err(e) => Err(e),
+        };
+        match body {
+            Ok(body) => return serde_json::from_slice::<T>(&body).map_err(FetchError::Decode),
+            Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => {
+                return Err(FetchError::NotFound)
+            }
+            Err(e) if attempt < options.retries && is_transient(&e) => {
+                tokio::time::sleep(backoff_delay(options.backoff, attempt)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(FetchError::Network(e)),
+        }
+    }
+}
+
+/// Longest delay between two attempts, however many retries are configured
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Delay before retrying after a failed attempt (starting at 0): `backoff` doubled on every attempt, up to `MAX_BACKOFF`
+fn backoff_delay(backoff: Duration, attempt: u32) -> Duration {
+    return 2_u32
+        .checked_pow(attempt)
+        .and_then(|factor| backoff.checked_mul(factor))
+        .map_or(MAX_BACKOFF, |delay| delay.min(MAX_BACKOFF));
+}
+
+/// Whether a failed request is worth retrying
+fn is_transient(e: &reqwest::Error) -> bool {
+    return match e.status() {
+        Some(status) => status.is_server_error(),
+        None => e.is_timeout() || e.is_connect() || e.is_request() || e.is_body(),
+    };
+}
+
+/// Base URL of the Nominatim OpenStreetMap geocoding API
+pub const NOMINATIM_URL: &str = "https://nominatim.openstreetmap.org";
+
+/// Headers required by Nominatim, which rejects requests without a user agent
+pub fn nominatim_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(USER_AGENT, HeaderValue::from_static("salah-cli"));
+    return headers;
+}
+
+/// Maximum number of candidates requested from the Nominatim search endpoint
+pub const GEOCODE_LIMIT: usize = 5;
+
+/// A candidate location for a city and country, with its full name for disambiguation
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeocodeMatch {
+    /// Full name of the place (e.g. `Springfield, Sangamon County, Illinois, United States`)
+    pub display_name: String,
+    pub lat: f64,
+    pub lng: f64,
+}
+
+/// A single result of the Nominatim search endpoint
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    lat: String,
+    lon: String,
+    display_name: Option<String>,
+}
+
+/// Returns the candidate locations (at most `GEOCODE_LIMIT`, best match first) for a city and country using Nominatim
+///
+/// ### Arguments
+/// * `city` - The city to look up
+/// * `country` - The country of the city
+pub async fn geocode(city: &str, country: &str) -> Result<Vec<GeocodeMatch>> {
+    return geocode_with(NOMINATIM_URL, city, country).await;
+}
+
+/// Returns the candidate locations for a city and country from a Nominatim compatible API at `base_url`.
+/// Errors when there is no match.
+pub async fn geocode_with(base_url: &str, city: &str, country: &str) -> Result<Vec<GeocodeMatch>> {
+    let url = format!(
+        "{}/search?city={}&country={}&format=jsonv2&limit={}",
+        base_url, city, country, GEOCODE_LIMIT
+    );
+    let results = fetch::<Vec<SearchResult>>(url.as_str(), nominatim_headers())
+        .await
+        .with_context(|| {
+            format!(
+                "Could not get coordinates with city = `{}` and country = `{}`",
+                city, country
+            )
+        })?;
+
+    if results.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Could not find lat, lng from city = `{}` and country = `{}`. Please check spelling!",
+            city,
+            country
+        ));
+    }
+
+    let mut matches: Vec<GeocodeMatch> = vec![];
+    for result in results {
+        let lat = result
+            .lat
+            .parse::<f64>()
+            .with_context(|| format!("Could not convert `lat` = `{}` to f64", result.lat))?;
+        let lng = result
+            .lon
+            .parse::<f64>()
+            .with_context(|| format!("Could not convert `lng` = `{}` to f64", result.lon))?;
+        matches.push(GeocodeMatch {
+            display_name: result
+                .display_name
+                .unwrap_or_else(|| format!("{}, {}", city, country)),
+            lat,
+            lng,
+        });
+    }
+    return Ok(matches);
+}
+
+/// Response of the Nominatim reverse endpoint, either an address or an error
+#[derive(Debug, Deserialize)]
+struct ReverseResponse {
+    display_name: Option<String>,
+    address: Option<HashMap<String, String>>,
+    error: Option<String>,
+}
+
+/// Returns the place name (e.g. `Toronto, Ontario, Canada`) for the coordinates using Nominatim
+///
+/// ### Arguments
+/// * `lat` - The latitude value
+/// * `lng` - The longitude value
+pub async fn reverse_geocode(lat: f64, lng: f64) -> Result<String> {
+    return reverse_geocode_with(NOMINATIM_URL, lat, lng).await;
+}
+
+/// Returns the place name for the coordinates from a Nominatim compatible API at `base_url`.
+/// Errors when the API has no address for the coordinates (e.g. in the ocean).
+pub async fn reverse_geocode_with(base_url: &str, lat: f64, lng: f64) -> Result<String> {
+    let url = format!("{}/reverse?lat={}&lon={}&format=jsonv2", base_url, lat, lng);
+    let resp = fetch::<ReverseResponse>(url.as_str(), nominatim_headers())
+        .await
+        .with_context(|| {
+            format!(
+                "Could not get place name for lat = `{}`, lng = `{}`",
+                lat, lng
+            )
+        })?;
+
+    let no_address = || {
+        return anyhow::anyhow!(
+            "No address found for lat = `{}`, lng = `{}`{}",
+            lat,
+            lng,
+            resp.error
+                .as_ref()
+                .map(|e| format!(" ({})", e))
+                .unwrap_or_default()
+        );
+    };
+    let address = match &resp.address {
+        Some(address) => address,
+        None => return resp.display_name.clone().ok_or_else(no_address),
+    };
+
+    const LOCALITIES: [&str; 5] = ["city", "town", "village", "hamlet", "municipality"];
+    let locality = LOCALITIES.iter().find_map(|key| address.get(*key));
+    let parts: Vec<&String> = [locality, address.get("state"), address.get("country")]
+        .into_iter()
+        .flatten()
+        .collect();
+    if parts.is_empty() {
+        return resp.display_name.clone().ok_or_else(no_address);
+    }
+    return Ok(parts
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<&str>>()
+        .join(", "));
+}
+
+/// Base URL of the timeapi.io API, which looks up timezones from their boundaries
+pub const TIMEAPI_URL: &str = "https://timeapi.io";
+
+/// Response of the timeapi.io coordinate endpoint
+#[derive(Debug, Deserialize)]
+struct TimezoneResponse {
+    #[serde(rename = "timeZone")]
+    time_zone: String,
+}
+
+/// Returns the IANA timezone name (e.g. `Asia/Karachi`) of the coordinates using timeapi.io
+///
+/// ### Arguments
+/// * `lat` - The latitude value
+/// * `lng` - The longitude value, in [-180, 180]
+pub async fn timezone(lat: f64, lng: f64) -> Result<String> {
+    return timezone_with(TIMEAPI_URL, lat, lng).await;
+}
+
+/// Returns the IANA timezone name of the coordinates from a timeapi.io compatible API at `base_url`
+pub async fn timezone_with(base_url: &str, lat: f64, lng: f64) -> Result<String> {
+    let url = format!(
+        "{}/api/timezone/coordinate?latitude={}&longitude={}",
+        base_url, lat, lng
+    );
+    let resp = fetch::<TimezoneResponse>(url.as_str(), HeaderMap::new())
+        .await
+        .with_context(|| {
+            format!(
+                "Could not get the timezone for lat = `{}`, lng = `{}`",
+                lat, lng
+            )
+        })?;
+    return Ok(resp.time_zone);
+}
+
+/// How long geocoding results are reused before they are fetched again
+pub const GEOCODE_CACHE_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// A cached geocoding result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    lat: f64,
+    lng: f64,
+
+    // Seconds since the UNIX epoch when the coordinates were fetched
+    fetched: u64,
+}
+
+/// On-disk cache of city/country to latitude/longitude lookups, stored as a JSON object.
+/// A missing, unreadable or corrupt file is treated as an empty cache.
+#[derive(Debug, Clone)]
+pub struct GeocodeCache {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+impl GeocodeCache {
+    /// Creates a cache stored at `path`, entries expire after `GEOCODE_CACHE_TTL`
+    ///
+    /// ### Arguments
+    /// * `path` - The JSON file to read and write, parent directories are created on insert
+    pub fn new(path: &Path) -> Self {
+        return GeocodeCache {
+            path: path.to_path_buf(),
+            ttl: GEOCODE_CACHE_TTL,
+        };
+    }
+
+    /// The default cache file under the user's cache directory (e.g. `~/.cache/salah/geocode.json`),
+    /// `None` if the platform has no cache directory
+    pub fn default_path() -> Option<PathBuf> {
+        return dirs::cache_dir().map(|dir| dir.join("salah").join("geocode.json"));
+    }
+
+    /// Sets how long entries are reused before they are considered expired
+    pub fn with_ttl(mut self, ttl: &Duration) -> Self {
+        self.ttl = *ttl;
+        return self;
+    }
+
+    /// Returns the cached `(lat, lng)` for the city and country if present and not expired
+    pub fn get(&self, city: &str, country: &str) -> Option<(f64, f64)> {
+        let entries = self.entries();
+        let entry = entries.get(&cache_key(city, country))?;
+        let age = now_secs().saturating_sub(entry.fetched);
+        if Duration::from_secs(age) >= self.ttl {
+            return None;
+        }
+        return Some((entry.lat, entry.lng));
+    }
+
+    /// Stores the coordinates for the city and country, replacing any previous entry
+    pub fn insert(&self, city: &str, country: &str, lat: f64, lng: f64) -> Result<()> {
+        let mut entries = self.entries();
+        entries.insert(
+            cache_key(city, country),
+            CacheEntry {
+                lat,
+                lng,
+                fetched: now_secs(),
+            },
+        );
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Could not create cache directory `{}`", dir.display()))?;
+        }
+        let json = serde_json::to_string(&entries).with_context(|| "Could not serialize cache")?;
+        std::fs::write(&self.path, json)
+            .with_context(|| format!("Could not write cache `{}`", self.path.display()))?;
+        return Ok(());
+    }
+
+    fn entries(&self) -> HashMap<String, CacheEntry> {
+        let json = match std::fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(_) => return HashMap::new(),
+        };
+        return match serde_json::from_str(&json) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn(format!(
+                    "Ignoring unreadable cache `{}`: {}",
+                    self.path.display(),
+                    e
+                ));
+                HashMap::new()
+            }
+        };
+    }
+}
+
+fn cache_key(city: &str, country: &str) -> String {
+    return format!(
+        "{},{}",
+        city.trim().to_lowercase(),
+        country.trim().to_lowercase()
+    );
+}
+
+fn now_secs() -> u64 {
+    return SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
 }
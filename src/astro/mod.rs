@@ -1,7 +1,239 @@
+use crate::error::SalahError;
 use crate::math::*;
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
 
-/// Returns the Julian Date for the given date
+#[cfg(test)]
+mod tests {
+    use super::{
+        day_of_year, delta_t, equation_of_time, from_julian, horizon_hour_angle,
+        horizon_hour_angle_from_decl, julian, julian_from_datetime, shadow_length_hour,
+        shadow_length_hour_from_decl, sun_altitude, sun_coords, sun_coords_precise,
+        sun_declination, try_horizon_hour_angle_from_decl, zenith, zenith_from_eqt,
+        HorizonDirection, Precision,
+    };
+    use chrono::{Datelike, NaiveDate, Timelike};
+
+    // Reference values from Jean Meeus, Astronomical Algorithms (Ch. 7)
+    const REFERENCE: [(i32, u32, u32, f64); 8] = [
+        (-4713, 11, 24, -0.5),
+        (1, 1, 1, 1_721_425.5),
+        (1600, 1, 1, 2_305_447.5),
+        (1957, 10, 4, 2_436_115.5),
+        (1987, 1, 27, 2_446_822.5),
+        (2000, 1, 1, 2_451_544.5),
+        (2100, 3, 1, 2_488_128.5),
+        (3000, 1, 1, 2_816_787.5),
+    ];
+
+    #[test]
+    fn test_julian() {
+        for (y, m, d, expected) in REFERENCE {
+            let date = NaiveDate::from_ymd_opt(y, m, d).expect("Error!");
+            assert_eq!(julian(date), expected, "julian({})", date);
+        }
+    }
+
+    #[test]
+    fn test_julian_from_datetime() {
+        let at = |y: i32, m: u32, d: u32, h: u32, min: u32, s: u32| {
+            return NaiveDate::from_ymd_opt(y, m, d)
+                .and_then(|date| date.and_hms_opt(h, min, s))
+                .expect("Error!");
+        };
+        // Midnight matches the date-only version
+        for (y, m, d, expected) in REFERENCE {
+            assert_eq!(julian_from_datetime(at(y, m, d, 0, 0, 0)), expected);
+        }
+        // Reference values from Jean Meeus, Astronomical Algorithms (Ch. 7)
+        assert_eq!(julian_from_datetime(at(2000, 1, 1, 12, 0, 0)), 2_451_545.0);
+        assert_eq!(julian_from_datetime(at(1988, 6, 19, 12, 0, 0)), 2_447_332.0);
+        let sputnik = julian_from_datetime(at(1957, 10, 4, 19, 26, 24));
+        assert!((sputnik - 2_436_116.31).abs() < 1e-6, "{}", sputnik);
+        // Sub-second precision is kept
+        let precise = at(2024, 2, 11, 18, 0, 0)
+            .with_nanosecond(500_000_000)
+            .expect("Error!");
+        let expected = 2_460_352.25 + 0.5 / 86_400.0;
+        assert!((julian_from_datetime(precise) - expected).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_from_julian() {
+        for (y, m, d, jd) in REFERENCE {
+            let expected = NaiveDate::from_ymd_opt(y, m, d);
+            assert_eq!(from_julian(jd), expected, "from_julian({})", jd);
+            // Any time of the day maps back to the same date
+            assert_eq!(
+                from_julian(jd + 0.99),
+                expected,
+                "from_julian({})",
+                jd + 0.99
+            );
+        }
+    }
+
+    #[test]
+    fn test_day_of_year() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 31).expect("Error!");
+        assert_eq!(day_of_year(julian(date)), Some(366));
+        let date = NaiveDate::from_ymd_opt(2023, 2, 11).expect("Error!");
+        assert_eq!(day_of_year(julian(date) + 0.5), Some(42));
+    }
+
+    #[test]
+    fn test_julian_extreme_years() {
+        // Every 7th day from year -10000 to 10000 must be exact and round trip
+        let mut date = NaiveDate::from_ymd_opt(-10_000, 1, 1).expect("Error!");
+        let end = NaiveDate::from_ymd_opt(10_000, 12, 31).expect("Error!");
+        while date <= end {
+            let expected = date.num_days_from_ce() as f64 + 1_721_424.5;
+            assert_eq!(julian(date), expected, "julian({})", date);
+            assert_eq!(
+                from_julian(expected),
+                Some(date),
+                "from_julian({})",
+                expected
+            );
+            date += chrono::Duration::days(7);
+        }
+    }
+
+    #[test]
+    fn test_try_horizon_hour_angle_from_decl() {
+        // Equinox at the equator, sunrise/sunset are 6 hours from noon
+        let sunrise =
+            try_horizon_hour_angle_from_decl(0.0, 0.0, 12.0, 0.0, HorizonDirection::Sunrise)
+                .expect("Error!");
+        assert!((sunrise - 6.0).abs() < 1e-9);
+
+        // At 60N around the June solstice the sun never gets 18 degrees below the horizon
+        assert_eq!(
+            try_horizon_hour_angle_from_decl(18.0, 23.44, 12.0, 60.0, HorizonDirection::Sunrise),
+            None
+        );
+        assert!(
+            horizon_hour_angle_from_decl(18.0, 23.44, 12.0, 60.0, HorizonDirection::Sunset)
+                .is_nan()
+        );
+
+        // Polar night, the sun never rises
+        assert_eq!(
+            try_horizon_hour_angle_from_decl(0.833, -23.44, 12.0, 80.0, HorizonDirection::Sunrise),
+            None
+        );
+    }
+
+    #[test]
+    fn test_julian_date_helpers() {
+        // The Julian date helpers compute the sun coordinates themselves and match the `_from` variants
+        let jd = julian(NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!"));
+        let (eqt, decl) = sun_coords(jd);
+        let noon = zenith(jd, -79.38, -5.0);
+        assert_eq!(noon, zenith_from_eqt(eqt, -79.38, -5.0));
+        assert_eq!(
+            horizon_hour_angle(0.833, jd, noon, 43.65, HorizonDirection::Sunrise),
+            horizon_hour_angle_from_decl(0.833, decl, noon, 43.65, HorizonDirection::Sunrise)
+        );
+        assert_eq!(
+            shadow_length_hour(1.0, jd, noon, 43.65),
+            shadow_length_hour_from_decl(1.0, decl, noon, 43.65, true)
+        );
+    }
+
+    #[test]
+    fn test_sun_altitude() {
+        // Equinox at the equator, the sun is overhead at noon and on the horizon 6 hours before
+        assert!((sun_altitude(12.0, 0.0, 12.0, 0.0) - 90.0).abs() < 1e-9);
+        assert!(sun_altitude(6.0, 0.0, 12.0, 0.0).abs() < 1e-9);
+
+        // Inverse of `horizon_hour_angle_from_decl`
+        let sunset =
+            horizon_hour_angle_from_decl(0.833, 15.0, 12.2, 43.65, HorizonDirection::Sunset);
+        assert!((sun_altitude(sunset, 15.0, 12.2, 43.65) + 0.833).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_equation_of_time_and_declination() {
+        // Jean Meeus, Astronomical Algorithms, examples 25.a and 28.a (1992 October 13.0)
+        let jd = 2_448_908.5;
+        assert!((equation_of_time(jd) - 0.228500).abs() < 0.001);
+        assert!((sun_declination(jd) - -7.78507).abs() < 0.005);
+        assert_eq!((equation_of_time(jd), sun_declination(jd)), sun_coords(jd));
+
+        // J2000.0, about 3.3 minutes behind the clock near the December solstice
+        let jd = 2_451_545.0;
+        assert!((equation_of_time(jd) * 60.0 - -3.3).abs() < 0.05);
+        assert!((sun_declination(jd) - -23.03).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_delta_t() {
+        // Espenak and Meeus fits at the start of each span, and the observed ~69s in 2024
+        let at = |y, m, d| delta_t(julian(NaiveDate::from_ymd_opt(y, m, d).expect("Error!")));
+        assert!((at(1900, 1, 1) - -2.79).abs() < 0.1);
+        assert!((at(1950, 1, 1) - 29.07).abs() < 0.1);
+        assert!((at(2000, 1, 1) - 63.86).abs() < 0.1);
+        assert!((at(2024, 1, 1) - 69.0).abs() < 6.0);
+        // Continuous across the spans
+        for y in [
+            1600, 1700, 1800, 1860, 1900, 1920, 1941, 1961, 1986, 2005, 2050, 2150,
+        ] {
+            let before = at(y - 1, 12, 31);
+            let after = at(y, 1, 1);
+            assert!(
+                (before - after).abs() < 2.0,
+                "{}: {} vs {}",
+                y,
+                before,
+                after
+            );
+        }
+    }
+
+    #[test]
+    fn test_precision_from_str() {
+        for precision in [Precision::Low, Precision::High] {
+            assert_eq!(precision.to_string().parse(), Ok(precision));
+        }
+        assert_eq!(Precision::from_str("HIGH"), Ok(Precision::High));
+        assert!(Precision::from_str("medium").is_err());
+    }
+
+    #[test]
+    fn test_precision_against_ephemeris() {
+        // Jean Meeus, Astronomical Algorithms, examples 25.a and 28.a: 1992 October 13.0 TD
+        let jde = 2_448_908.5;
+        let decl_ref = -7.78507;
+        let eqt_ref = 13.711 / 60.0;
+
+        // The models take the Julian Date in UT, ΔT behind TD
+        let jd = jde - (delta_t(jde) / 86_400.0);
+        let (_, low_decl) = Precision::Low.sun_coords(jd);
+        let (high_eqt, high_decl) = Precision::High.sun_coords(jd);
+
+        assert!((high_decl - decl_ref).abs() < 0.0005, "{}", high_decl);
+        assert!((high_decl - decl_ref).abs() < (low_decl - decl_ref).abs());
+        // The equation of time is within a second of the reference
+        assert!((high_eqt - eqt_ref).abs() * 3600.0 < 1.0, "{}", high_eqt);
+    }
+
+    #[test]
+    fn test_sun_coords_equinox() {
+        // The equation of time never exceeds ~17 minutes, including across the right ascension wrap
+        let start = NaiveDate::from_ymd_opt(2024, 3, 15).expect("Error!");
+        for date in start.iter_days().take(14) {
+            let jd = julian(date);
+            let (low, _) = sun_coords(jd);
+            let (high, _) = sun_coords_precise(jd);
+            assert!(low.abs() < 0.3, "{}: {}", date, low);
+            assert!((low - high).abs() < 0.01, "{}: {} vs {}", date, low, high);
+        }
+    }
+}
+
+/// Returns the Julian Date for the given date (at 00:00) in the proleptic Gregorian calendar.
+/// All terms are whole multiples of 0.25 before flooring, so the result is exact for any year
+/// `NaiveDate` can represent.
 ///
 /// ### Arguments
 ///
@@ -15,8 +247,8 @@ pub fn julian(date: NaiveDate) -> f64 {
     let mut m = month as f64;
     let d = day as f64;
     if m == 1.0 || m == 2.0 {
-        y = y - 1.0;
-        m = m + 12.0;
+        y -= 1.0;
+        m += 12.0;
     }
 
     let a = (y / 100.0).floor();
@@ -24,10 +256,53 @@ pub fn julian(date: NaiveDate) -> f64 {
     return (365.25 * (y + 4716.0)).floor() + (30.6001 * (m + 1.0)).floor() + d + b - 1524.5;
 }
 
+/// Returns the Julian Date for the given date and time (UTC), including the fraction of the day.
+/// Julian days start at noon, so 00:00 is `x.5` (as `julian`) and 12:00 is a whole number.
+///
+/// ### Arguments
+///
+/// * `datetime` - The date and time in UTC
+pub fn julian_from_datetime(datetime: NaiveDateTime) -> f64 {
+    let seconds = datetime.num_seconds_from_midnight() as f64
+        + datetime.nanosecond() as f64 / 1_000_000_000_f64;
+    return julian(datetime.date()) + seconds / 86_400_f64;
+}
+
+/// Returns the date for the given Julian Date (proleptic Gregorian calendar)
+/// as per Jean Meeus, Astronomical Algorithms (Ch. 7). The fraction of the day is ignored.
+///
+/// ### Arguments
+///
+/// * `jd` - A float value representing the Julian Date
+pub fn from_julian(jd: f64) -> Option<NaiveDate> {
+    let z = (jd + 0.5).floor();
+    let alpha = ((z - 1_867_216.25) / 36_524.25).floor();
+    let a = z + 1.0 + alpha - (alpha / 4.0).floor();
+    let b = a + 1524.0;
+    let c = ((b - 122.1) / 365.25).floor();
+    let d = (365.25 * c).floor();
+    let e = ((b - d) / 30.6001).floor();
+
+    let day = b - d - (30.6001 * e).floor();
+    let month = if e < 14.0 { e - 1.0 } else { e - 13.0 };
+    let year = if month > 2.0 { c - 4716.0 } else { c - 4715.0 };
+
+    return NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32);
+}
+
+/// Returns the day of the year (1 - 366) for the given Julian Date, `None` if it is out of range
+///
+/// ### Arguments
+///
+/// * `jd` - A float value representing the Julian Date
+pub fn day_of_year(jd: f64) -> Option<u32> {
+    return from_julian(jd).map(|date| date.ordinal());
+}
+
 /// Returns the Equation of Time and Declination of the Sun for a given Julian Date
 /// as per the approximation found at: https://web.archive.org/web/20181115153648/http://aa.usno.navy.mil/faq/docs/SunApprox.php
 ///
-/// Equation of Time is in hours (0 - 24)
+/// Equation of Time is in hours (within about ±0.3)
 /// Declination of the Sun is in degrees
 ///
 /// ### Arguments
@@ -58,30 +333,274 @@ pub fn sun_coords(jd: f64) -> (f64, f64) {
     // Converting to hours
     ra = time::normalize_hour(ra);
 
-    let eqt = (q / 15.0) - ra;
+    // Wrapped to [-12, 12) hours, the right ascension jumps from 24 to 0 around the March equinox
+    let eqt = time::normalize_hour((q / 15.0) - ra + 12.0) - 12.0;
 
     let decl = deg::asin(deg::sin(e) * deg::sin(l));
 
     return (eqt, decl);
 }
 
+/// Returns the Equation of Time and Declination of the Sun for a given Julian Date
+/// using the higher accuracy algorithm from Jean Meeus, Astronomical Algorithms (Ch. 25 & 28),
+/// which accounts for the centuries since J2000, nutation and aberration.
+///
+/// Equation of Time is in hours
+/// Declination of the Sun is in degrees
+///
+/// ### Arguments
+///
+/// * `jd` - A float value representing the Julian Date
+pub fn sun_coords_precise(jd: f64) -> (f64, f64) {
+    // Julian centuries since J2000.0
+    let t = (jd - 2_451_545.0) / 36_525.0;
+
+    // Geometric mean longitude of the Sun
+    let l0 = deg::normalize_angle(280.46646 + (36_000.769_83 * t) + (0.0003032 * t * t));
+
+    // Mean anomaly of the Sun
+    let m = deg::normalize_angle(357.52911 + (35_999.050_29 * t) - (0.0001537 * t * t));
+
+    // Equation of center
+    let c = ((1.914602 - (0.004817 * t) - (0.000014 * t * t)) * deg::sin(m))
+        + ((0.019993 - (0.000101 * t)) * deg::sin(2.0 * m))
+        + (0.000289 * deg::sin(3.0 * m));
+
+    // Longitude of the ascending node of the Moon's orbit
+    let omega = deg::normalize_angle(125.04 - (1_934.136 * t));
+
+    // Apparent longitude of the Sun (corrected for nutation and aberration)
+    let lambda = l0 + c - 0.00569 - (0.00478 * deg::sin(omega));
+
+    // Mean obliquity of the ecliptic, corrected for nutation
+    let e0 = 23.439291 - (0.0130042 * t) - (0.00000016 * t * t) + (0.000000504 * t * t * t);
+    let e = e0 + (0.00256 * deg::cos(omega));
+
+    // Apparent right ascension of the Sun, in degrees
+    let ra = deg::normalize_angle(deg::atan2(deg::cos(e) * deg::sin(lambda), deg::cos(lambda)));
+
+    let decl = deg::asin(deg::sin(e) * deg::sin(lambda));
+
+    // Nutation in longitude, in degrees
+    let moon_l = 218.3165 + (481_267.881_3 * t);
+    let nutation = ((-17.20 * deg::sin(omega))
+        - (1.32 * deg::sin(2.0 * l0))
+        - (0.23 * deg::sin(2.0 * moon_l))
+        + (0.21 * deg::sin(2.0 * omega)))
+        / 3600.0;
+
+    // Equation of time (Meeus 28.1), wrapped to [-180, 180) degrees then converted to hours
+    let eqt = deg::normalize_angle(l0 - 0.0057183 - ra + (nutation * deg::cos(e)) + 180.0) - 180.0;
+
+    return (eqt / 15.0, decl);
+}
+
+/// Returns ΔT (Terrestrial Time minus Universal Time) in seconds for a given Julian Date,
+/// using the polynomial fits by Espenak and Meeus (NASA, 2006). The sun's position is computed in
+/// Terrestrial Time, which runs about a minute ahead of the clock (UT) in the 21st century.
+///
+/// ### Arguments
+///
+/// * `jd` - A float value representing the Julian Date (UT)
+pub fn delta_t(jd: f64) -> f64 {
+    let y = 2000_f64 + (jd - 2_451_545.0) / 365.25;
+    let long_term = |y: f64| {
+        let u = (y - 1820_f64) / 100_f64;
+        return -20_f64 + (32_f64 * u * u);
+    };
+    let poly = |t: f64, coefficients: &[f64]| {
+        return coefficients
+            .iter()
+            .rev()
+            .fold(0_f64, |acc, c| (acc * t) + c);
+    };
+
+    return match y {
+        y if y < -500_f64 => long_term(y),
+        y if y < 500_f64 => poly(
+            y / 100_f64,
+            &[
+                10_583.6,
+                -1_014.41,
+                33.783_11,
+                -5.952_053,
+                -0.179_845_2,
+                0.022_174_192,
+                0.009_031_652_1,
+            ],
+        ),
+        y if y < 1600_f64 => poly(
+            (y - 1000_f64) / 100_f64,
+            &[
+                1_574.2,
+                -556.01,
+                71.234_72,
+                0.319_781,
+                -0.850_346_3,
+                -0.005_050_998,
+                0.008_357_207_3,
+            ],
+        ),
+        y if y < 1700_f64 => poly(y - 1600_f64, &[120.0, -0.9808, -0.01532, 1.0 / 7129.0]),
+        y if y < 1800_f64 => poly(
+            y - 1700_f64,
+            &[8.83, 0.1603, -0.005_928_5, 0.000_133_36, -1.0 / 1_174_000.0],
+        ),
+        y if y < 1860_f64 => poly(
+            y - 1800_f64,
+            &[
+                13.72,
+                -0.332_447,
+                0.006_861_2,
+                0.004_111_6,
+                -0.000_374_36,
+                0.000_012_127_2,
+                -0.000_000_169_9,
+                0.000_000_000_875,
+            ],
+        ),
+        y if y < 1900_f64 => poly(
+            y - 1860_f64,
+            &[
+                7.62,
+                0.5737,
+                -0.251_754,
+                0.016_806_68,
+                -0.000_447_362_4,
+                1.0 / 233_174.0,
+            ],
+        ),
+        y if y < 1920_f64 => poly(
+            y - 1900_f64,
+            &[-2.79, 1.494_119, -0.059_893_9, 0.006_196_6, -0.000_197],
+        ),
+        y if y < 1941_f64 => poly(y - 1920_f64, &[21.20, 0.844_93, -0.076_100, 0.002_093_6]),
+        y if y < 1961_f64 => poly(y - 1950_f64, &[29.07, 0.407, -1.0 / 233.0, 1.0 / 2547.0]),
+        y if y < 1986_f64 => poly(y - 1975_f64, &[45.45, 1.067, -1.0 / 260.0, -1.0 / 718.0]),
+        y if y < 2005_f64 => poly(
+            y - 2000_f64,
+            &[
+                63.86,
+                0.3345,
+                -0.060_374,
+                0.001_727_5,
+                0.000_651_814,
+                0.000_023_735_99,
+            ],
+        ),
+        y if y < 2050_f64 => poly(y - 2000_f64, &[62.92, 0.322_17, 0.005_589]),
+        y if y < 2150_f64 => long_term(y) - (0.5628 * (2150_f64 - y)),
+        y => long_term(y),
+    };
+}
+
+/// Returns the Equation of Time for a given Julian Date in hours (see `sun_coords`).
+///
+/// This is apparent solar time minus mean solar time: positive when a sundial is ahead of the clock,
+/// ranging from about -14 minutes (-0.24) in February to about +16 minutes (+0.27) in November.
+/// Multiply by 60 for minutes.
+///
+/// ### Arguments
+///
+/// * `jd` - A float value representing the Julian Date
+pub fn equation_of_time(jd: f64) -> f64 {
+    let (eqt, _) = sun_coords(jd);
+    return eqt;
+}
+
+/// Returns the Declination of the Sun for a given Julian Date in degrees (see `sun_coords`).
+///
+/// This is the angle of the sun north (positive) or south (negative) of the celestial equator,
+/// between about -23.44 at the December solstice and +23.44 at the June solstice.
+///
+/// ### Arguments
+///
+/// * `jd` - A float value representing the Julian Date
+pub fn sun_declination(jd: f64) -> f64 {
+    let (_, decl) = sun_coords(jd);
+    return decl;
+}
+
+/// The solar position model used for the calculations
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Precision {
+    /// Low precision USNO approximation (~1 arcminute), fast
+    Low,
+    /// Higher precision Meeus algorithm evaluated in Terrestrial Time (see `delta_t`), slightly slower
+    High,
+}
+
+impl Precision {
+    pub fn from_str(name: &str) -> Result<Self, SalahError> {
+        let lowercase = name.to_lowercase();
+        match lowercase.as_str() {
+            "low" => Ok(Self::Low),
+            "high" => Ok(Self::High),
+            _ => Err(SalahError::InvalidPrecision(name.to_owned())),
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Low => "low",
+            Self::High => "high",
+        }
+    }
+
+    /// Returns the Equation of Time (hours) and Declination of the Sun (degrees) using this model
+    pub fn sun_coords(&self, jd: f64) -> (f64, f64) {
+        match self {
+            Self::Low => sun_coords(jd),
+            Self::High => sun_coords_precise(jd + (delta_t(jd) / 86_400_f64)),
+        }
+    }
+}
+
+impl std::fmt::Display for Precision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f.pad(self.to_str());
+    }
+}
+
+impl std::str::FromStr for Precision {
+    type Err = SalahError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return Precision::from_str(name);
+    }
+}
+
 /// Gets the zenith time in hours of the day (0 - 24)
 ///
 /// ### Arguments
 /// * `jd` - The Julian date
-/// * `lng` - The longitude value
+/// * `lng` - The longitude value, east-positive (see `math::normalize_longitude`)
 /// * `tz` - The timezone offset value
 pub fn zenith(jd: f64, lng: f64, tz: f64) -> f64 {
     let eqt = sun_coords(jd).0;
-    return 12_f64 + tz - (lng / 15_f64) - eqt;
+    return zenith_from_eqt(eqt, lng, tz);
+}
+
+/// Gets the zenith time in hours of the day (0 - 24) from an already calculated equation of time.
+/// Normalized, since near the date line the offset and longitude don't cancel out (e.g. 36.5 hours in Kiritimati,
+/// UTC+14 at 157°W), which would evaluate the sun a day late when refining the events.
+///
+/// ### Arguments
+/// * `eqt` - The equation of time in hours (see `sun_coords`)
+/// * `lng` - The longitude value, east-positive (see `math::normalize_longitude`)
+/// * `tz` - The timezone offset value
+pub fn zenith_from_eqt(eqt: f64, lng: f64, tz: f64) -> f64 {
+    return time::normalize_hour(12_f64 + tz - (lng / 15_f64) - eqt);
 }
 
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum HorizonDirection {
     Sunrise,
     Sunset,
 }
 
-/// Gets the hour at which the sun makes a specified angle from the horizon
+/// Gets the hour at which the sun makes a specified angle from the horizon.
+/// Returns NaN if the sun never reaches the angle (see `try_horizon_hour_angle_from_decl`).
 ///
 /// ### Arguments
 /// * `angle` - The angle to calculate the time for (0 would be sunrise/sunset time)
@@ -97,29 +616,110 @@ pub fn horizon_hour_angle(
     direction: HorizonDirection,
 ) -> f64 {
     let decl = sun_coords(jd).1;
-    let t_a = (1_f64 / 15_f64)
-        * deg::acos(
-            (-deg::sin(angle) - deg::sin(lat) * deg::sin(decl)) / (deg::cos(lat) * deg::cos(decl)),
-        );
+    return horizon_hour_angle_from_decl(angle, decl, zenith, lat, direction);
+}
+
+/// Gets the hour at which the sun makes a specified angle from the horizon from an already calculated declination.
+/// Returns NaN if the sun never reaches the angle (see `try_horizon_hour_angle_from_decl`).
+///
+/// ### Arguments
+/// * `angle` - The angle to calculate the time for (0 would be sunrise/sunset time)
+/// * `decl` - The declination of the sun in degrees (see `sun_coords`)
+/// * `zenith` - The hour time for when the sun hits the zenith
+/// * `lat` - The latitude value
+/// * `direction` - The direction to calculate the angle for (from Sunrise, from Sunset)
+pub fn horizon_hour_angle_from_decl(
+    angle: f64,
+    decl: f64,
+    zenith: f64,
+    lat: f64,
+    direction: HorizonDirection,
+) -> f64 {
+    return try_horizon_hour_angle_from_decl(angle, decl, zenith, lat, direction)
+        .unwrap_or(f64::NAN);
+}
+
+/// Gets the hour at which the sun makes a specified angle from the horizon.
+/// Returns `None` if the sun never crosses the angle on that day, e.g. fajr/isha angles near the poles in the summer
+/// or sunrise in the polar night.
+///
+/// ### Arguments
+/// * `angle` - The angle to calculate the time for (0 would be sunrise/sunset time)
+/// * `decl` - The declination of the sun in degrees (see `sun_coords`)
+/// * `zenith` - The hour time for when the sun hits the zenith
+/// * `lat` - The latitude value
+/// * `direction` - The direction to calculate the angle for (from Sunrise, from Sunset)
+pub fn try_horizon_hour_angle_from_decl(
+    angle: f64,
+    decl: f64,
+    zenith: f64,
+    lat: f64,
+    direction: HorizonDirection,
+) -> Option<f64> {
+    let cos_t =
+        (-deg::sin(angle) - deg::sin(lat) * deg::sin(decl)) / (deg::cos(lat) * deg::cos(decl));
+    if !(-1_f64..=1_f64).contains(&cos_t) {
+        return None;
+    }
+    let t_a = (1_f64 / 15_f64) * deg::acos(cos_t);
     match direction {
-        HorizonDirection::Sunrise => return zenith - t_a,
-        HorizonDirection::Sunset => return zenith + t_a,
+        HorizonDirection::Sunrise => return Some(zenith - t_a),
+        HorizonDirection::Sunset => return Some(zenith + t_a),
     }
 }
 
+/// Gets the altitude of the sun above the horizon in degrees at an hour, negative when it is below the horizon.
+/// This is the inverse of `horizon_hour_angle_from_decl`, without atmospheric refraction.
+///
+/// ### Arguments
+/// * `hour` - The hour to calculate the altitude at
+/// * `decl` - The declination of the sun in degrees (see `sun_coords`)
+/// * `zenith` - The hour time for when the sun hits the zenith
+/// * `lat` - The latitude value
+pub fn sun_altitude(hour: f64, decl: f64, zenith: f64, lat: f64) -> f64 {
+    let hour_angle = 15_f64 * (hour - zenith);
+    return deg::asin(
+        deg::sin(lat) * deg::sin(decl) + deg::cos(lat) * deg::cos(decl) * deg::cos(hour_angle),
+    );
+}
+
 /// Gets the hour at which the shadow is a specified length of a given object
 /// e.g. length = 1 -> the shadow is the same size as the object
 ///
 /// ### Arguments
-/// * `length` - The relative shadow length
+/// * `length` - The relative shadow length, measured on top of the shadow at noon
 /// * `jd` - The Julian date
 /// * `zenith` - The hour time for when the sun hits the zenith
 /// * `lat` - The latitude value
 pub fn shadow_length_hour(length: f64, jd: f64, zenith: f64, lat: f64) -> f64 {
     let decl = sun_coords(jd).1;
+    return shadow_length_hour_from_decl(length, decl, zenith, lat, true);
+}
+
+/// Gets the hour at which the shadow is a specified length of a given object from an already calculated declination
+///
+/// ### Arguments
+/// * `length` - The relative shadow length
+/// * `decl` - The declination of the sun in degrees (see `sun_coords`)
+/// * `zenith` - The hour time for when the sun hits the zenith
+/// * `lat` - The latitude value
+/// * `noon_shadow` - If `true`, the shadow length is measured on top of the shadow at noon
+///   (shadow = noon shadow + `length`), otherwise the shadow is exactly `length`
+pub fn shadow_length_hour_from_decl(
+    length: f64,
+    decl: f64,
+    zenith: f64,
+    lat: f64,
+    noon_shadow: bool,
+) -> f64 {
+    let baseline = if noon_shadow {
+        deg::tan(lat - decl).abs()
+    } else {
+        0_f64
+    };
     let a_t = (1_f64 / 15_f64)
         * deg::acos(
-            (deg::sin(deg::acot(length + deg::tan(lat - decl))) - (deg::sin(lat) * deg::sin(decl)))
+            (deg::sin(deg::acot(length + baseline)) - (deg::sin(lat) * deg::sin(decl)))
                 / (deg::cos(lat) * deg::cos(decl)),
         );
     return zenith + a_t;
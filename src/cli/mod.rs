@@ -1,41 +1,1444 @@
 use anyhow::{Context, Result};
-use chrono::NaiveDate;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, Timelike, Utc};
 use chrono_tz::Tz;
 use clap::{ArgAction, Parser, Subcommand};
 use colored::*;
-use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
-use serde::Deserialize;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
 use std::io::Write;
+use std::path::{Path, PathBuf};
 
+#[cfg(feature = "network")]
 use crate::api;
+use crate::astro;
+use crate::cities;
 use crate::datetime;
-use crate::times::types;
+use crate::error::SalahError;
+use crate::hijri::Hijri;
+use crate::log;
+use crate::math;
+use crate::qibla;
+use crate::times::{types, CheckedTimes, PrayerTimes};
 
-pub const ALLOWED_TIMES: [&'static str; 8] = [
-    "fajr", "sunrise", "dhuhr", "asr", "maghrib", "isha", "midnight", "fardh",
+#[cfg(test)]
+mod tests {
+    use super::{
+        diff_minutes, parse_latitude, parse_longitude, parse_timezone, parsed_format,
+        render_calendar, render_compare_now, render_csv, render_diff, render_half_night,
+        render_hijri, render_ics, render_json, render_next, render_parameters, render_qibla,
+        render_table, render_tabular, render_timings, render_watch, Locale, Snap, SnapDirection,
+        TimingSort,
+    };
+    use crate::fixtures;
+    use crate::times::{types, PrayerTimes};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_render_timings_with_date_format() {
+        let pt = fixtures::toronto();
+        let timings = [types::Timing::Fajr, types::Timing::Midnight];
+        let rendered = render_timings(
+            &pt,
+            &timings,
+            "%Y-%m-%d %H:%M",
+            &Snap::default(),
+            &Locale::default(),
+        );
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "fajr 2024-02-11 06:03");
+        // midnight belongs to the next day
+        assert!(lines[1].starts_with("midnight 2024-02-12 00:"));
+
+        // time only formats still work
+        let rendered = render_timings(
+            &pt,
+            &timings[..1],
+            "%H:%M:%S",
+            &Snap::default(),
+            &Locale::default(),
+        );
+        assert_eq!(rendered, "fajr 06:03:00");
+    }
+
+    #[test]
+    fn test_render_timings_polar() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let pt = PrayerTimes::new(78.2232, 15.6267)
+            .with_date(&date)
+            .with_offset_hours(2.0);
+        let rendered = render_timings(
+            &pt,
+            &types::Timing::list(),
+            "%H:%M",
+            &Snap::default(),
+            &Locale::default(),
+        );
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[1], "fajr N/A (polar)");
+        assert_eq!(lines[2], "sunrise N/A (polar)");
+        assert!(lines[4].starts_with("dhuhr ") && !lines[4].contains("N/A"));
+        assert_eq!(lines[6], "maghrib N/A (polar)");
+    }
+
+    #[test]
+    fn test_render_outputs_polar() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let pt = PrayerTimes::new(78.2232, 15.6267)
+            .with_date(&date)
+            .with_offset_hours(2.0);
+        let fardh = types::Timing::fardh_list();
+
+        assert_eq!(
+            render_json(&pt, &fardh, true, "%H:%M", &Snap::default()),
+            format!(
+                "{{\"fajr\":null,\"dhuhr\":\"12:59\",\"asr\":\"21:21\",\"maghrib\":null,\"isha\":null,\"half-night\":null,\"meta\":{{\"version\":\"{}\"}}}}",
+                crate::version()
+            )
+        );
+        assert_eq!(
+            render_half_night(&pt, "%H:%M", &Snap::default()),
+            "half-night N/A (polar)"
+        );
+        let csv = render_csv(&pt, date, date, "%H:%M", &Snap::default());
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("2024-06-20,N/A (polar),N/A (polar),12:59,21:21,N/A (polar),N/A (polar),N/A (polar)")
+        );
+        assert!(render_tabular(&pt, "%H:%M").contains("MWL           N/A (polar) N/A (polar)"));
+        let ics = render_ics(&pt, &fardh, 1);
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(!ics.contains("Fajr"));
+    }
+
+    #[test]
+    fn test_render_table() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+        let fardh = types::Timing::fardh_list();
+
+        let now = date.and_hms_opt(14, 30, 0).expect("Error!");
+        assert_eq!(
+            render_table(
+                &pt,
+                &fardh,
+                Some(now),
+                "%H:%M",
+                &Snap::default(),
+                &Locale::default(),
+                false
+            ),
+            [
+                "  fajr     06:03",
+                "  dhuhr    12:32",
+                "> asr      15:57",
+                "  maghrib  17:41",
+                "  isha     19:01",
+            ]
+            .join("\n")
+        );
+
+        // No highlight after the last timing, on other dates or without a current time
+        let plain = render_table(
+            &pt,
+            &fardh,
+            None,
+            "%H:%M",
+            &Snap::default(),
+            &Locale::default(),
+            false,
+        );
+        assert!(!plain.contains('>'));
+        let late = date.and_hms_opt(23, 0, 0).expect("Error!");
+        assert_eq!(
+            render_table(
+                &pt,
+                &fardh,
+                Some(late),
+                "%H:%M",
+                &Snap::default(),
+                &Locale::default(),
+                false
+            ),
+            plain
+        );
+        let other_day = now + chrono::Duration::days(1);
+        assert_eq!(
+            render_table(
+                &pt,
+                &fardh,
+                Some(other_day),
+                "%H:%M",
+                &Snap::default(),
+                &Locale::default(),
+                false
+            ),
+            plain
+        );
+
+        // Columns are as wide as the longest requested timing
+        let rendered = render_table(
+            &pt,
+            &[types::Timing::LastThird, types::Timing::Fajr],
+            None,
+            "%H:%M",
+            &Snap::default(),
+            &Locale::default(),
+            false,
+        );
+        assert_eq!(rendered, "  last-third  02:49\n  fajr        06:03");
+    }
+
+    #[test]
+    fn test_render_watch() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+
+        // Asr is at 15:57
+        let now = date.and_hms_opt(14, 30, 15).expect("Error!");
+        let rendered = render_watch(&pt, now, "%H:%M");
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "2024-02-11 14:30:15");
+        assert!(lines[4].starts_with("> ") && lines[4].contains("15:57"));
+        assert!(lines[2].starts_with("  ") && lines[2].contains("06:03"));
+        assert!(rendered.contains("01:26:45"));
+        assert!(rendered.ends_with("until asr"));
+
+        // After isha the countdown is to the next day's fajr
+        let now = date.and_hms_opt(23, 0, 0).expect("Error!");
+        let rendered = render_watch(&pt, now, "%H:%M");
+        assert!(rendered.ends_with("until fajr (tomorrow)"));
+        assert!(!rendered.contains("> "));
+
+        // Midnight sun in Longyearbyen (78°N), the timings with no solution are N/A and skipped by the countdown
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let polar = PrayerTimes::new(78.2232, 15.6267)
+            .with_date(&solstice)
+            .with_offset_hours(2.0)
+            .with_authority(&types::Authority::MWL);
+        let now = solstice.and_hms_opt(10, 0, 0).expect("Error!");
+        let rendered = render_watch(&polar, now, "%H:%M");
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[2], "  fajr    N/A (polar)");
+        assert!(lines[3].starts_with("> ") && lines[3].contains("dhuhr"));
+        assert_eq!(lines[6], "  isha    N/A (polar)");
+        assert!(rendered.ends_with("until dhuhr"));
+
+        let now = solstice.and_hms_opt(23, 0, 0).expect("Error!");
+        let rendered = render_watch(&polar, now, "%H:%M");
+        assert!(rendered.ends_with("until dhuhr (tomorrow)"));
+    }
+
+    #[test]
+    fn test_render_next() {
+        use crate::datetime::FixedClock;
+        use chrono::TimeZone;
+
+        let tz: chrono_tz::Tz = "America/Toronto".parse().expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347)
+            .with_timezone(&tz)
+            .with_school(&types::School::Shafi);
+        let clock = |h, m| {
+            let local = NaiveDate::from_ymd_opt(2024, 2, 11)
+                .expect("Error!")
+                .and_hms_opt(h, m, 0)
+                .expect("Error!");
+            return FixedClock(
+                tz.from_local_datetime(&local)
+                    .single()
+                    .expect("Error!")
+                    .to_utc(),
+            );
+        };
+
+        // Maghrib is at 17:41
+        assert_eq!(
+            render_next(&pt, &tz, &clock(16, 18), "%H:%M"),
+            "Maghrib in 1h 23m (17:41)"
+        );
+        assert_eq!(
+            render_next(&pt, &tz, &clock(17, 30), "%H:%M"),
+            "Maghrib in 11m (17:41)"
+        );
+
+        // After isha, tomorrow's fajr
+        let next = render_next(&pt, &tz, &clock(23, 0), "%Y-%m-%d %H:%M");
+        assert!(next.starts_with("Fajr in 7h "), "{}", next);
+        assert!(next.ends_with("(2024-02-12 06:01)"), "{}", next);
+    }
+
+    #[test]
+    fn test_render_compare_now() {
+        use crate::datetime::FixedClock;
+        use chrono::TimeZone;
+
+        let tz: chrono_tz::Tz = "America/Toronto".parse().expect("Error!");
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347)
+            .with_date(&date)
+            .with_timezone(&tz);
+        let local = date.and_hms_opt(14, 0, 0).expect("Error!");
+        let clock = FixedClock(
+            tz.from_local_datetime(&local)
+                .single()
+                .expect("Error!")
+                .to_utc(),
+        );
+        let timings = [
+            types::Timing::Fajr,
+            types::Timing::Dhuhr,
+            types::Timing::Asr,
+            types::Timing::Isha,
+        ];
+        let rendered = render_compare_now(
+            &pt,
+            &timings,
+            &tz,
+            &clock,
+            "%H:%M",
+            &Snap::default(),
+            &Locale::default(),
+        );
+        assert_eq!(
+            rendered.lines().collect::<Vec<&str>>(),
+            [
+                "fajr 06:03 (7h 57m ago)",
+                "dhuhr 12:32 (1h 28m ago)",
+                "asr 15:57 (in 1h 57m)",
+                "isha 19:01 (in 5h 1m)"
+            ]
+        );
+
+        // Not today, so there is nothing to compare to
+        let tomorrow = pt.clone().with_date(&date.succ_opt().expect("Error!"));
+        let rendered = render_compare_now(
+            &tomorrow,
+            &timings,
+            &tz,
+            &clock,
+            "%H:%M",
+            &Snap::default(),
+            &Locale::default(),
+        );
+        assert!(!rendered.contains("ago") && !rendered.contains("in "));
+    }
+
+    #[test]
+    fn test_parse_timezone_suggestions() {
+        use super::CommonConfig;
+        use clap::Parser;
+
+        let config = CommonConfig::parse_from(["salah", "--timezone", "america/toronto"]);
+        let err = config.parsed_timezone().expect_err("Error!");
+        assert_eq!(
+            err.to_string(),
+            "timezone = `america/toronto` is not valid! Did you mean `America/Toronto`?"
+        );
+
+        let err = parse_timezone("Nowhere/Special").expect_err("Error!");
+        assert!(err
+            .to_string()
+            .ends_with("See `salah timezones` for the valid values."));
+        assert_eq!(
+            parse_timezone("Asia/Karachi").expect("Error!").name(),
+            "Asia/Karachi"
+        );
+    }
+
+    #[test]
+    fn test_twelve_hour() {
+        use super::CommonConfig;
+        use clap::Parser;
+
+        let config = CommonConfig::parse_from(["salah", "--12h", "--date", "2024-02-11"]);
+        let format = match config
+            .calculation(43.6534817, -79.3839347, None)
+            .expect("Error!")
+        {
+            super::ParsedOptions::Calculation { format, .. } => format,
+            _ => panic!("Expected calculation options"),
+        };
+        assert_eq!(format, super::TWELVE_HOUR_FORMAT);
+
+        let pt = fixtures::toronto();
+        let rendered = render_timings(
+            &pt,
+            &[types::Timing::Fajr, types::Timing::Asr],
+            &format,
+            &Snap::default(),
+            &Locale::default(),
+        );
+        assert_eq!(rendered, "fajr 06:03 AM\nasr 03:57 PM");
+
+        // An explicit --format conflicts with --12h
+        assert!(CommonConfig::try_parse_from(["salah", "--12h", "--format", "%H:%M"]).is_err());
+    }
+
+    #[test]
+    fn test_parsed_school() {
+        use super::CommonConfig;
+        use clap::Parser;
+
+        let parsed = |args: &[&str]| CommonConfig::parse_from(args).parsed_school();
+        assert_eq!(parsed(&["salah"]).expect("Error!"), types::School::Shafi);
+        assert_eq!(
+            parsed(&["salah", "--hanafi"]).expect("Error!"),
+            types::School::Hanafi
+        );
+        assert_eq!(
+            parsed(&["salah", "--madhab", "Hanafi"]).expect("Error!"),
+            types::School::Hanafi
+        );
+        assert_eq!(
+            parsed(&["salah", "--madhab", "maliki"]).expect("Error!"),
+            types::School::Maliki
+        );
+        assert!(parsed(&["salah", "--madhab", "zahiri"]).is_err());
+        assert!(CommonConfig::try_parse_from(["salah", "--hanafi", "--madhab", "shafi"]).is_err());
+    }
+
+    #[test]
+    fn test_parsed_adjustments() {
+        use super::CommonConfig;
+        use clap::Parser;
+
+        let config =
+            CommonConfig::parse_from(["salah", "--adjust-fajr", "2", "--adjust-isha", "-3"]);
+        assert_eq!(
+            config.parsed_adjustments(),
+            types::Adjustments {
+                fajr: 2,
+                isha: -3,
+                ..Default::default()
+            }
+        );
+        let config = CommonConfig::parse_from(["salah"]);
+        assert_eq!(config.parsed_adjustments(), types::Adjustments::default());
+    }
+
+    #[test]
+    fn test_parsed_auth_custom() {
+        use super::CommonConfig;
+        use clap::Parser;
+        use types::{Authority, IshaParam};
+
+        let config =
+            CommonConfig::parse_from(["salah", "--fajr-angle", "16.5", "--isha-angle", "15"]);
+        assert_eq!(
+            config.parsed_auth().expect("Error!"),
+            Authority::Custom {
+                fajr_angle: 16.5,
+                isha: IshaParam::Angle(15.0)
+            }
+        );
+
+        // Overriding only one angle keeps the other from --auth
+        let config = CommonConfig::parse_from(["salah", "--auth", "Makkah", "--fajr-angle", "19"]);
+        assert_eq!(
+            config.parsed_auth().expect("Error!"),
+            Authority::Custom {
+                fajr_angle: 19.0,
+                isha: IshaParam::Duration(std::time::Duration::from_secs(90 * 60))
+            }
+        );
+
+        // Angles matching a named authority resolve to it
+        let config =
+            CommonConfig::parse_from(["salah", "--fajr-angle", "18", "--isha-angle", "17"]);
+        assert_eq!(config.parsed_auth().expect("Error!"), Authority::MWL);
+
+        let config = CommonConfig::parse_from([
+            "salah",
+            "--auth",
+            "custom",
+            "--fajr-angle",
+            "18.5",
+            "--isha-interval",
+            "75",
+        ]);
+        assert_eq!(
+            config.parsed_auth().expect("Error!"),
+            Authority::Custom {
+                fajr_angle: 18.5,
+                isha: IshaParam::Duration(std::time::Duration::from_secs(75 * 60))
+            }
+        );
+
+        assert!(
+            CommonConfig::parse_from(["salah", "--auth", "custom", "--fajr-angle", "18"])
+                .parsed_auth()
+                .is_err()
+        );
+        assert!(CommonConfig::parse_from(["salah", "--fajr-angle", "95"])
+            .parsed_auth()
+            .is_err());
+        assert!(CommonConfig::try_parse_from([
+            "salah",
+            "--isha-angle",
+            "17",
+            "--isha-interval",
+            "90"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_dhuhr_offset_range() {
+        use super::{minutes_duration, CommonConfig};
+        use clap::Parser;
+
+        let config = CommonConfig::parse_from(["salah", "--dhuhr-offset", "2"]);
+        assert_eq!(config.dhuhr_offset, 2);
+        assert!(CommonConfig::try_parse_from(["salah", "--dhuhr-offset", "61"]).is_err());
+        assert!(
+            CommonConfig::try_parse_from(["salah", "--dhuhr-offset", "307445734561825861"])
+                .is_err()
+        );
+
+        assert_eq!(
+            minutes_duration(2).expect("Error!"),
+            std::time::Duration::from_secs(120)
+        );
+        assert!(minutes_duration(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_parsed_isha_interval() {
+        use super::CommonConfig;
+        use clap::Parser;
+
+        // Keeps the named authority, replacing only its isha
+        let config = CommonConfig::parse_from(["salah", "--auth", "MWL", "--isha-interval", "75"]);
+        assert_eq!(config.parsed_auth().expect("Error!"), types::Authority::MWL);
+        assert_eq!(
+            config.parsed_isha_interval().expect("Error!"),
+            Some(std::time::Duration::from_secs(75 * 60))
+        );
+        assert_eq!(
+            CommonConfig::parse_from(["salah"])
+                .parsed_isha_interval()
+                .expect("Error!"),
+            None
+        );
+
+        // Isha can't be at maghrib, nor overflow when converted to seconds
+        for minutes in ["0", "241", "307445734561825861"] {
+            assert!(
+                CommonConfig::try_parse_from(["salah", "--isha-interval", minutes]).is_err(),
+                "{}",
+                minutes
+            );
+        }
+    }
+
+    #[test]
+    fn test_parsed_twilight() {
+        use super::CommonConfig;
+        use clap::Parser;
+
+        let twilight = CommonConfig::parse_from([
+            "salah",
+            "--fajr-twilight",
+            "astronomical",
+            "--isha-twilight",
+            "Nautical",
+        ]);
+        let angles = CommonConfig::parse_from([
+            "salah",
+            "--fajr-angle-override",
+            "18",
+            "--isha-angle-override",
+            "12",
+        ]);
+        assert_eq!(
+            twilight.parsed_angle_overrides().expect("Error!"),
+            angles.parsed_angle_overrides().expect("Error!")
+        );
+        assert_eq!(
+            twilight.parsed_angle_overrides().expect("Error!"),
+            (Some(18_f64), Some(12_f64))
+        );
+
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347).with_date(&date);
+        assert_eq!(
+            pt.clone()
+                .with_fajr_angle_override(Some(types::Twilight::Astronomical.angle()))
+                .fajr(),
+            pt.with_fajr_angle_override(Some(18_f64)).fajr()
+        );
+
+        let invalid = CommonConfig::parse_from(["salah", "--fajr-twilight", "golden"]);
+        assert!(invalid.parsed_angle_overrides().is_err());
+        assert!(CommonConfig::try_parse_from([
+            "salah",
+            "--fajr-twilight",
+            "civil",
+            "--fajr-angle-override",
+            "15"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parsed_timezone_auto() {
+        use super::CommonConfig;
+        use clap::Parser;
+
+        // `auto` is only valid once it has been looked up, it is never guessed
+        let config = CommonConfig::parse_from(["salah", "--timezone", "auto"]);
+        assert!(config.parsed_timezone().is_err());
+        assert!(config.calculation(33.6844, 73.0479, None).is_err());
+
+        let config = CommonConfig::parse_from(["salah", "--timezone", "Asia/Riyadh"]);
+        let tz = config.parsed_timezone().expect("Error!");
+        assert_eq!(tz.name(), "Asia/Riyadh");
+    }
+
+    #[test]
+    fn test_parsed_angle_overrides() {
+        use super::CommonConfig;
+        use clap::Parser;
+
+        let config =
+            CommonConfig::parse_from(["salah", "--auth", "ISNA", "--fajr-angle-override", "18"]);
+        assert_eq!(
+            config.parsed_auth().expect("Error!"),
+            types::Authority::ISNA
+        );
+        assert_eq!(
+            config.parsed_angle_overrides().expect("Error!"),
+            (Some(18_f64), None)
+        );
+        assert!(
+            CommonConfig::parse_from(["salah", "--isha-angle-override", "91"])
+                .parsed_angle_overrides()
+                .is_err()
+        );
+        assert!(CommonConfig::try_parse_from([
+            "salah",
+            "--fajr-angle",
+            "18",
+            "--fajr-angle-override",
+            "18"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parsed_timings_fardh() {
+        use super::CommonConfig;
+        use clap::Parser;
+        use types::Timing;
+
+        let config = CommonConfig::parse_from(["salah", "fardh"]);
+        assert_eq!(
+            config.parsed_timings().expect("Error!"),
+            Timing::fardh_list().to_vec()
+        );
+
+        // Composes with other timings without duplicates
+        let config = CommonConfig::parse_from(["salah", "sunrise", "fajr", "FARDH", "midnight"]);
+        assert_eq!(
+            config.parsed_timings().expect("Error!"),
+            vec![
+                Timing::Sunrise,
+                Timing::Fajr,
+                Timing::Dhuhr,
+                Timing::Asr,
+                Timing::Maghrib,
+                Timing::Isha,
+                Timing::Midnight
+            ]
+        );
+
+        // --all still overrides everything
+        let config = CommonConfig::parse_from(["salah", "fardh", "--all"]);
+        assert_eq!(
+            config.parsed_timings().expect("Error!"),
+            Timing::list().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_parse_coordinates() {
+        assert_eq!(parse_longitude("78.9W").expect("Error!"), -78.9);
+        assert_eq!(parse_longitude("78.9 w").expect("Error!"), -78.9);
+        assert_eq!(parse_longitude("39.8E").expect("Error!"), 39.8);
+        assert_eq!(parse_latitude("43.8S").expect("Error!"), -43.8);
+        assert_eq!(parse_latitude("43.8 N").expect("Error!"), 43.8);
+        assert_eq!(parse_latitude("-43.8").expect("Error!"), -43.8);
+
+        // Contradictory or mismatched inputs
+        assert!(parse_latitude("-43.8N").is_err());
+        assert!(parse_latitude("+43.8S").is_err());
+        assert!(parse_latitude("43.8W").is_err());
+        assert!(parse_longitude("78.9N").is_err());
+        assert!(parse_longitude("W").is_err());
+    }
+
+    #[test]
+    fn test_parsed_format() {
+        assert!(parsed_format("%Y-%m-%d %H:%M").is_ok());
+        assert!(parsed_format("%I:%M %p").is_ok());
+        assert!(parsed_format("%H:%Q").is_err());
+    }
+
+    #[test]
+    fn test_render_json() {
+        let pt = fixtures::toronto();
+        let timings = [types::Timing::Fajr, types::Timing::Asr];
+        assert_eq!(
+            render_json(&pt, &timings, false, "%H:%M:%S", &Snap::default()),
+            format!(
+                r#"{{"fajr":"06:03:00","asr":"15:57:00","meta":{{"version":"{}"}}}}"#,
+                crate::version()
+            )
+        );
+
+        let json = render_json(&pt, &timings, true, "%I:%M %p", &Snap::default());
+        let parsed: serde_json::Value = serde_json::from_str(&json).expect("Error!");
+        assert_eq!(parsed["fajr"], "06:03 AM");
+        assert!(parsed["half-night"].is_string());
+    }
+
+    #[test]
+    fn test_render_ics() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let tz: chrono_tz::Tz = "America/Toronto".parse().expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347)
+            .with_date(&date)
+            .with_timezone(&tz);
+        let ics = render_ics(&pt, &[], 30);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 30 * 5);
+        assert_eq!(ics.matches("END:VEVENT").count(), 30 * 5);
+        assert!(ics.contains("UID:20240211-fajr@salah\r\n"));
+        // 06:03 EST and, after the change to daylight saving time, 06:18 EDT
+        assert!(ics.contains("DTSTART:20240211T110300Z\r\n"));
+        assert!(ics.contains("DTSTART:20240311T101800Z\r\n"));
+        assert!(!ics.contains("TZID"));
+        assert!(ics.contains("SUMMARY:Fajr\r\n"));
+        assert!(ics.contains("UID:20240311-isha@salah\r\n"));
+        assert!(!ics.contains("UID:20240312"));
+        assert!(!ics.contains("SUMMARY:Sunrise"));
+
+        let sunrise = render_ics(&pt, &[types::Timing::Sunrise], 2);
+        assert_eq!(sunrise.matches("SUMMARY:Sunrise").count(), 2);
+    }
+
+    #[test]
+    fn test_render_calendar() {
+        let date = NaiveDate::from_ymd_opt(2024, 4, 1).expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347).with_offset_hours(-4.0);
+        let calendar = render_calendar(&pt, date, "%H:%M", &Snap::default());
+        let lines: Vec<&str> = calendar.lines().collect();
+
+        // Header and one row per day of April
+        assert_eq!(lines.len(), 1 + 30);
+        assert!(lines[0].starts_with("Date"));
+        for name in ["Fajr", "Dhuhr", "Asr", "Maghrib", "Isha"] {
+            assert!(lines[0].contains(name), "{}", name);
+        }
+        assert!(lines[1].starts_with("2024-04-01"));
+        assert!(lines[30].starts_with("2024-04-30"));
+
+        // Columns are aligned
+        let dhuhr = lines[0].find("Dhuhr").expect("Error!");
+        assert!(lines.iter().skip(1).all(|l| l[dhuhr..].starts_with("13:")));
+
+        // February of a leap year
+        let date = NaiveDate::from_ymd_opt(2024, 2, 1).expect("Error!");
+        assert_eq!(
+            render_calendar(&pt, date, "%H:%M", &Snap::default())
+                .lines()
+                .count(),
+            1 + 29
+        );
+    }
+
+    #[test]
+    fn test_render_csv() {
+        use super::CommonConfig;
+        use clap::Parser;
+
+        let start = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let end = NaiveDate::from_ymd_opt(2024, 2, 17).expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347).with_offset_hours(-5.0);
+        let csv = render_csv(&pt, start, end, "%H:%M", &Snap::default());
+        let rows: Vec<Vec<&str>> = csv.lines().map(|l| l.split(',').collect()).collect();
+
+        assert_eq!(
+            rows[0],
+            vec!["date", "fajr", "sunrise", "dhuhr", "asr", "maghrib", "isha", "midnight"]
+        );
+        assert_eq!(rows.len(), 1 + 7);
+        assert!(rows.iter().all(|row| row.len() == 8));
+        assert_eq!(rows[1][0], "2024-02-11");
+        assert_eq!(rows[1][1], "06:03");
+        assert_eq!(rows[7][0], "2024-02-17");
+
+        // A single date and formats with commas
+        let csv = render_csv(&pt, start, start, "%a, %H:%M", &Snap::default());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("2024-02-11,\"Sun, 06:03\","));
+        // Midnight belongs to the next day
+        assert!(lines[1].contains(",\"Mon, 00:"));
+
+        // Only one output mode at a time
+        assert!(CommonConfig::try_parse_from(["salah", "--csv", "--json"]).is_err());
+        assert!(CommonConfig::try_parse_from(["salah", "--csv", "--ics"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_coords_file() {
+        use super::{parse_coords_file, CoordsEntry};
+
+        let entries =
+            parse_coords_file("name,lat,lng\nToronto,43.6534817,-79.3839347\n\n# comment\nMakkah\t21.4225N\t39.8262E\n")
+                .expect("Error!");
+        assert_eq!(
+            entries,
+            [
+                CoordsEntry {
+                    name: String::from("Toronto"),
+                    lat: 43.6534817,
+                    lng: -79.3839347
+                },
+                CoordsEntry {
+                    name: String::from("Makkah"),
+                    lat: 21.4225,
+                    lng: 39.8262
+                }
+            ]
+        );
+
+        // Errors point at the malformed line
+        let err = parse_coords_file("Toronto,43.65,-79.38\nWhitby,43.87\n").unwrap_err();
+        assert!(format!("{:#}", err).starts_with("line 2:"));
+        let err = parse_coords_file("Toronto,43.65,-79.38\n\nWhitby,north,-78.94\n").unwrap_err();
+        assert!(format!("{:#}", err).starts_with("line 3:"));
+        let err = parse_coords_file("Nowhere,95,0\n").unwrap_err();
+        assert!(format!("{:#}", err).starts_with("line 1:"));
+        let err = parse_coords_file("Nowhere,0,360\n").unwrap_err();
+        assert!(format!("{:#}", err).starts_with("line 1:"));
+        // Longitudes in [0, 360) are accepted like --lng, and wrapped when calculated
+        let entries = parse_coords_file("Honolulu,21.3069,202.1417\n").expect("Error!");
+        assert_eq!(entries[0].lng, 202.1417);
+        assert!(parse_coords_file("name,lat,lng\n").is_err());
+    }
+
+    #[test]
+    fn test_write_output() {
+        let dir = std::env::temp_dir().join(format!("salah-test-output-{}", std::process::id()));
+        let path = dir.join("nested").join("times.txt");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        super::write_output(Some(&path), "fajr 06:03:00\n").expect("Error!");
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("Error!"),
+            "fajr 06:03:00\n"
+        );
+
+        // Existing files are overwritten
+        super::write_output(Some(&path), "isha 19:01:00\n").expect("Error!");
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("Error!"),
+            "isha 19:01:00\n"
+        );
+
+        // A directory can't be written to
+        assert!(super::write_output(Some(&dir), "").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_select_match() {
+        use super::{render_matches, select_match};
+        use crate::api::GeocodeMatch;
+
+        let matches = vec![
+            GeocodeMatch {
+                display_name: String::from("Springfield, Sangamon County, Illinois, United States"),
+                lat: 39.7990175,
+                lng: -89.6439575,
+            },
+            GeocodeMatch {
+                display_name: String::from("Springfield, Greene County, Missouri, United States"),
+                lat: 37.2081729,
+                lng: -93.2922715,
+            },
+        ];
+        assert_eq!(
+            render_matches(&matches),
+            "1. Springfield, Sangamon County, Illinois, United States (39.7990, -89.6440)\n2. Springfield, Greene County, Missouri, United States (37.2082, -93.2923)"
+        );
+        assert_eq!(select_match(&matches, 2).expect("Error!"), &matches[1]);
+        assert!(select_match(&matches, 0).is_err());
+        assert!(select_match(&matches, 3).is_err());
+    }
+
+    #[test]
+    fn test_locale() {
+        let pt = fixtures::toronto();
+        let timings = [types::Timing::Fajr, types::Timing::Dhuhr];
+        let render = |locale: Locale| {
+            return render_timings(&pt, &timings, "%H:%M", &Snap::default(), &locale);
+        };
+        assert_eq!(render(Locale::En), "fajr 06:03\ndhuhr 12:32");
+        assert_eq!(render(Locale::Ar), "الفجر 06:03\nالظهر 12:32");
+        assert_eq!(render(Locale::Tr), "sabah 06:03\nöğle 12:32");
+
+        // Columns are aligned by characters, not bytes
+        let table = render_table(
+            &pt,
+            &timings,
+            None,
+            "%H:%M",
+            &Snap::default(),
+            &Locale::Tr,
+            false,
+        );
+        assert_eq!(table, "  sabah  06:03\n  öğle   12:32");
+
+        for locale in Locale::list() {
+            assert_eq!(Locale::from_str(locale.to_str()).expect("Error!"), locale);
+            for timing in types::Timing::list() {
+                assert!(!locale.label(&timing).is_empty());
+            }
+        }
+        assert!(Locale::from_str("fr").is_err());
+    }
+
+    #[test]
+    fn test_timing_sort() {
+        let pt = fixtures::toronto();
+        let timings = [
+            types::Timing::Midnight,
+            types::Timing::Isha,
+            types::Timing::Asr,
+            types::Timing::Sunrise,
+        ];
+        assert_eq!(
+            TimingSort::Time.apply(&pt, &timings),
+            [
+                types::Timing::Sunrise,
+                types::Timing::Asr,
+                types::Timing::Isha,
+                types::Timing::Midnight
+            ]
+        );
+        assert_eq!(
+            TimingSort::Name.apply(&pt, &timings),
+            [
+                types::Timing::Asr,
+                types::Timing::Isha,
+                types::Timing::Midnight,
+                types::Timing::Sunrise
+            ]
+        );
+        assert_eq!(TimingSort::Input.apply(&pt, &timings), timings);
+        for sort in TimingSort::list() {
+            assert_eq!(TimingSort::from_str(sort.to_str()).expect("Error!"), sort);
+        }
+        assert!(TimingSort::from_str("random").is_err());
+    }
+
+    #[test]
+    fn test_snap() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let at = |h: u32, m: u32, s: u32| date.and_hms_opt(h, m, s).expect("Error!");
+        let snap = |minutes: u32, direction: SnapDirection| Snap { minutes, direction };
+
+        assert_eq!(
+            snap(5, SnapDirection::Up).apply_naive(at(5, 12, 0)),
+            at(5, 15, 0)
+        );
+        assert_eq!(
+            snap(5, SnapDirection::Down).apply_naive(at(5, 12, 0)),
+            at(5, 10, 0)
+        );
+        assert_eq!(
+            snap(5, SnapDirection::Nearest).apply_naive(at(5, 12, 0)),
+            at(5, 10, 0)
+        );
+        assert_eq!(
+            snap(5, SnapDirection::Nearest).apply_naive(at(5, 12, 30)),
+            at(5, 15, 0)
+        );
+        // Times on the grid stay, seconds past it round up
+        assert_eq!(
+            snap(5, SnapDirection::Up).apply_naive(at(5, 15, 0)),
+            at(5, 15, 0)
+        );
+        assert_eq!(
+            snap(5, SnapDirection::Up).apply_naive(at(5, 15, 1)),
+            at(5, 20, 0)
+        );
+        assert_eq!(Snap::default().apply_naive(at(5, 12, 34)), at(5, 12, 34));
+        // Up from just before midnight is the next day
+        assert_eq!(
+            snap(15, SnapDirection::Up).apply_naive(at(23, 52, 0)),
+            NaiveDate::from_ymd_opt(2024, 2, 12)
+                .expect("Error!")
+                .and_hms_opt(0, 0, 0)
+                .expect("Error!")
+        );
+
+        for direction in SnapDirection::list() {
+            assert_eq!(
+                SnapDirection::from_str(direction.to_str()).expect("Error!"),
+                direction
+            );
+        }
+        assert!(SnapDirection::from_str("sideways").is_err());
+
+        let pt = fixtures::toronto();
+        let timings = [types::Timing::Fajr, types::Timing::Isha];
+        assert_eq!(
+            render_timings(
+                &pt,
+                &timings,
+                "%H:%M",
+                &snap(5, SnapDirection::Up),
+                &Locale::default()
+            ),
+            "fajr 06:05\nisha 19:05"
+        );
+        assert_eq!(
+            render_timings(
+                &pt,
+                &timings,
+                "%H:%M",
+                &snap(5, SnapDirection::Down),
+                &Locale::default()
+            ),
+            "fajr 06:00\nisha 19:00"
+        );
+    }
+
+    #[test]
+    fn test_render_parameters() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347).with_date(&date);
+        assert_eq!(
+            render_parameters(&pt),
+            "authority ISNA\nfajr-angle 15°\nisha-angle 15°\nmadhab hanafi (shadow factor 2)\nhigh-latitude none\noffsets none"
+        );
+
+        let makkah = pt
+            .with_authority(&types::Authority::Makkah)
+            .with_fajr_angle_override(Some(18.0))
+            .with_night_fraction_clamp(Some(1_f64 / 7_f64))
+            .with_adjustments(&types::Adjustments {
+                isha: -3,
+                ..Default::default()
+            });
+        let rendered = render_parameters(&makkah);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[1], "fajr-angle 18°");
+        assert_eq!(lines[2], "isha-interval 90 min after maghrib");
+        assert_eq!(lines[4], "high-latitude 0.143 of the night");
+        assert_eq!(lines[5], "offsets isha -3 min");
+    }
+
+    #[test]
+    fn test_render_hijri() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 11).expect("Error!");
+        assert_eq!(render_hijri(date), "hijri 1 Ramadan 1445 AH");
+    }
+
+    #[test]
+    fn test_render_qibla() {
+        assert_eq!(render_qibla(40.7128, -74.0060), "qibla 58.48°");
+        assert_eq!(render_qibla(51.5074, -0.1278), "qibla 118.99°");
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+        let mwl = pt
+            .clone()
+            .with_authority(&types::Authority::MWL)
+            .all_checked();
+        let isna = pt
+            .clone()
+            .with_authority(&types::Authority::ISNA)
+            .all_checked();
+        let diffs = diff_minutes(date, &mwl, &isna);
+        let diff = |timing: types::Timing| {
+            return diffs
+                .iter()
+                .find(|(t, _)| *t == timing)
+                .and_then(|(_, minutes)| *minutes)
+                .expect("Error!");
+        };
+        // MWL uses 18° for both, ISNA 15°: fajr is later and isha earlier with ISNA
+        assert!(diff(types::Timing::Fajr) > 0);
+        assert!(diff(types::Timing::Isha) < 0);
+        assert_eq!(diff(types::Timing::Dhuhr), 0);
+        assert_eq!(diff(types::Timing::Asr), 0);
+        assert_eq!(diff(types::Timing::Maghrib), 0);
+
+        let table = render_diff(
+            &pt,
+            &types::Authority::MWL,
+            &types::Authority::ISNA,
+            "%H:%M",
+        );
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), types::Timing::list().len() + 1);
+        assert!(lines[0].starts_with("Timing") && lines[0].contains("MWL"));
+        let dhuhr = lines
+            .iter()
+            .find(|line| line.starts_with("dhuhr "))
+            .expect("Error!");
+        assert!(dhuhr.ends_with(" 0"));
+        let fajr = lines
+            .iter()
+            .find(|line| line.starts_with("fajr "))
+            .expect("Error!");
+        assert!(fajr.contains("06:03") && fajr.ends_with(" min"));
+
+        // Midnight sun in Longyearbyen, fajr has no solution under either authority
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let polar = PrayerTimes::new(78.2232, 15.6267)
+            .with_date(&solstice)
+            .with_offset_hours(2.0);
+        let table = render_diff(
+            &polar,
+            &types::Authority::MWL,
+            &types::Authority::ISNA,
+            "%H:%M",
+        );
+        let fajr = table
+            .lines()
+            .find(|line| line.starts_with("fajr "))
+            .expect("Error!");
+        assert_eq!(fajr.matches("N/A (polar)").count(), 3);
+        let dhuhr = table
+            .lines()
+            .find(|line| line.starts_with("dhuhr "))
+            .expect("Error!");
+        assert!(dhuhr.ends_with(" 0"));
+    }
+
+    #[test]
+    fn test_render_tabular() {
+        let pt = fixtures::toronto();
+        let table = render_tabular(&pt, "%H:%M");
+        let lines: Vec<&str> = table.lines().collect();
+
+        for auth in types::Authority::list() {
+            let rows = lines
+                .iter()
+                .filter(|l| l.starts_with(&format!("{} ", auth.to_str())))
+                .count();
+            assert_eq!(rows, 1, "row for {}", auth.to_str());
+        }
+
+        let footnote = lines
+            .iter()
+            .find(|l| l.starts_with("* Asr"))
+            .expect("Error!");
+        assert!(footnote.contains("Hanafi"));
+        assert!(footnote.contains("Shafi"));
+        assert!(footnote.contains("15:57"));
+    }
+}
+
+pub const ALLOWED_TIMES: [&str; 11] = [
+    "imsak",
+    "fajr",
+    "sunrise",
+    "duha",
+    "dhuhr",
+    "asr",
+    "maghrib",
+    "isha",
+    "midnight",
+    "last-third",
+    "fardh",
 ];
 
-pub const TIMES_DESC: [&'static str; 8] = [
+pub const TIMES_DESC: [&str; 11] = [
+    "The time to stop eating before a fast.",
     "The dawn prayer time.",
     "Sunrise time. Fajr ends at sunrise",
+    "The forenoon prayer time. 20 minutes after sunrise",
     "The mid-day prayer time.",
     "The evening prayer time.",
     "The sunset prayer time.",
     "The night prayer time.",
     "Islamic midnight time. Isha ends at midnight",
+    "Start of the last third of the night. Preferred time for Tahajjud",
     "Gets only the 5 obligatory (fardh) prayer times. Ignores any others",
 ];
 
+pub const DEFAULT_AUTHORITY: &str = "ISNA";
+
+pub const DEFAULT_TIMEZONE: &str = "America/Toronto";
+
+/// `--timezone` value to detect the timezone from the coordinates
+pub const AUTO_TIMEZONE: &str = "auto";
+
+pub const TWELVE_HOUR_FORMAT: &str = "%I:%M %p";
+
+/// Printed instead of a time when a timing has no solution (e.g. the midnight sun)
+pub const POLAR_NA: &str = "N/A (polar)";
+
+/// Direction printed times are snapped to the `--round` grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapDirection {
+    Up,
+    Down,
+    #[default]
+    Nearest,
+}
+
+impl SnapDirection {
+    pub fn from_str(name: &str) -> Result<Self> {
+        return match name.to_lowercase().as_str() {
+            "up" => Ok(Self::Up),
+            "down" => Ok(Self::Down),
+            "nearest" => Ok(Self::Nearest),
+            _ => Err(anyhow::anyhow!(
+                "round direction = `{}` is not one of up, down or nearest!",
+                name
+            )),
+        };
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Nearest => "nearest",
+        }
+    }
+
+    pub fn list() -> [Self; 3] {
+        return [Self::Up, Self::Down, Self::Nearest];
+    }
+}
+
+/// Order the requested timings are displayed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimingSort {
+    /// Chronological, with timings past midnight last and timings with no solution at the end
+    #[default]
+    Time,
+    /// Alphabetical by name
+    Name,
+    /// As given on the command line
+    Input,
+}
+
+impl TimingSort {
+    pub fn from_str(name: &str) -> Result<Self> {
+        return match name.to_lowercase().as_str() {
+            "time" => Ok(Self::Time),
+            "name" => Ok(Self::Name),
+            "input" => Ok(Self::Input),
+            _ => Err(anyhow::anyhow!(
+                "sort = `{}` is not one of time, name or input!",
+                name
+            )),
+        };
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Time => "time",
+            Self::Name => "name",
+            Self::Input => "input",
+        }
+    }
+
+    pub fn list() -> [Self; 3] {
+        return [Self::Time, Self::Name, Self::Input];
+    }
+
+    /// Returns the timings in this order
+    ///
+    /// ### Arguments
+    /// * `pt` - The prayer times the timings are ordered by for `Time`
+    /// * `timings` - The requested timings, in command line order
+    pub fn apply(&self, pt: &PrayerTimes, timings: &[types::Timing]) -> Vec<types::Timing> {
+        let mut sorted: Vec<types::Timing> = timings.to_vec();
+        match self {
+            Self::Time => {
+                let chronological: Vec<types::Timing> =
+                    pt.iter().map(|(timing, _)| timing).collect();
+                // Timings with no solution are not yielded by `iter` and keep their order at the end
+                sorted.sort_by_key(|timing| {
+                    chronological
+                        .iter()
+                        .position(|t| t == timing)
+                        .unwrap_or(chronological.len())
+                });
+            }
+            Self::Name => sorted.sort_by_key(|timing| timing.to_str().to_string()),
+            Self::Input => {}
+        }
+        return sorted;
+    }
+}
+
+/// Language the timing names are displayed in. Display only: timings are always parsed by their canonical names
+/// (see `Timing::from_str`), and machine readable output (`--json`, `--csv`, `--ics`) keeps them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// The canonical names, e.g. `dhuhr`
+    #[default]
+    En,
+    /// Arabic script, e.g. `الظهر`
+    Ar,
+    /// Turkish, e.g. `öğle`
+    Tr,
+}
+
+impl Locale {
+    pub fn from_str(name: &str) -> Result<Self> {
+        return match name.to_lowercase().as_str() {
+            "en" => Ok(Self::En),
+            "ar" => Ok(Self::Ar),
+            "tr" => Ok(Self::Tr),
+            _ => Err(anyhow::anyhow!(
+                "locale = `{}` is not one of en, ar or tr!",
+                name
+            )),
+        };
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::En => "en",
+            Self::Ar => "ar",
+            Self::Tr => "tr",
+        }
+    }
+
+    pub fn list() -> [Self; 3] {
+        return [Self::En, Self::Ar, Self::Tr];
+    }
+
+    /// Returns the display name of a timing in this locale
+    ///
+    /// ### Arguments
+    /// * `timing` - The timing to name
+    pub fn label<'a>(&self, timing: &'a types::Timing) -> &'a str {
+        return match self {
+            Self::En => timing.to_str(),
+            Self::Ar => match timing {
+                types::Timing::Imsak => "الإمساك",
+                types::Timing::Fajr => "الفجر",
+                types::Timing::Sunrise => "الشروق",
+                types::Timing::Duha => "الضحى",
+                types::Timing::Dhuhr => "الظهر",
+                types::Timing::Asr => "العصر",
+                types::Timing::Maghrib => "المغرب",
+                types::Timing::Isha => "العشاء",
+                types::Timing::Midnight => "منتصف الليل",
+                types::Timing::LastThird => "الثلث الأخير",
+            },
+            Self::Tr => match timing {
+                types::Timing::Imsak => "imsak",
+                types::Timing::Fajr => "sabah",
+                types::Timing::Sunrise => "güneş",
+                types::Timing::Duha => "kuşluk",
+                types::Timing::Dhuhr => "öğle",
+                types::Timing::Asr => "ikindi",
+                types::Timing::Maghrib => "akşam",
+                types::Timing::Isha => "yatsı",
+                types::Timing::Midnight => "gece yarısı",
+                types::Timing::LastThird => "son üçte bir",
+            },
+        };
+    }
+}
+
+/// Snaps printed times to a grid of minutes from midnight, e.g. to the nearest 5 minutes to match a mosque's timetable.
+/// Applied when rendering, after the times are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Snap {
+    /// Size of the grid in minutes, 0 leaves the times unchanged
+    pub minutes: u32,
+    pub direction: SnapDirection,
+}
+
+impl Snap {
+    /// Snaps a date and time to the grid. Snapping up from just before midnight moves to the next day.
+    ///
+    /// ### Arguments
+    /// * `datetime` - The date and time to snap, e.g. a computed timing
+    pub fn apply<Tz: chrono::TimeZone>(&self, datetime: DateTime<Tz>) -> DateTime<Tz> {
+        let local = datetime.naive_local();
+        return datetime + (self.apply_naive(local) - local);
+    }
+
+    /// Snaps every timing of a date to the grid, keeping the date each one falls on (see `CheckedTimes::datetime`).
+    /// Timings with no solution stay `None`.
+    ///
+    /// ### Arguments
+    /// * `date` - The date the timings were calculated for
+    /// * `times` - The timings to snap
+    pub fn apply_times(&self, date: NaiveDate, times: &CheckedTimes) -> CheckedTimes {
+        let snap = |timing: types::Timing| {
+            return times
+                .datetime(date, &timing)
+                .map(|datetime| self.apply_naive(datetime).time());
+        };
+        return CheckedTimes {
+            imsak: snap(types::Timing::Imsak),
+            fajr: snap(types::Timing::Fajr),
+            sunrise: snap(types::Timing::Sunrise),
+            duha: snap(types::Timing::Duha),
+            dhuhr: snap(types::Timing::Dhuhr),
+            asr: snap(types::Timing::Asr),
+            maghrib: snap(types::Timing::Maghrib),
+            isha: snap(types::Timing::Isha),
+            midnight: snap(types::Timing::Midnight),
+            last_third: snap(types::Timing::LastThird),
+        };
+    }
+
+    /// Snaps a local date and time to the grid, like `apply`
+    pub fn apply_naive(&self, datetime: NaiveDateTime) -> NaiveDateTime {
+        if self.minutes == 0 {
+            return datetime;
+        }
+        let datetime = datetime.with_nanosecond(0).unwrap_or(datetime);
+        let step = self.minutes as i64 * 60;
+        let seconds = datetime.num_seconds_from_midnight() as i64;
+        let snapped = match self.direction {
+            SnapDirection::Up => (seconds + step - 1).div_euclid(step) * step,
+            SnapDirection::Down => seconds.div_euclid(step) * step,
+            SnapDirection::Nearest => (seconds + step / 2).div_euclid(step) * step,
+        };
+        return datetime + Duration::seconds(snapped - seconds);
+    }
+}
+
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(version = crate::version(), about, long_about = None)]
 pub struct Options {
     #[command(subcommand)]
     commands: Commands,
+
+    /// Suppresses warnings (e.g. a failed place lookup). Errors are still printed to stderr.
+    #[arg(short, long, global = true, alias = "no-warnings", action=ArgAction::SetTrue)]
+    quiet: bool,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
-    /// Use location (city/country) to get prayer times. WARNING: Uses external API call, network connection required.
+    /// Use location (city/country) to get prayer times. Common cities are bundled, others use an external API call (network connection required).
     Location {
         #[command(flatten)]
         common: CommonConfig,
@@ -47,19 +1450,39 @@ pub enum Commands {
         /// Country to calculate the times for
         #[arg(long)]
         country: String,
+
+        /// Always looks up the coordinates with the API, ignoring bundled and cached results. The result is still cached.
+        #[arg(long, action=ArgAction::SetTrue)]
+        no_cache: bool,
+
+        /// Lists the matching places when the city is ambiguous (e.g. Springfield) and asks which one to use
+        #[arg(long, action=ArgAction::SetTrue, conflicts_with = "index")]
+        interactive: bool,
+
+        /// Uses the N-th matching place (starting at 1) when the city is ambiguous, as listed by --interactive
+        #[arg(long, value_parser=clap::value_parser!(u32).range(1..))]
+        index: Option<u32>,
     },
     /// Use coordinates (latitude/longitude) to get prayer times.
     Coord {
         #[command(flatten)]
         common: CommonConfig,
 
-        /// Latitude to calculate the times for
-        #[arg(long)]
-        lat: f64,
+        /// Latitude to calculate the times for (e.g. `43.8`, `-43.8` or `43.8S`)
+        #[arg(long, value_parser=parse_latitude, allow_negative_numbers = true, required_unless_present = "coords_file")]
+        lat: Option<f64>,
 
-        /// Longitude to calculate the time for
-        #[arg(long)]
-        lng: f64,
+        /// Longitude to calculate the time for (e.g. `-78.9` or `78.9W`)
+        #[arg(long, value_parser=parse_longitude, allow_negative_numbers = true, required_unless_present = "coords_file")]
+        lng: Option<f64>,
+
+        /// Looks up and prints the place name for the coordinates. WARNING: Uses external API call, network connection required.
+        #[arg(long, action=ArgAction::SetTrue)]
+        resolve: bool,
+
+        /// CSV/TSV file with a `name,lat,lng` row per location, printing a labeled block of times for each
+        #[arg(long, conflicts_with_all = ["lat", "lng", "resolve", "watch"])]
+        coords_file: Option<PathBuf>,
     },
     /// Lists all the available timings.
     Timings,
@@ -71,20 +1494,114 @@ pub enum Commands {
         #[arg(default_value_t=String::new())]
         query: String,
     },
+    /// Prints the direction of the Kaaba (Qibla) from the coordinates
+    Qibla {
+        /// Latitude to calculate the direction from (e.g. `43.8`, `-43.8` or `43.8S`)
+        #[arg(long, value_parser=parse_latitude, allow_negative_numbers = true)]
+        lat: f64,
+
+        /// Longitude to calculate the direction from (e.g. `-78.9` or `78.9W`)
+        #[arg(long, value_parser=parse_longitude, allow_negative_numbers = true)]
+        lng: f64,
+    },
+    /// Prints the next prayer and the time left until it on one line (e.g. for status bars)
+    Next {
+        #[command(flatten)]
+        common: CommonConfig,
+
+        /// Latitude to calculate the times for (e.g. `43.8`, `-43.8` or `43.8S`)
+        #[arg(long, value_parser=parse_latitude, allow_negative_numbers = true)]
+        lat: f64,
+
+        /// Longitude to calculate the time for (e.g. `-78.9` or `78.9W`)
+        #[arg(long, value_parser=parse_longitude, allow_negative_numbers = true)]
+        lng: f64,
+    },
+    /// Prints a table of the fardh timings for every day of a month
+    Calendar {
+        #[command(flatten)]
+        common: CommonConfig,
+
+        /// Latitude to calculate the times for (e.g. `43.8`, `-43.8` or `43.8S`)
+        #[arg(long, value_parser=parse_latitude, allow_negative_numbers = true)]
+        lat: f64,
+
+        /// Longitude to calculate the time for (e.g. `-78.9` or `78.9W`)
+        #[arg(long, value_parser=parse_longitude, allow_negative_numbers = true)]
+        lng: f64,
+
+        /// Month of the calendar (1-12) [default: the month of --date]
+        #[arg(long, value_parser=clap::value_parser!(u32).range(1..=12))]
+        month: Option<u32>,
+
+        /// Year of the calendar [default: the year of --date]
+        #[arg(long)]
+        year: Option<i32>,
+    },
+    /// Compares the timings of two calculation authorities, with the difference in minutes for each timing
+    Diff {
+        /// Latitude to calculate the times for (e.g. `43.8`, `-43.8` or `43.8S`)
+        #[arg(long, value_parser=parse_latitude, allow_negative_numbers = true)]
+        lat: f64,
+
+        /// Longitude to calculate the time for (e.g. `-78.9` or `78.9W`)
+        #[arg(long, value_parser=parse_longitude, allow_negative_numbers = true)]
+        lng: f64,
+
+        /// The two calculation authorities to compare, e.g. `--auth mwl --auth isna` (see `salah authority` for available values)
+        #[arg(long, required = true, action=ArgAction::Append)]
+        auth: Vec<String>,
+
+        /// Date to calculate the timings for (YYYY-MM-DD). Use `today`, `tomorrow`, `yesterday` or a day offset (e.g. `+3`, `-2`) for dates relative to today.
+        #[arg(short, long, default_value_t=String::from("today"), allow_negative_numbers = true)]
+        date: String,
+
+        /// Timezone to output the timings for. Use `auto` to look it up from the coordinates (requires the `network` feature).
+        #[arg(short, long, default_value_t=String::from(DEFAULT_TIMEZONE))]
+        timezone: String,
+
+        /// If set, uses Hanafi madhab for Asr calculation [default: false]
+        #[arg(long, action=ArgAction::SetTrue)]
+        hanafi: bool,
+
+        /// Madhab for Asr calculation (hanafi | shafi | maliki | hanbali | standard). Only Hanafi differs. [default: shafi]
+        #[arg(long, conflicts_with = "hanafi")]
+        madhab: Option<String>,
+
+        /// Solar position model (low | high). `high` agrees more closely with official tables at a small speed cost.
+        #[arg(long, default_value_t=String::from("low"))]
+        precision: String,
+
+        /// Format string for timings output. See `man strftime` for configuration.
+        #[arg(long, default_value_t=String::from("%H:%M:%S"))]
+        format: String,
+    },
+    /// Prints the Hijri (Islamic) date
+    Hijri {
+        /// Date to convert (YYYY-MM-DD). Use `today`, `tomorrow`, `yesterday` or a day offset (e.g. `+3`, `-2`) for dates relative to today.
+        #[arg(short, long, default_value_t=String::from("today"), allow_negative_numbers = true)]
+        date: String,
+
+        /// Timezone used to determine today's date.
+        #[arg(short, long, default_value_t=String::from(DEFAULT_TIMEZONE))]
+        timezone: String,
+    },
+    /// Prints the version and build information
+    Info,
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 pub struct CommonConfig {
-    /// Names of the timings to calculate for (see `salah timings` for available values) (ignored by --all)
+    /// Names of the timings to calculate for (see `salah timings` for available values). The fardh timings if none are given (ignored by --all)
     #[arg(action=ArgAction::Append)]
     timings: Vec<String>,
 
-    /// Date to calculate the timings for (YYYY-MM-DD). Use `today` for today's date.
-    #[arg(short, long, default_value_t=String::from("today"))]
+    /// Date to calculate the timings for (YYYY-MM-DD). Use `today`, `tomorrow`, `yesterday` or a day offset (e.g. `+3`, `-2`) for dates relative to today.
+    #[arg(short, long, default_value_t=String::from("today"), allow_negative_numbers = true)]
     date: String,
 
-    /// Timezone to output the timings for.
-    #[arg(short, long, default_value_t=String::from("America/Toronto"))]
+    /// Timezone to output the timings for. Use `auto` to look it up from the coordinates (requires the `network` feature).
+    #[arg(short, long, default_value_t=String::from(DEFAULT_TIMEZONE))]
     timezone: String,
 
     /// Calculates all the available prayer timings.
@@ -95,57 +1612,650 @@ pub struct CommonConfig {
     #[arg(long, action=ArgAction::SetTrue)]
     hanafi: bool,
 
+    /// Madhab for Asr calculation (hanafi | shafi | maliki | hanbali | standard). Only Hanafi differs. [default: shafi]
+    #[arg(long, conflicts_with = "hanafi")]
+    madhab: Option<String>,
+
     /// Calculation authority to use (see `salah authority` for available values)
-    #[arg(long, default_value_t=String::from("ISNA"))]
+    #[arg(long, default_value_t=String::from(DEFAULT_AUTHORITY))]
     auth: String,
 
-    /// Format string for timings output. See `man strftime` for configuration.
+    /// Format string for timings output. See `man strftime` for configuration. Date specifiers (e.g. `%Y-%m-%d`) are supported.
     #[arg(long, default_value_t=String::from("%H:%M:%S"))]
     format: String,
+
+    /// Prints 12-hour times (e.g. `05:12 AM`), short for `--format "%I:%M %p"`. Cannot be used with --format.
+    #[arg(long = "12h", action=ArgAction::SetTrue, conflicts_with = "format")]
+    twelve_hour: bool,
+
+    /// If set, the date is in Ramadan (Makkah authority uses 120 min after Maghrib for Isha) [default: false]
+    #[arg(long, action=ArgAction::SetTrue)]
+    ramadan: bool,
+
+    /// Also prints the half of the night (midpoint between maghrib and fajr), an alternative end to isha.
+    #[arg(long, action=ArgAction::SetTrue)]
+    half_night: bool,
+
+    /// Also prints the Hijri date (see `salah hijri`) before the timings
+    #[arg(long, action=ArgAction::SetTrue, conflicts_with_all = ["json", "ics", "csv", "tabular", "watch"])]
+    hijri: bool,
+
+    /// Annotates each timing with the time since or until it, e.g. `(2h 5m ago)` or `(in 40m)`, when the date is today
+    #[arg(long, action=ArgAction::SetTrue, conflicts_with_all = ["json", "ics", "csv", "tabular", "watch"])]
+    compare_now: bool,
+
+    /// Prints a table of fajr and isha for every calculation authority, with asr for both schools. Ignores [TIMINGS]...
+    #[arg(long, action=ArgAction::SetTrue)]
+    tabular: bool,
+
+    /// Also prints the calculation parameters actually used (angles after overrides, madhab, high latitude rule and offsets)
+    #[arg(long, action=ArgAction::SetTrue)]
+    show_angles: bool,
+
+    /// Solar position model (low | high). `high` agrees more closely with official tables at a small speed cost.
+    #[arg(long, default_value_t=String::from("low"))]
+    precision: String,
+
+    /// Prints the timings as a JSON object (`{"fajr":"05:12:00", ...}`) instead of one per line. Times follow --format.
+    #[arg(long, action=ArgAction::SetTrue)]
+    json: bool,
+
+    /// Prints an iCalendar (.ics) with one event per timing per day, starting at --date. Defaults to the fardh timings.
+    #[arg(long, action=ArgAction::SetTrue)]
+    ics: bool,
+
+    /// Prints the timings as CSV (`date,fajr,sunrise,...`) with one row per day. Times follow --format.
+    #[arg(long, action=ArgAction::SetTrue, conflicts_with_all = ["json", "ics", "tabular"])]
+    csv: bool,
+
+    /// Custom angle of the sun below the horizon for Fajr in degrees (overrides --auth's Fajr)
+    #[arg(long)]
+    fajr_angle: Option<f64>,
+
+    /// Custom angle of the sun below the horizon for Isha in degrees (overrides --auth's Isha)
+    #[arg(long, conflicts_with = "isha_interval")]
+    isha_angle: Option<f64>,
+
+    /// Custom interval in minutes after Maghrib for Isha (overrides --auth's Isha, 1 - 240)
+    #[arg(long, value_parser=clap::value_parser!(u64).range(1..=240))]
+    isha_interval: Option<u64>,
+
+    /// Fajr angle in degrees replacing only --auth's Fajr, keeping the rest of the authority's behaviour
+    #[arg(long, conflicts_with = "fajr_angle")]
+    fajr_angle_override: Option<f64>,
+
+    /// Isha angle in degrees replacing only --auth's Isha, keeping the rest of the authority's behaviour
+    #[arg(long, conflicts_with_all = ["isha_angle", "isha_interval"])]
+    isha_angle_override: Option<f64>,
+
+    /// Named twilight for Fajr (civil = 6°, nautical = 12°, astronomical = 18°), like --fajr-angle-override
+    #[arg(long, conflicts_with_all = ["fajr_angle", "fajr_angle_override"])]
+    fajr_twilight: Option<String>,
+
+    /// Named twilight for Isha (civil = 6°, nautical = 12°, astronomical = 18°), like --isha-angle-override
+    #[arg(long, conflicts_with_all = ["isha_angle", "isha_interval", "isha_angle_override"])]
+    isha_twilight: Option<String>,
+
+    /// Minutes after solar noon for Dhuhr, so the sun has clearly passed the meridian (0 - 60)
+    #[arg(long, default_value_t = 0, value_parser=clap::value_parser!(u64).range(0..=60))]
+    dhuhr_offset: u64,
+
+    /// Shows a live countdown to the next prayer, updated every second until Ctrl-C
+    #[arg(long, action=ArgAction::SetTrue, conflicts_with_all = ["json", "ics", "csv", "tabular"])]
+    watch: bool,
+
+    /// Writes the output to a file instead of stdout, creating its parent directories if needed
+    #[arg(short, long, conflicts_with = "watch")]
+    output: Option<PathBuf>,
+
+    /// Number of days to include in the --ics or --csv output
+    #[arg(long, default_value_t = 1, value_parser=clap::value_parser!(u32).range(1..=366))]
+    days: u32,
+
+    /// Height above sea level in meters. Sunrise is earlier and Maghrib later at higher elevations.
+    #[arg(long, default_value_t = 0_f64)]
+    elevation: f64,
+
+    /// Also applies the --elevation horizon dip to the Fajr and Isha angles, not only to Sunrise and Maghrib
+    #[arg(long, action=ArgAction::SetTrue)]
+    elevation_twilight: bool,
+
+    /// Prints the times in local mean time (sun based time of the longitude, Dhuhr near 12:00) instead of the timezone's civil time.
+    /// Not available for outputs compared with the current time or read by calendars.
+    #[arg(long, action=ArgAction::SetTrue, conflicts_with_all = ["ics", "watch", "compare_now"])]
+    lmt: bool,
+
+    /// Snaps the printed times to a grid of minutes (e.g. `5` for 05:10, 05:15, ...), 0 leaves them unchanged
+    #[arg(long, default_value_t = 0, value_parser=clap::value_parser!(u32).range(0..=60))]
+    round: u32,
+
+    /// Direction times are snapped to the --round grid (up | down | nearest)
+    #[arg(long, default_value_t=String::from("nearest"))]
+    round_dir: String,
+
+    /// Language the timing names are displayed in (en | ar | tr). Timings are still given by their English names.
+    #[arg(long, default_value_t=String::from("en"))]
+    locale: String,
+
+    /// Order the timings are displayed in (time | name | input)
+    #[arg(long, default_value_t=String::from("time"))]
+    sort: String,
+
+    /// Minutes to add to Fajr (negative to subtract), e.g. to match a local mosque
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    adjust_fajr: i32,
+
+    /// Minutes to add to Sunrise (negative to subtract), e.g. to match a local mosque
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    adjust_sunrise: i32,
+
+    /// Minutes to add to Dhuhr (negative to subtract), e.g. to match a local mosque
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    adjust_dhuhr: i32,
+
+    /// Minutes to add to Asr (negative to subtract), e.g. to match a local mosque
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    adjust_asr: i32,
+
+    /// Minutes to add to Maghrib (negative to subtract), e.g. to match a local mosque
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    adjust_maghrib: i32,
+
+    /// Minutes to add to Isha (negative to subtract), e.g. to match a local mosque
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    adjust_isha: i32,
 }
 
 impl CommonConfig {
-    fn parsed_date(&self) -> Result<NaiveDate> {
-        let tz: Tz = self
+    fn parsed_date(&self, tz: Tz) -> Result<NaiveDate> {
+        return Ok(datetime::str2date(&self.date, tz)?);
+    }
+
+    /// Parses --timezone. `auto` must have been replaced by `with_detected_timezone` first.
+    fn parsed_timezone(&self) -> Result<Tz> {
+        if self.timezone.to_lowercase() == AUTO_TIMEZONE {
+            return Err(anyhow::anyhow!(
+                "timezone = `{}` could not be detected, please pass a timezone name (e.g. `{}`)!",
+                self.timezone,
+                DEFAULT_TIMEZONE
+            ));
+        }
+        return parse_timezone(&self.timezone);
+    }
+
+    /// Replaces `--timezone auto` with the timezone of the coordinates, looked up from the timezone boundaries
+    ///
+    /// ### Arguments
+    /// * `lat` - The latitude value
+    /// * `lng` - The longitude value
+    async fn with_detected_timezone(&self, lat: f64, lng: f64) -> Result<Self> {
+        let mut config = self.clone();
+        if self.timezone.to_lowercase() == AUTO_TIMEZONE {
+            config.timezone = detect_timezone(lat, math::normalize_longitude(lng)).await?;
+        }
+        return Ok(config);
+    }
+
+    fn parsed_timings(&self) -> Result<Vec<types::Timing>> {
+        let mut timings: Vec<types::Timing> = vec![];
+        let all_timings: Vec<types::Timing> = types::Timing::list().into_iter().collect();
+        if self.all {
+            timings = all_timings;
+        } else {
+            for timing in &self.timings {
+                let parsed: Vec<types::Timing> = if timing.to_lowercase() == "fardh" {
+                    types::Timing::fardh_list().into_iter().collect()
+                } else {
+                    vec![types::Timing::from_str(timing)?]
+                };
+                // Skip duplicates (e.g. `fajr fardh`), keeping the first occurrence's order
+                for t in parsed {
+                    if !timings.contains(&t) {
+                        timings.push(t);
+                    }
+                }
+            }
+            if timings.is_empty() {
+                timings = types::Timing::fardh_list().into_iter().collect();
+            }
+        }
+
+        return Ok(timings);
+    }
+
+    fn parsed_auth(&self) -> Result<types::Authority> {
+        let isha = match (self.isha_angle, self.parsed_isha_interval()?) {
+            (Some(angle), _) => Some(types::IshaParam::Angle(parsed_angle(angle)?)),
+            (None, Some(interval)) => Some(types::IshaParam::Duration(interval)),
+            (None, None) => None,
+        };
+        let fajr_angle = match self.fajr_angle {
+            Some(angle) => Some(parsed_angle(angle)?),
+            None => None,
+        };
+
+        if self.auth.to_lowercase() == "custom" {
+            return match (fajr_angle, isha) {
+                (Some(fajr_angle), Some(isha)) => Ok(types::Authority::Custom { fajr_angle, isha }),
+                _ => Err(anyhow::anyhow!(
+                    "authority = `custom` requires --fajr-angle and one of --isha-angle or --isha-interval!"
+                )),
+            };
+        }
+
+        // --isha-interval replaces the isha of a named authority without changing it (see `parsed_isha_interval`)
+        let isha = isha.filter(|_| self.isha_angle.is_some());
+        let base = types::Authority::from_str(&self.auth)?;
+        if fajr_angle.is_none() && isha.is_none() {
+            return Ok(base);
+        }
+        return Ok(types::Authority::from_angles(
+            fajr_angle.unwrap_or(base.fajr_angle()),
+            isha.unwrap_or(base.isha_param()),
+        ));
+    }
+
+    fn parsed_isha_interval(&self) -> Result<Option<std::time::Duration>> {
+        return self.isha_interval.map(minutes_duration).transpose();
+    }
+
+    /// Parses the fajr/isha angle overrides, given as angles or named twilights
+    fn parsed_angle_overrides(&self) -> Result<(Option<f64>, Option<f64>)> {
+        let parse = |angle: Option<f64>, twilight: &Option<String>| -> Result<Option<f64>> {
+            if let Some(twilight) = twilight {
+                return Ok(Some(types::Twilight::from_str(twilight)?.angle()));
+            }
+            return match angle {
+                Some(angle) => Ok(Some(parsed_angle(angle)?)),
+                None => Ok(None),
+            };
+        };
+        let fajr = parse(self.fajr_angle_override, &self.fajr_twilight)?;
+        let isha = parse(self.isha_angle_override, &self.isha_twilight)?;
+        return Ok((fajr, isha));
+    }
+
+    fn parsed_school(&self) -> Result<types::School> {
+        return parse_school(self.hanafi, &self.madhab);
+    }
+
+    fn parsed_adjustments(&self) -> types::Adjustments {
+        return types::Adjustments {
+            fajr: self.adjust_fajr,
+            sunrise: self.adjust_sunrise,
+            dhuhr: self.adjust_dhuhr,
+            asr: self.adjust_asr,
+            maghrib: self.adjust_maghrib,
+            isha: self.adjust_isha,
+        };
+    }
+
+    fn parsed_precision(&self) -> Result<astro::Precision> {
+        return Ok(astro::Precision::from_str(&self.precision)?);
+    }
+
+    /// Validates the calculation options for the given coordinates
+    ///
+    /// ### Arguments
+    /// * `lat` - The latitude value
+    /// * `lng` - The longitude value
+    /// * `place` - The resolved place name for the coordinates, if any
+    fn calculation(&self, lat: f64, lng: f64, place: Option<String>) -> Result<ParsedOptions> {
+        if !math::is_valid_longitude(lng) {
+            return Err(anyhow::anyhow!(
+                "longitude = `{}` is out of range [-180, 180] or [0, 360)!",
+                lng
+            ));
+        }
+        let normalized = math::normalize_longitude(lng);
+        if normalized != lng {
+            log::warn(format!(
+                "longitude = `{}` is outside of [-180, 180], using `{}` (east is positive)",
+                lng, normalized
+            ));
+        }
+        let lng = normalized;
+        let timezone = self
             .parsed_timezone()
-            .with_context(|| format!("Unable to parse timezone"))?;
+            .with_context(|| format!("Failed to create timezone with `{}`", self.timezone))?;
+        let date = self
+            .parsed_date(timezone)
+            .with_context(|| format!("Failed to create date with `{}`", self.date))?;
+        let timings = self
+            .parsed_timings()
+            .with_context(|| format!("Failed to parse timings with {:?}", self.timings))?;
+        let sort = TimingSort::from_str(&self.sort)
+            .with_context(|| format!("Failed to parse sort with `{}`", self.sort))?;
+        let locale = Locale::from_str(&self.locale)
+            .with_context(|| format!("Failed to parse locale with `{}`", self.locale))?;
+        let auth = self
+            .parsed_auth()
+            .with_context(|| format!("Failed to parse authority with `{}`", self.auth))?;
+        let (fajr_angle_override, isha_angle_override) = self
+            .parsed_angle_overrides()
+            .with_context(|| "Failed to parse angle overrides")?;
+        let precision = self
+            .parsed_precision()
+            .with_context(|| format!("Failed to parse precision with `{}`", self.precision))?;
+        let school = self.parsed_school().with_context(|| {
+            format!(
+                "Failed to parse madhab with `{}`",
+                self.madhab.as_deref().unwrap_or_default()
+            )
+        })?;
+        let format = if self.twelve_hour {
+            String::from(TWELVE_HOUR_FORMAT)
+        } else {
+            parsed_format(&self.format)
+                .with_context(|| format!("Failed to parse format with `{}`", self.format))?
+        };
+        if !self.elevation.is_finite() {
+            return Err(anyhow::anyhow!(
+                "elevation = `{}` is not a valid number of meters!",
+                self.elevation
+            ));
+        }
+
+        return Ok(ParsedOptions::Calculation {
+            date,
+            timezone,
+            lat,
+            lng,
+            timings,
+            sort,
+            locale,
+            auth,
+            fajr_angle_override,
+            isha_angle_override,
+            isha_interval: self.parsed_isha_interval()?,
+            school,
+            format,
+            precision,
+            tabular: self.tabular,
+            half_night: self.half_night,
+            hijri: self.hijri,
+            show_angles: self.show_angles,
+            compare_now: self.compare_now,
+            ramadan: self.ramadan,
+            json: self.json,
+            ics: self.ics,
+            csv: self.csv,
+            output: self.output.clone(),
+            days: self.days,
+            watch: self.watch,
+            dhuhr_offset: minutes_duration(self.dhuhr_offset)?,
+            elevation: self.elevation,
+            elevation_twilight: self.elevation_twilight,
+            local_mean_time: self.lmt,
+            snap: Snap {
+                minutes: self.round,
+                direction: SnapDirection::from_str(&self.round_dir)?,
+            },
+            adjustments: self.parsed_adjustments(),
+            place,
+            calendar: None,
+            next: false,
+        });
+    }
+
+    /// Validates the calculation options for the next prayer at the given coordinates
+    ///
+    /// ### Arguments
+    /// * `lat` - The latitude value
+    /// * `lng` - The longitude value
+    fn next(&self, lat: f64, lng: f64) -> Result<ParsedOptions> {
+        if self.lmt {
+            return Err(anyhow::anyhow!(
+                "--lmt can't be used with `salah next`, which compares the timings with the current civil time!"
+            ));
+        }
+        // The next prayer is one line for the current time, the other outputs and timings would be silently ignored
+        let unsupported = [
+            ("[TIMINGS]", !self.timings.is_empty()),
+            ("--all", self.all),
+            ("--date", self.date.to_lowercase() != "today"),
+            ("--json", self.json),
+            ("--ics", self.ics),
+            ("--csv", self.csv),
+            ("--tabular", self.tabular),
+            ("--watch", self.watch),
+            ("--compare-now", self.compare_now),
+            ("--hijri", self.hijri),
+            ("--half-night", self.half_night),
+            ("--show-angles", self.show_angles),
+        ];
+        if let Some((flag, _)) = unsupported.iter().find(|(_, set)| *set) {
+            return Err(anyhow::anyhow!(
+                "{} can't be used with `salah next`, which prints the next prayer after the current time!",
+                flag
+            ));
+        }
+        let mut parsed = self.calculation(lat, lng, None)?;
+        if let ParsedOptions::Calculation { next, .. } = &mut parsed {
+            *next = true;
+        }
+        return Ok(parsed);
+    }
+
+    /// Validates the calculation options for a month calendar at the given coordinates
+    ///
+    /// ### Arguments
+    /// * `lat` - The latitude value
+    /// * `lng` - The longitude value
+    /// * `month` - The month of the calendar, the month of `--date` if `None`
+    /// * `year` - The year of the calendar, the year of `--date` if `None`
+    fn calendar(
+        &self,
+        lat: f64,
+        lng: f64,
+        month: Option<u32>,
+        year: Option<i32>,
+    ) -> Result<ParsedOptions> {
+        // The calendar is a table (or CSV with --csv), the other output modes would be silently ignored
+        let unsupported = [
+            ("--json", self.json),
+            ("--ics", self.ics),
+            ("--tabular", self.tabular),
+            ("--watch", self.watch),
+            ("--compare-now", self.compare_now),
+        ];
+        if let Some((flag, _)) = unsupported.iter().find(|(_, set)| *set) {
+            return Err(anyhow::anyhow!(
+                "{} can't be used with `salah calendar`, use --csv for machine readable output!",
+                flag
+            ));
+        }
+        let mut parsed = self.calculation(lat, lng, None)?;
+        if let ParsedOptions::Calculation { date, calendar, .. } = &mut parsed {
+            let month = month.unwrap_or(date.month());
+            let year = year.unwrap_or(date.year());
+            let first = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(|| {
+                anyhow::anyhow!("month = `{}` and year = `{}` is not valid!", month, year)
+            })?;
+            *date = first;
+            *calendar = Some(first);
+        }
+        return Ok(parsed);
+    }
+}
+
+/// Parses the school from --hanafi or --madhab, Shafi if neither is given
+///
+/// ### Arguments
+/// * `hanafi` - Whether --hanafi was given
+/// * `madhab` - The --madhab value
+fn parse_school(hanafi: bool, madhab: &Option<String>) -> Result<types::School> {
+    if hanafi {
+        return Ok(types::School::Hanafi);
+    }
+    return match madhab {
+        Some(madhab) => Ok(types::School::from_str(madhab)?),
+        None => Ok(types::School::Shafi),
+    };
+}
+
+/// Validates a fajr/isha angle of the sun below the horizon in degrees
+fn parsed_angle(angle: f64) -> Result<f64> {
+    if !(angle > 0_f64 && angle < 90_f64) {
+        return Err(anyhow::anyhow!(
+            "angle = `{}` is out of range (0, 90)!",
+            angle
+        ));
+    }
+    return Ok(angle);
+}
+
+/// Parses a coordinate as a signed decimal or with a directional suffix (e.g. `43.8N`, `78.9 W`)
+///
+/// ### Arguments
+/// * `value` - The coordinate string
+/// * `positive` - The suffix for positive values (`N` or `E`)
+/// * `negative` - The suffix for negative values (`S` or `W`)
+fn parse_coordinate(value: &str, positive: char, negative: char) -> Result<f64> {
+    let trimmed = value.trim();
+    let suffix = trimmed
+        .chars()
+        .last()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase());
+    let number = match suffix {
+        Some(_) => trimmed[..trimmed.len() - 1].trim_end(),
+        None => trimmed,
+    };
+
+    let parsed = number
+        .parse::<f64>()
+        .with_context(|| format!("Could not convert `{}` to a number", number))?;
+
+    return match suffix {
+        None => Ok(parsed),
+        Some(_) if number.starts_with(['-', '+']) => Err(anyhow::anyhow!(
+            "`{}` has both a sign and a direction, use one or the other!",
+            value
+        )),
+        Some(c) if c == positive => Ok(parsed),
+        Some(c) if c == negative => Ok(-parsed),
+        Some(c) => Err(anyhow::anyhow!(
+            "`{}` is not a valid direction, expected `{}` or `{}`!",
+            c,
+            positive,
+            negative
+        )),
+    };
+}
+
+/// A named location from a `--coords-file`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoordsEntry {
+    pub name: String,
+    pub lat: f64,
+    pub lng: f64,
+}
 
-        return datetime::str2date(&self.date, tz);
-    }
+/// Parses the rows of a `--coords-file`, one `name,lat,lng` location per line.
+/// Fields are separated by commas, or tabs if the line has any. Blank lines, `#` comments
+/// and a leading `name,lat,lng` header are skipped. Coordinates accept the same forms as `--lat` and `--lng`.
+///
+/// ### Arguments
+/// * `content` - The contents of the file
+pub fn parse_coords_file(content: &str) -> Result<Vec<CoordsEntry>> {
+    let mut entries: Vec<CoordsEntry> = vec![];
+    for (i, line) in content.lines().enumerate() {
+        let line_number = i + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let separator = if trimmed.contains('\t') { '\t' } else { ',' };
+        let fields: Vec<&str> = trimmed.split(separator).map(|f| f.trim()).collect();
+        if entries.is_empty() && fields.len() == 3 && fields[1].eq_ignore_ascii_case("lat") {
+            continue;
+        }
+        if fields.len() != 3 || fields[0].is_empty() {
+            return Err(anyhow::anyhow!(
+                "line {}: expected `name,lat,lng`, got `{}`",
+                line_number,
+                trimmed
+            ));
+        }
 
-    fn parsed_timezone(&self) -> Result<Tz> {
-        match self.timezone.parse::<Tz>() {
-            Ok(t) => Ok(t),
-            Err(e) => return Err(anyhow::anyhow!(e)),
+        let lat = parse_latitude(fields[1])
+            .with_context(|| format!("line {}: invalid latitude", line_number))?;
+        let lng = parse_longitude(fields[2])
+            .with_context(|| format!("line {}: invalid longitude", line_number))?;
+        if !(-90_f64..=90_f64).contains(&lat) || !math::is_valid_longitude(lng) {
+            return Err(anyhow::anyhow!(
+                "line {}: lat = `{}`, lng = `{}` is out of range",
+                line_number,
+                lat,
+                lng
+            ));
         }
+        entries.push(CoordsEntry {
+            name: fields[0].to_owned(),
+            lat,
+            lng,
+        });
     }
+    if entries.is_empty() {
+        return Err(anyhow::anyhow!("no locations found"));
+    }
+    return Ok(entries);
+}
 
-    fn parsed_timings(&self) -> Result<Vec<types::Timing>> {
-        let mut timings: Vec<types::Timing> = vec![];
-        let all_timings: Vec<types::Timing> = types::Timing::list().into_iter().collect();
-        if self.all {
-            timings = all_timings;
-        } else {
-            for timing in &self.timings {
-                let m = match types::Timing::from_str(timing) {
-                    Some(t) => t,
-                    None => return Err(anyhow::anyhow!("timing = `{}` is not valid!", timing)),
-                };
-                timings.push(m);
-            }
-        }
+/// Labels the rendered output for one location of a `--coords-file`
+///
+/// ### Arguments
+/// * `name` - The location's name
+/// * `rendered` - The rendered output for the location
+pub fn render_labeled(name: &str, rendered: &str) -> String {
+    return format!("[{}]\n{}", name, rendered);
+}
 
-        return Ok(timings);
-    }
+fn parse_latitude(value: &str) -> Result<f64> {
+    return parse_coordinate(value, 'N', 'S');
+}
 
-    fn parsed_auth(&self) -> Result<types::Authority> {
-        match types::Authority::from_str(&self.auth) {
-            Some(a) => Ok(a),
-            None => Err(anyhow::anyhow!("authority = `{}` is not valid!", self.auth)),
+fn parse_longitude(value: &str) -> Result<f64> {
+    return parse_coordinate(value, 'E', 'W');
+}
+
+/// Converts a number of minutes given on the command line to a duration, erroring instead of overflowing
+fn minutes_duration(minutes: u64) -> Result<std::time::Duration> {
+    return minutes
+        .checked_mul(60)
+        .map(std::time::Duration::from_secs)
+        .ok_or_else(|| anyhow::anyhow!("`{}` minutes is out of range!", minutes));
+}
+
+/// Parses an IANA timezone name, suggesting close matches (e.g. `America/Toronto` for `america/toronto`) if it is not one
+fn parse_timezone(name: &str) -> Result<Tz> {
+    return datetime::parse_timezone(name).map_err(|err| {
+        let suggestions: Vec<String> = datetime::suggest_timezones(name)
+            .iter()
+            .map(|tz| format!("`{}`", tz.name()))
+            .collect();
+        if suggestions.is_empty() {
+            return anyhow::anyhow!("{} See `salah timezones` for the valid values.", err);
         }
+        return anyhow::anyhow!("{} Did you mean {}?", err, suggestions.join(" or "));
+    });
+}
+
+/// Validates a strftime format string, both time and date specifiers are allowed
+fn parsed_format(format: &str) -> Result<String> {
+    let invalid = StrftimeItems::new(format).any(|item| matches!(item, Item::Error));
+    if invalid {
+        return Err(anyhow::anyhow!(
+            "format = `{}` contains an invalid specifier! See `man strftime`.",
+            format
+        ));
     }
+    return Ok(format.to_owned());
 }
 
+// Parsed once per run, so the size of the calculation variant does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum ParsedOptions {
     Calculation {
@@ -154,120 +2264,149 @@ pub enum ParsedOptions {
         lat: f64,
         lng: f64,
         timings: Vec<types::Timing>,
+        sort: TimingSort,
+        locale: Locale,
         auth: types::Authority,
+        fajr_angle_override: Option<f64>,
+        isha_angle_override: Option<f64>,
+        isha_interval: Option<std::time::Duration>,
         school: types::School,
         format: String,
+        precision: astro::Precision,
+        tabular: bool,
+        half_night: bool,
+        hijri: bool,
+        show_angles: bool,
+        compare_now: bool,
+        ramadan: bool,
+        json: bool,
+        ics: bool,
+        csv: bool,
+        output: Option<PathBuf>,
+        days: u32,
+        watch: bool,
+        dhuhr_offset: std::time::Duration,
+        elevation: f64,
+        elevation_twilight: bool,
+        local_mean_time: bool,
+        adjustments: types::Adjustments,
+        snap: Snap,
+        place: Option<String>,
+        calendar: Option<NaiveDate>,
+        next: bool,
+    },
+    /// Calculation options for each named location of a `--coords-file`
+    Batch {
+        locations: Vec<(String, ParsedOptions)>,
+        output: Option<PathBuf>,
     },
     Timings,
     Authority,
     Timezones {
         query: String,
     },
+    Qibla {
+        lat: f64,
+        lng: f64,
+    },
+    Hijri {
+        date: NaiveDate,
+    },
+    Diff {
+        date: NaiveDate,
+        timezone: Tz,
+        lat: f64,
+        lng: f64,
+        auths: [types::Authority; 2],
+        school: types::School,
+        precision: astro::Precision,
+        format: String,
+    },
+    Info,
 }
 
 /// Validates the command-line arguments
 pub async fn parse() -> Result<ParsedOptions> {
     let opts = Options::parse();
+    log::set_quiet(opts.quiet);
 
     match &opts.commands {
         Commands::Location {
             common,
             city,
             country,
+            no_cache,
+            interactive,
+            index,
         } => {
-            let date = common
-                .parsed_date()
-                .with_context(|| format!("Failed to create date with `{}`", common.date))?;
-            let timezone = common
-                .parsed_timezone()
-                .with_context(|| format!("Failed to create timezone with `{}`", common.timezone))?;
-            let timings = common
-                .parsed_timings()
-                .with_context(|| format!("Failed to parse timings with {:?}", common.timings))?;
-            let auth = common
-                .parsed_auth()
-                .with_context(|| format!("Failed to parse authority with `{}`", common.auth))?;
-            let school = if common.hanafi {
-                types::School::Hanafi
-            } else {
-                types::School::Shafi
-            };
-            let format = common.format.to_owned();
-
-            // API call to get lat,lng from city, country
-            #[derive(Deserialize)]
-            struct APICoord {
-                lat: String,
-                lon: String,
+            // Common cities are bundled, unless the API is asked for (e.g. to choose between matches)
+            let choosing = *interactive || index.is_some();
+            if !no_cache && !choosing {
+                if let Some((lat, lng)) = cities::lookup(city, country) {
+                    let common = common.with_detected_timezone(lat, lng).await?;
+                    return common.calculation(lat, lng, None);
+                }
             }
-            let url = format!(
-                "https://nominatim.openstreetmap.org/search?city={}&country={}&format=jsonv2",
-                city, country
-            );
-            let mut headers = HeaderMap::new();
-            headers.insert(USER_AGENT, HeaderValue::from_static("salah-cli"));
-            let coords: Vec<APICoord> = api::fetch::<Vec<APICoord>>(url.as_str(), headers)
-                .await
-                .with_context(|| {
-                    format!(
-                        "Could not get coordinates with city = `{}` and country = `{}`",
-                        city, country
-                    )
-                })?;
-
-            if coords.len() < 1 {
-                return Err(anyhow::anyhow!("Could not find lat, lng from city = `{}` and country = `{}`. Please check spelling!", city, country));
+            let (lat, lng) =
+                geocode_location(city, country, *no_cache, *interactive, *index).await?;
+            let common = common.with_detected_timezone(lat, lng).await?;
+            return common.calculation(lat, lng, None);
+        }
+        Commands::Coord {
+            common,
+            lat,
+            lng,
+            resolve,
+            coords_file,
+        } => {
+            if let Some(path) = coords_file {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read `{}`", path.display()))?;
+                let entries = parse_coords_file(&content)
+                    .with_context(|| format!("Failed to parse `{}`", path.display()))?;
+                let mut locations: Vec<(String, ParsedOptions)> = vec![];
+                for entry in entries {
+                    let parsed = common
+                        .with_detected_timezone(entry.lat, entry.lng)
+                        .await?
+                        .calculation(entry.lat, entry.lng, None)?;
+                    locations.push((entry.name, parsed));
+                }
+                return Ok(ParsedOptions::Batch {
+                    locations,
+                    output: common.output.clone(),
+                });
             }
 
-            let lat = coords[0]
-                .lat
-                .parse::<f64>()
-                .with_context(|| format!("Could not convert `lat` = `{}` to f64", coords[0].lat))?;
-            let lng = coords[0]
-                .lon
-                .parse::<f64>()
-                .with_context(|| format!("Could not convert `lng` = `{}` to f64", coords[0].lon))?;
-
-            return Ok(ParsedOptions::Calculation {
-                date,
-                timezone,
-                lat,
-                lng,
-                timings,
-                auth,
-                school,
-                format,
-            });
-        }
-        Commands::Coord { common, lat, lng } => {
-            let date = common
-                .parsed_date()
-                .with_context(|| format!("Failed to create date with `{}`", common.date))?;
-            let timezone = common
-                .parsed_timezone()
-                .with_context(|| format!("Failed to create timezone with `{}`", common.timezone))?;
-            let timings = common
-                .parsed_timings()
-                .with_context(|| format!("Failed to parse timings with {:?}", common.timings))?;
-            let auth = common
-                .parsed_auth()
-                .with_context(|| format!("Failed to parse authority with `{}`", common.auth))?;
-            let school = if common.hanafi {
-                types::School::Hanafi
+            let (lat, lng) = match (lat, lng) {
+                (Some(lat), Some(lng)) => (*lat, *lng),
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "--lat and --lng are required without --coords-file!"
+                    ))
+                }
+            };
+            let place = if *resolve {
+                resolve_place(lat, lng).await
             } else {
-                types::School::Shafi
+                None
             };
-            let format = common.format.to_owned();
-            return Ok(ParsedOptions::Calculation {
-                date,
-                timezone,
-                lat: *lat,
-                lng: *lng,
-                timings,
-                auth,
-                school,
-                format,
-            });
+            let common = common.with_detected_timezone(lat, lng).await?;
+            return common.calculation(lat, lng, place);
+        }
+        Commands::Next { common, lat, lng } => {
+            let common = common.with_detected_timezone(*lat, *lng).await?;
+            return common.next(*lat, *lng);
+        }
+        Commands::Calendar {
+            common,
+            lat,
+            lng,
+            month,
+            year,
+        } => {
+            let common = common.with_detected_timezone(*lat, *lng).await?;
+            return common.calendar(*lat, *lng, *month, *year);
         }
         Commands::Timings => {
             return Ok(ParsedOptions::Timings);
@@ -280,7 +2419,216 @@ pub async fn parse() -> Result<ParsedOptions> {
                 query: query.to_owned(),
             });
         }
+        Commands::Qibla { lat, lng } => {
+            return Ok(ParsedOptions::Qibla {
+                lat: *lat,
+                lng: *lng,
+            });
+        }
+        Commands::Hijri { date, timezone } => {
+            let tz: Tz = parse_timezone(timezone)
+                .with_context(|| format!("Failed to create timezone with `{}`", timezone))?;
+            let date = datetime::str2date(date, tz)
+                .with_context(|| format!("Failed to create date with `{}`", date))?;
+            return Ok(ParsedOptions::Hijri { date });
+        }
+        Commands::Diff {
+            lat,
+            lng,
+            auth,
+            date,
+            timezone,
+            hanafi,
+            madhab,
+            precision,
+            format,
+        } => {
+            let auths = match auth.as_slice() {
+                [first, second] => [
+                    types::Authority::from_str(first)?,
+                    types::Authority::from_str(second)?,
+                ],
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "--auth must be given exactly twice (e.g. `--auth mwl --auth isna`)!"
+                    ))
+                }
+            };
+            let timezone = if timezone.to_lowercase() == AUTO_TIMEZONE {
+                detect_timezone(*lat, math::normalize_longitude(*lng)).await?
+            } else {
+                timezone.clone()
+            };
+            let tz: Tz = parse_timezone(&timezone)
+                .with_context(|| format!("Failed to create timezone with `{}`", timezone))?;
+            let date = datetime::str2date(date, tz)
+                .with_context(|| format!("Failed to create date with `{}`", date))?;
+            let format = parsed_format(format)?;
+            return Ok(ParsedOptions::Diff {
+                date,
+                timezone: tz,
+                lat: *lat,
+                lng: *lng,
+                auths,
+                school: parse_school(*hanafi, madhab)?,
+                precision: astro::Precision::from_str(precision)?,
+                format,
+            });
+        }
+        Commands::Info => {
+            return Ok(ParsedOptions::Info);
+        }
+    }
+}
+
+/// Looks up the coordinates of a city with the geocoding API (or its cache)
+///
+/// ### Arguments
+/// * `city` - The city to look up
+/// * `country` - The country of the city
+/// * `no_cache` - If `true`, ignores cached results. The result of the lookup is still cached.
+/// * `interactive` - If `true`, asks which place to use when several match
+/// * `index` - The position of the place to use when several match, starting at 1
+#[cfg(feature = "network")]
+async fn geocode_location(
+    city: &str,
+    country: &str,
+    no_cache: bool,
+    interactive: bool,
+    index: Option<u32>,
+) -> Result<(f64, f64)> {
+    let cache = api::GeocodeCache::default_path().map(|path| api::GeocodeCache::new(&path));
+    // Choosing between matches needs the full list, not the cached choice
+    let choosing = interactive || index.is_some();
+    if let Some((lat, lng)) = cache
+        .as_ref()
+        .filter(|_| !no_cache && !choosing)
+        .and_then(|c| c.get(city, country))
+    {
+        return Ok((lat, lng));
+    }
+
+    let matches = api::geocode(city, country).await?;
+    let chosen = if interactive {
+        prompt_match(&matches)?
+    } else {
+        select_match(&matches, index.unwrap_or(1) as usize)?
+    };
+    if matches.len() > 1 && !choosing {
+        log::warn(format!(
+            "{} places match city = `{}` and country = `{}`, using `{}`. Use --interactive or --index to choose another",
+            matches.len(),
+            city,
+            country,
+            chosen.display_name
+        ));
+    }
+    let (lat, lng) = (chosen.lat, chosen.lng);
+
+    if let Some(cache) = cache {
+        if let Err(e) = cache.insert(city, country, lat, lng) {
+            log::warn(format!("{:#}", e));
+        }
+    }
+
+    return Ok((lat, lng));
+}
+
+#[cfg(not(feature = "network"))]
+async fn geocode_location(
+    city: &str,
+    country: &str,
+    _no_cache: bool,
+    _interactive: bool,
+    _index: Option<u32>,
+) -> Result<(f64, f64)> {
+    return Err(anyhow::anyhow!(
+        "city = `{}` and country = `{}` is not a bundled city, looking it up requires salah to be built with the `network` feature!",
+        city,
+        country
+    ));
+}
+
+/// Renders the places matching a city and country as a numbered list (starting at 1), e.g. for `--interactive`
+#[cfg(feature = "network")]
+pub fn render_matches(matches: &[api::GeocodeMatch]) -> String {
+    return matches
+        .iter()
+        .enumerate()
+        .map(|(i, m)| format!("{}. {} ({:.4}, {:.4})", i + 1, m.display_name, m.lat, m.lng))
+        .collect::<Vec<String>>()
+        .join("\n");
+}
+
+/// Returns the matching place at a position of `render_matches`
+///
+/// ### Arguments
+/// * `matches` - The places matching a city and country
+/// * `index` - The position of the place, starting at 1
+#[cfg(feature = "network")]
+pub fn select_match(matches: &[api::GeocodeMatch], index: usize) -> Result<&api::GeocodeMatch> {
+    return index
+        .checked_sub(1)
+        .and_then(|i| matches.get(i))
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "index = `{}` is out of range, there are {} matching place(s)!",
+                index,
+                matches.len()
+            )
+        });
+}
+
+/// Lists the matching places on stderr and reads the chosen position from stdin, without asking if there is only one
+#[cfg(feature = "network")]
+fn prompt_match(matches: &[api::GeocodeMatch]) -> Result<&api::GeocodeMatch> {
+    if matches.len() == 1 {
+        return select_match(matches, 1);
     }
+    eprintln!("{}", render_matches(matches));
+    eprint!("Choose a place [1-{}]: ", matches.len());
+    let mut answer = String::new();
+    std::io::stdin()
+        .read_line(&mut answer)
+        .with_context(|| "Failed to read the chosen place")?;
+    let index = answer
+        .trim()
+        .parse::<usize>()
+        .with_context(|| format!("`{}` is not a number", answer.trim()))?;
+    return select_match(matches, index);
+}
+
+/// Looks up the place name for the coordinates, warning instead of failing if it can't be found
+#[cfg(feature = "network")]
+async fn resolve_place(lat: f64, lng: f64) -> Option<String> {
+    return match api::reverse_geocode(lat, lng).await {
+        Ok(place) => Some(place),
+        Err(e) => {
+            log::warn(format!("{:#}", e));
+            None
+        }
+    };
+}
+
+/// Looks up the timezone name of the coordinates
+#[cfg(feature = "network")]
+async fn detect_timezone(lat: f64, lng: f64) -> Result<String> {
+    return api::timezone(lat, lng).await.with_context(|| {
+        "Could not detect the timezone, please pass a timezone name with --timezone"
+    });
+}
+
+#[cfg(not(feature = "network"))]
+async fn detect_timezone(_lat: f64, _lng: f64) -> Result<String> {
+    return Err(anyhow::anyhow!(
+        "--timezone auto requires salah to be built with the `network` feature, please pass a timezone name instead!"
+    ));
+}
+
+#[cfg(not(feature = "network"))]
+async fn resolve_place(_lat: f64, _lng: f64) -> Option<String> {
+    log::warn("--resolve requires salah to be built with the `network` feature");
+    return None;
 }
 
 pub fn stdout_writer() -> std::io::BufWriter<std::io::StdoutLock<'static>> {
@@ -289,11 +2637,742 @@ pub fn stdout_writer() -> std::io::BufWriter<std::io::StdoutLock<'static>> {
     return writer;
 }
 
+/// Returns a writer to the file at `path` (creating its parent directories), or to stdout if `None`
+///
+/// ### Arguments
+/// * `path` - The file to write to, it is truncated if it exists
+pub fn output_writer(path: Option<&Path>) -> Result<Box<dyn Write>> {
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(Box::new(stdout_writer())),
+    };
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory `{}`", parent.display()))?;
+    }
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create output file `{}`", path.display()))?;
+    return Ok(Box::new(std::io::BufWriter::new(file)));
+}
+
+/// Writes the rendered output to the file at `path`, or to stdout if `None`
+///
+/// ### Arguments
+/// * `path` - The file to write to, it is truncated if it exists
+/// * `content` - The rendered output
+pub fn write_output(path: Option<&Path>, content: &str) -> Result<()> {
+    let mut writer = output_writer(path)?;
+    let target = path.map_or(String::from("stdout"), |p| format!("`{}`", p.display()));
+    writer
+        .write_all(content.as_bytes())
+        .and_then(|_| writer.flush())
+        .with_context(|| format!("Failed to write output to {}", target))?;
+    return Ok(());
+}
+
+/// Renders each timing on its own line as `<name> <time>`.
+/// Times are formatted with their full date, so `format` can include date specifiers (e.g. `%Y-%m-%d`).
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times
+/// * `timings` - The timings to render, in order
+/// * `format` - strftime format string for the times
+/// * `snap` - Grid the times are snapped to
+/// * `locale` - Language of the timing names
+pub fn render_timings(
+    pt: &PrayerTimes,
+    timings: &[types::Timing],
+    format: &str,
+    snap: &Snap,
+    locale: &Locale,
+) -> String {
+    let date = pt.date();
+    let offset = pt.utc_offset();
+    let times = snap.apply_times(date, &pt.all_checked());
+    let lines: Vec<String> = timings
+        .iter()
+        .map(|timing| {
+            let time = times.pretty(date, &offset, &[*timing], format, false, POLAR_NA);
+            return format!("{} {}", locale.label(timing), time);
+        })
+        .collect();
+    return lines.join("\n");
+}
+
+/// Renders the timings as a table with aligned columns, one timing per row.
+/// When `now` is on the date, the next upcoming timing is marked with `>` and, with `color`, highlighted in bold green.
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times
+/// * `timings` - The timings to render, in order
+/// * `now` - The current local date and time, `None` to skip the highlight
+/// * `format` - strftime format string for the times
+/// * `snap` - Grid the times are snapped to
+/// * `locale` - Language of the timing names
+/// * `color` - Whether the next timing is also colored, `false` keeps just the `>` marker (e.g. when not in a terminal)
+pub fn render_table(
+    pt: &PrayerTimes,
+    timings: &[types::Timing],
+    now: Option<NaiveDateTime>,
+    format: &str,
+    snap: &Snap,
+    locale: &Locale,
+    color: bool,
+) -> String {
+    let checked = pt.all_checked();
+    let width = timings
+        .iter()
+        .map(|timing| locale.label(timing).chars().count())
+        .max()
+        .unwrap_or(0);
+    let next = now
+        .filter(|now| now.date() == pt.date())
+        .and_then(|now| {
+            return timings
+                .iter()
+                .filter(|timing| checked.get(timing).is_some())
+                .map(|timing| (*timing, pt.datetime(timing).naive_local()))
+                .filter(|(_, datetime)| *datetime > now)
+                .min_by_key(|(_, datetime)| *datetime);
+        })
+        .map(|(timing, _)| timing);
+
+    let lines: Vec<String> = timings
+        .iter()
+        .map(|timing| {
+            let time = match checked.get(timing) {
+                Some(_) => snap.apply(pt.datetime(timing)).format(format).to_string(),
+                None => String::from(POLAR_NA),
+            };
+            let line = format!("{:<width$}  {}", locale.label(timing), time, width = width);
+            if Some(*timing) == next && color {
+                return format!("> {}", line.green().bold());
+            }
+            if Some(*timing) == next {
+                return format!("> {}", line);
+            }
+            return format!("  {}", line);
+        })
+        .collect();
+    return lines.join("\n");
+}
+
+/// Renders the half of the night as `half-night <time>`, snapped to the grid of `snap`.
+/// `POLAR_NA` when the sun never sets or never reaches the fajr angle.
+pub fn render_half_night(pt: &PrayerTimes, format: &str, snap: &Snap) -> String {
+    let time = match pt.try_half_night_datetime() {
+        Ok(datetime) => snap.apply(datetime).format(format).to_string(),
+        Err(_) => String::from(POLAR_NA),
+    };
+    return format!("half-night {}", time);
+}
+
+/// Renders the calculation parameters actually used, one `<name> <value>` per line: the authority, the fajr and isha
+/// angles after any overrides, the madhab with its shadow factor, the high latitude rule and the offsets.
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times
+pub fn render_parameters(pt: &PrayerTimes) -> String {
+    let params = pt.parameters();
+    let angles = pt.angles();
+    let minutes = |d: std::time::Duration| d.as_secs() / 60;
+
+    let mut lines: Vec<String> = vec![format!("authority {}", params.authority.to_str())];
+    lines.push(format!("fajr-angle {}°", angles.fajr));
+    match (angles.isha, angles.isha_minutes) {
+        (Some(angle), _) => lines.push(format!("isha-angle {}°", angle)),
+        (None, Some(m)) => lines.push(format!("isha-interval {} min after maghrib", m)),
+        (None, None) => {}
+    }
+    lines.push(format!(
+        "madhab {} (shadow factor {})",
+        params.school.to_str(),
+        params.school.shadow_length()
+    ));
+    lines.push(match params.night_fraction_clamp {
+        Some(fraction) => format!("high-latitude {:.3} of the night", fraction),
+        None => String::from("high-latitude none"),
+    });
+
+    let mut offsets: Vec<String> = vec![];
+    if !pt.dhuhr_offset().is_zero() {
+        offsets.push(format!(
+            "dhuhr +{} min after noon",
+            minutes(pt.dhuhr_offset())
+        ));
+    }
+    if !pt.maghrib_delay().is_zero() {
+        offsets.push(format!(
+            "maghrib +{} min after sunset",
+            minutes(pt.maghrib_delay())
+        ));
+    }
+    for timing in types::Timing::list() {
+        let adjustment = params.adjustments.minutes(&timing);
+        if adjustment != 0 {
+            offsets.push(format!("{} {:+} min", timing.to_str(), adjustment));
+        }
+    }
+    if offsets.is_empty() {
+        offsets.push(String::from("none"));
+    }
+    lines.push(format!("offsets {}", offsets.join(", ")));
+    return lines.join("\n");
+}
+
+/// Formats a countdown as `HH:MM:SS`
+fn format_countdown(remaining: Duration) -> String {
+    let seconds = remaining.num_seconds().max(0);
+    return format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    );
+}
+
+/// Renders the fardh timings with the next prayer highlighted, followed by a countdown to it.
+/// After isha, the countdown is to the next day's fajr.
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times, for the date of `now`
+/// * `now` - The local date and time to count down from
+/// * `format` - strftime format string for the times
+pub fn render_watch(pt: &PrayerTimes, now: NaiveDateTime, format: &str) -> String {
+    let (next, _) = pt.next_prayer(now.time());
+    let remaining = pt.time_until_next(now);
+    let tomorrow = (now + remaining).date() > now.date();
+
+    let mut lines: Vec<String> = vec![now.format("%Y-%m-%d %H:%M:%S").to_string(), String::new()];
+    for timing in types::Timing::fardh_list() {
+        let time = match pt.try_datetime(&timing) {
+            Ok(datetime) => datetime.format(format).to_string(),
+            Err(_) => String::from(POLAR_NA),
+        };
+        let line = format!("{:<8}{}", timing.to_str(), time);
+        if timing == next && !tomorrow {
+            lines.push(format!("> {}", line.green().bold()));
+        } else {
+            lines.push(format!("  {}", line));
+        }
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "{} until {}{}",
+        format_countdown(remaining).bold(),
+        next.to_str(),
+        if tomorrow { " (tomorrow)" } else { "" }
+    ));
+    return lines.join("\n");
+}
+
+/// Renders the next fardh prayer after the current local time on one line, e.g. `Maghrib in 1h 23m (18:45)`.
+/// After isha, the next prayer is tomorrow's fajr.
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times, its date is replaced by the current local date
+/// * `timezone` - The timezone used to determine the local date and time
+/// * `clock` - The source of the current time
+/// * `format` - strftime format string for the time
+pub fn render_next(
+    pt: &PrayerTimes,
+    timezone: &Tz,
+    clock: &dyn datetime::Clock,
+    format: &str,
+) -> String {
+    let now = clock.now_utc().with_timezone(timezone).naive_local();
+    let pt = pt.clone().with_date(&now.date());
+    let (next, _) = pt.next_prayer(now.time());
+    let at = now + pt.time_until_next(now);
+
+    // Rounded up, so the countdown never reads 0m before the prayer
+    let minutes = ((at - now).num_seconds().max(0) + 59) / 60;
+    return format!(
+        "{} in {} ({})",
+        capitalize(next.to_str()),
+        render_minutes(minutes),
+        at.format(format)
+    );
+}
+
+/// Renders a number of minutes as `1h 23m`, or `23m` under an hour
+fn render_minutes(minutes: i64) -> String {
+    if minutes >= 60 {
+        return format!("{}h {}m", minutes / 60, minutes % 60);
+    }
+    return format!("{}m", minutes);
+}
+
+/// Renders each timing on its own line like `render_timings`, annotated with the time since or until it
+/// relative to the current local time, e.g. `fajr 06:03 (7h 57m ago)` or `isha 19:01 (in 5h 1m)`.
+/// There are no annotations when the date is not today.
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times
+/// * `timings` - The timings to render, in order
+/// * `timezone` - The timezone used to determine the local date and time
+/// * `clock` - The source of the current time
+/// * `format` - strftime format string for the times
+/// * `snap` - Grid the times are snapped to
+/// * `locale` - Language of the timing names
+pub fn render_compare_now(
+    pt: &PrayerTimes,
+    timings: &[types::Timing],
+    timezone: &Tz,
+    clock: &dyn datetime::Clock,
+    format: &str,
+    snap: &Snap,
+    locale: &Locale,
+) -> String {
+    let rendered = render_timings(pt, timings, format, snap, locale);
+    let now = clock.now_utc().with_timezone(timezone).naive_local();
+    if now.date() != pt.date() {
+        return rendered;
+    }
+    let checked = pt.all_checked();
+    let lines: Vec<String> = rendered
+        .lines()
+        .zip(timings)
+        .map(|(line, timing)| {
+            if checked.get(timing).is_none() {
+                return line.to_owned();
+            }
+            let seconds = (snap.apply(pt.datetime(timing)).naive_local() - now).num_seconds();
+            // Rounded away from now, so a prayer is only `now` within its minute
+            let minutes = (seconds.abs() + 59) / 60;
+            let relative = if minutes == 0 {
+                String::from("now")
+            } else if seconds > 0 {
+                format!("in {}", render_minutes(minutes))
+            } else {
+                format!("{} ago", render_minutes(minutes))
+            };
+            return format!("{} ({})", line, relative);
+        })
+        .collect();
+    return lines.join("\n");
+}
+
+/// Redraws the next prayer countdown every second until Ctrl-C.
+/// The timings are recomputed when the local date changes.
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times
+/// * `timezone` - The timezone used to determine the local date and time
+/// * `format` - strftime format string for the times
+pub async fn watch(pt: &PrayerTimes, timezone: &Tz, format: &str) -> Result<()> {
+    const CLEAR_SCREEN: &[u8] = b"\x1B[2J\x1B[1;1H";
+    let mut pt = pt.clone();
+    loop {
+        let now = Utc::now().with_timezone(timezone).naive_local();
+        if now.date() != pt.date() {
+            pt = pt.with_date(&now.date());
+        }
+
+        {
+            let mut writer = stdout_writer();
+            writer.write_all(CLEAR_SCREEN)?;
+            writer.write_all(render_watch(&pt, now, format).as_bytes())?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
+        }
+
+        tokio::select! {
+            signal = tokio::signal::ctrl_c() => {
+                signal.with_context(|| "Failed to listen for Ctrl-C")?;
+                stdout_writer().write_all(b"\n")?;
+                return Ok(());
+            }
+            _ = tokio::time::sleep(std::time::Duration::from_secs(1)) => {}
+        }
+    }
+}
+
+/// Formatted timings in the requested order, serialized as a JSON object keyed by the timing name
+/// and followed by a `meta` object. Timings with no solution are `null`.
+struct JsonTimings(Vec<(String, Option<String>)>);
+
+/// Build information added to the JSON output, for bug reports
+#[derive(Serialize)]
+struct JsonMeta {
+    version: &'static str,
+}
+
+impl Serialize for JsonTimings {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len() + 1))?;
+        for (name, time) in &self.0 {
+            map.serialize_entry(name, time)?;
+        }
+        map.serialize_entry(
+            "meta",
+            &JsonMeta {
+                version: crate::version(),
+            },
+        )?;
+        return map.end();
+    }
+}
+
+/// Renders the timings as a JSON object of `<name>: <time>`, in the order they are given, and a `meta` object
+/// with the version. Timings with no solution (e.g. the midnight sun) are `null`.
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times
+/// * `timings` - The timings to render, in order
+/// * `half_night` - If `true`, the half of the night is added as `half-night`
+/// * `format` - strftime format string for the times
+/// * `snap` - Grid the times are snapped to
+pub fn render_json(
+    pt: &PrayerTimes,
+    timings: &[types::Timing],
+    half_night: bool,
+    format: &str,
+    snap: &Snap,
+) -> String {
+    let formatted = |datetime: Result<DateTime<chrono::FixedOffset>, SalahError>| {
+        return datetime
+            .ok()
+            .map(|datetime| snap.apply(datetime).format(format).to_string());
+    };
+    let mut entries: Vec<(String, Option<String>)> = timings
+        .iter()
+        .map(|timing| {
+            (
+                timing.to_str().to_owned(),
+                formatted(pt.try_datetime(timing)),
+            )
+        })
+        .collect();
+    if half_night {
+        entries.push((
+            String::from("half-night"),
+            formatted(pt.try_half_night_datetime()),
+        ));
+    }
+    return serde_json::to_string(&JsonTimings(entries)).expect("Failed to serialize timings");
+}
+
+/// Renders an iCalendar (RFC 5545) with one event per timing for each day, starting at the configured date.
+/// Event times are in UTC, so calendars don't need the timezone's definition. Timings with no solution on a day
+/// (e.g. the midnight sun) have no event.
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times, its date is the first day
+/// * `timings` - The timings to add for every day, fardh timings if empty
+/// * `days` - The number of days to include
+pub fn render_ics(pt: &PrayerTimes, timings: &[types::Timing], days: u32) -> String {
+    let timings: Vec<types::Timing> = if timings.is_empty() {
+        types::Timing::fardh_list().into_iter().collect()
+    } else {
+        timings.to_vec()
+    };
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+
+    let mut lines: Vec<String> = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        format!("PRODID:-//salah//salah {}//EN", crate::version()),
+        String::from("CALSCALE:GREGORIAN"),
+    ];
+    let mut date = pt.date();
+    for _ in 0..days {
+        let day = pt.clone().with_date(&date);
+        for timing in &timings {
+            let Ok(start) = day.try_datetime(timing) else {
+                continue;
+            };
+            let start = start.with_timezone(&Utc);
+            lines.push(String::from("BEGIN:VEVENT"));
+            lines.push(format!(
+                "UID:{}-{}@salah",
+                date.format("%Y%m%d"),
+                timing.to_str()
+            ));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!("DTSTART:{}", start.format("%Y%m%dT%H%M%SZ")));
+            lines.push(format!("SUMMARY:{}", capitalize(timing.to_str())));
+            lines.push(String::from("END:VEVENT"));
+        }
+        date = date.succ_opt().expect("RangeError @ render_ics");
+    }
+    lines.push(String::from("END:VCALENDAR"));
+
+    // Content lines are delimited by CRLF
+    return lines.join("\r\n") + "\r\n";
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    return match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    };
+}
+
+/// Renders the Hijri date as `hijri <day> <month> <year> AH`
+pub fn render_hijri(date: NaiveDate) -> String {
+    return format!("hijri {}", Hijri::from_gregorian(date));
+}
+
+/// Renders the Qibla bearing as `qibla <degrees>°` (clockwise from true north)
+pub fn render_qibla(lat: f64, lng: f64) -> String {
+    return format!("qibla {:.2}°", qibla::qibla(lat, lng));
+}
+
+/// Returns the difference in minutes of every timing between two sets of times for the same date (`second - first`).
+/// Timings after midnight are compared on the next day, so a difference never wraps around the day.
+/// `None` when the timing has no solution in either set (e.g. fajr in a high latitude summer).
+///
+/// ### Arguments
+/// * `date` - The date the times were calculated for
+/// * `first` - The times to compare against
+/// * `second` - The times to compare
+pub fn diff_minutes(
+    date: NaiveDate,
+    first: &CheckedTimes,
+    second: &CheckedTimes,
+) -> Vec<(types::Timing, Option<i64>)> {
+    return types::Timing::list()
+        .into_iter()
+        .map(|timing| {
+            let minutes = second
+                .datetime(date, &timing)
+                .zip(first.datetime(date, &timing))
+                .map(|(second, first)| {
+                    return ((second - first).num_seconds() as f64 / 60_f64).round() as i64;
+                });
+            (timing, minutes)
+        })
+        .collect();
+}
+
+/// Renders every timing under two calculation authorities side by side, with the difference in minutes
+/// (`second - first`). Usually only fajr and isha (and the timings derived from them) differ.
+/// Timings with no solution are `POLAR_NA`, as is their difference.
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times (the authority is overridden for each column)
+/// * `first` - The authority to compare against
+/// * `second` - The authority to compare
+/// * `format` - strftime format string for the times
+pub fn render_diff(
+    pt: &PrayerTimes,
+    first: &types::Authority,
+    second: &types::Authority,
+    format: &str,
+) -> String {
+    let first_times = pt.clone().with_authority(first).all_checked();
+    let second_times = pt.clone().with_authority(second).all_checked();
+    let formatted = |time: Option<chrono::NaiveTime>| {
+        return time
+            .map(|time| time.format(format).to_string())
+            .unwrap_or_else(|| String::from(POLAR_NA));
+    };
+
+    let mut table = format!(
+        "{:<name_width$}{:<width$}{:<width$}{}",
+        "Timing",
+        first.to_str(),
+        second.to_str(),
+        "Diff",
+        name_width = 12,
+        width = 12
+    );
+    for (timing, minutes) in diff_minutes(pt.date(), &first_times, &second_times) {
+        let diff = match minutes {
+            None => String::from(POLAR_NA),
+            Some(0) => String::from("0"),
+            Some(minutes) => format!("{:+} min", minutes),
+        };
+        table.push_str(
+            format!(
+                "\n{:<name_width$}{:<width$}{:<width$}{}",
+                timing.to_str(),
+                formatted(first_times.get(&timing)),
+                formatted(second_times.get(&timing)),
+                diff,
+                name_width = 12,
+                width = 12
+            )
+            .as_str(),
+        );
+    }
+    return table;
+}
+
+/// Renders fajr and isha for every calculation authority, with a footnote for asr in both schools.
+/// Timings with no solution are `POLAR_NA`.
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times (the authority and school are overridden per row)
+/// * `format` - strftime format string for the times
+pub fn render_tabular(pt: &PrayerTimes, format: &str) -> String {
+    let find = |pt: &PrayerTimes, timing: types::Timing| {
+        return match pt.try_timing(&timing) {
+            Ok(time) => time.format(format).to_string(),
+            Err(_) => String::from(POLAR_NA),
+        };
+    };
+
+    let mut table = format!(
+        "{:<name_width$}{:<width$}{:<width$}{}",
+        "Authority",
+        "Fajr",
+        "Isha",
+        "Note",
+        name_width = 14,
+        width = 12
+    );
+    for auth in types::Authority::list() {
+        let authority = pt.clone().with_authority(&auth);
+        table.push_str(
+            format!(
+                "\n{:<name_width$}{:<width$}{:<width$}{}",
+                auth.to_str(),
+                find(&authority, types::Timing::Fajr),
+                find(&authority, types::Timing::Isha),
+                auth.desc(),
+                name_width = 14,
+                width = 12
+            )
+            .as_str(),
+        );
+    }
+
+    let shafi = pt.clone().with_school(&types::School::Shafi);
+    let hanafi = pt.clone().with_school(&types::School::Hanafi);
+    table.push_str(
+        format!(
+            "\n\n* Asr: {} (Shafi, Maliki, Hanbali), {} (Hanafi)",
+            find(&shafi, types::Timing::Asr),
+            find(&hanafi, types::Timing::Asr)
+        )
+        .as_str(),
+    );
+    return table;
+}
+
+/// Renders a table of the fardh timings with one row for every day of the month
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times (the date is overridden per row)
+/// * `month` - Any date in the month to render
+/// * `format` - strftime format string for the times
+/// * `snap` - Grid the times are snapped to
+pub fn render_calendar(pt: &PrayerTimes, month: NaiveDate, format: &str, snap: &Snap) -> String {
+    let (first, last) = month_bounds(month);
+    let timings = types::Timing::fardh_list();
+
+    let rows: Vec<(NaiveDate, Vec<String>)> = first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| {
+            let day = pt.clone().with_date(&date);
+            let cells = timings
+                .iter()
+                .map(|timing| polar_cell(&day, timing, format, snap))
+                .collect();
+            (date, cells)
+        })
+        .collect();
+    // Wide enough for the longest time, so long formats (e.g. with dates) stay aligned
+    let width = rows
+        .iter()
+        .flat_map(|(_, cells)| cells.iter().map(|cell| cell.chars().count() + 2))
+        .max()
+        .unwrap_or_default()
+        .max(10);
+
+    let mut header = format!("{:<width$}", "Date", width = 12);
+    for timing in &timings {
+        header.push_str(format!("{:<width$}", capitalize(timing.to_str()), width = width).as_str());
+    }
+    let mut lines: Vec<String> = vec![header.trim_end().to_owned()];
+    for (date, cells) in rows {
+        let mut line = format!("{:<width$}", date.format("%Y-%m-%d"), width = 12);
+        for cell in cells {
+            line.push_str(format!("{:<width$}", cell, width = width).as_str());
+        }
+        lines.push(line.trim_end().to_owned());
+    }
+    return lines.join("\n");
+}
+
+/// Returns the first and last dates of the month containing `date`
+pub fn month_bounds(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let first = date.with_day(1).expect("RangeError @ month_bounds");
+    let last = first
+        .checked_add_months(chrono::Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .expect("RangeError @ month_bounds");
+    return (first, last);
+}
+
+/// Timings included in every CSV row, in column order
+const CSV_TIMINGS: [types::Timing; 7] = [
+    types::Timing::Fajr,
+    types::Timing::Sunrise,
+    types::Timing::Dhuhr,
+    types::Timing::Asr,
+    types::Timing::Maghrib,
+    types::Timing::Isha,
+    types::Timing::Midnight,
+];
+
+/// Renders the timings as CSV with a header row, followed by one row per date from `start` to `end` (inclusive).
+///
+/// ### Arguments
+/// * `pt` - The configured prayer times (the date is overridden per row)
+/// * `start` - The first date
+/// * `end` - The last date
+/// * `format` - strftime format string for the times
+/// * `snap` - Grid the times are snapped to
+pub fn render_csv(
+    pt: &PrayerTimes,
+    start: NaiveDate,
+    end: NaiveDate,
+    format: &str,
+    snap: &Snap,
+) -> String {
+    let mut header: Vec<&str> = vec!["date"];
+    header.extend(CSV_TIMINGS.iter().map(|timing| timing.to_str()));
+
+    let mut lines: Vec<String> = vec![header.join(",")];
+    for date in start.iter_days().take_while(|date| *date <= end) {
+        let day = pt.clone().with_date(&date);
+        let mut row: Vec<String> = vec![date.format("%Y-%m-%d").to_string()];
+        row.extend(
+            CSV_TIMINGS
+                .iter()
+                .map(|timing| csv_field(&polar_cell(&day, timing, format, snap))),
+        );
+        lines.push(row.join(","));
+    }
+    return lines.join("\n");
+}
+
+/// Formats a timing with its full date for a table cell, `POLAR_NA` if it has no solution
+fn polar_cell(pt: &PrayerTimes, timing: &types::Timing, format: &str, snap: &Snap) -> String {
+    return match pt.try_datetime(timing) {
+        Ok(datetime) => snap.apply(datetime).format(format).to_string(),
+        Err(_) => String::from(POLAR_NA),
+    };
+}
+
+/// Quotes a CSV field if it contains a comma, quote or newline (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        return format!("\"{}\"", value.replace('"', "\"\""));
+    }
+    return value.to_owned();
+}
+
 pub fn display_timings() {
     let mut writer = stdout_writer();
 
     writer
-        .write(
+        .write_all(
             format!(
                 "{}: {}",
                 "Usage".underline(),
@@ -302,30 +3381,41 @@ pub fn display_timings() {
             .as_bytes(),
         )
         .unwrap();
-    writer.write(b"\n").unwrap();
+    writer.write_all(b"\n").unwrap();
     writer
-        .write(b"\nThe below can be passed to [TIMINGS]...")
+        .write_all(b"\nThe below can be passed to [TIMINGS]...")
         .unwrap();
-    writer.write(b"\n").unwrap();
+    writer.write_all(b"\n").unwrap();
     writer
-        .write(format!("\n{}:", "Timings".underline()).as_bytes())
+        .write_all(format!("\n{}:", "Timings".underline()).as_bytes())
         .unwrap();
 
     for time in types::Timing::list() {
         writer
-            .write(
+            .write_all(
                 format!(
                     "\n  {:<width$}{:<width$}",
                     time.to_str(),
                     time.desc(),
-                    width = 10
+                    width = 12
                 )
                 .as_bytes(),
             )
             .unwrap();
     }
+    writer
+        .write_all(
+            format!(
+                "\n  {:<width$}{:<width$}",
+                "fardh",
+                "Only the 5 obligatory prayers (fajr, dhuhr, asr, maghrib, isha).",
+                width = 12
+            )
+            .as_bytes(),
+        )
+        .unwrap();
 
-    writer.write(b"\n").unwrap();
+    writer.write_all(b"\n").unwrap();
     writer.flush().unwrap();
 }
 
@@ -333,54 +3423,54 @@ pub fn display_authority() {
     let mut writer = stdout_writer();
 
     writer
-        .write(format!("{}: {}", "Usage".underline(), "--auth <AUTH>").as_bytes())
+        .write_all(format!("{}: {}", "Usage".underline(), "--auth <AUTH>").as_bytes())
         .unwrap();
-    writer.write(b"\n").unwrap();
+    writer.write_all(b"\n").unwrap();
     writer
-        .write(format!("\n{}:", "Explanation".underline()).as_bytes())
+        .write_all(format!("\n{}:", "Explanation".underline()).as_bytes())
         .unwrap();
 
     writer
-        .write(b"\nCalculation authorities are used for the calculation of Fajr and Isha.")
+        .write_all(b"\nCalculation authorities are used for the calculation of Fajr and Isha.")
         .unwrap();
-    writer.write(b"\nThe time for Fajr is described as dawn; when there is fine white line at the horizon.").unwrap();
-    writer.write(b"\nIsha time is described as when the night sky has lost all the light from the sunset.").unwrap();
+    writer.write_all(b"\nThe time for Fajr is described as dawn; when there is fine white line at the horizon.").unwrap();
+    writer.write_all(b"\nIsha time is described as when the night sky has lost all the light from the sunset.").unwrap();
     writer
-        .write(
+        .write_all(
             b"\nAs this is quite ambiguous, the scholars have differed upon the angle that the sun",
         )
         .unwrap();
     writer
-        .write(
+        .write_all(
             b"\nmakes when these two times occur. Each authority has slightly different angles for",
         )
         .unwrap();
     writer
-        .write(b"\nFajr and Isha. Makkah uses a time difference from Maghrib (sunset).")
+        .write_all(b"\nFajr and Isha. Makkah uses a time difference from Maghrib (sunset).")
         .unwrap();
     writer
-        .write(
+        .write_all(
             b"\n\nThe below can be used with the --auth <AUTH> option when calculating timings.\n",
         )
         .unwrap();
     writer
-        .write(format!("\n{}:", "Authorities".underline()).as_bytes())
+        .write_all(format!("\n{}:", "Authorities".underline()).as_bytes())
         .unwrap();
 
     for auth in types::Authority::list() {
         writer
-            .write(
+            .write_all(
                 format!(
                     "\n  {:<width$}{:<width$}",
                     auth.to_str(),
                     format!("{} - {}", auth.desc(), auth.name()),
-                    width = 10
+                    width = 14
                 )
                 .as_bytes(),
             )
             .unwrap();
     }
-    writer.write(b"\n").unwrap();
+    writer.write_all(b"\n").unwrap();
 
     writer.flush().unwrap();
 }
@@ -389,33 +3479,37 @@ pub fn display_timezones(query: &String) {
     let timezones = include_str!("../data/tz.txt");
     let mut writer = stdout_writer();
     writer
-        .write(format!("{}: {}", "Usage".underline(), "-t, --timezone <TIMEZONE>").as_bytes())
+        .write_all(format!("{}: {}", "Usage".underline(), "-t, --timezone <TIMEZONE>").as_bytes())
         .unwrap();
-    writer.write(b"\n").unwrap();
+    writer.write_all(b"\n").unwrap();
     writer
-        .write(b"\nThe below values can be used with the -t, --timezone <TIMEZONE> option.")
+        .write_all(b"\nThe below values can be used with the -t, --timezone <TIMEZONE> option.")
         .unwrap();
     if query == &String::new() {
         writer
-            .write(b"\nOptionally, use salah timezones [QUERY] to search for specific timezones.")
+            .write_all(
+                b"\nOptionally, use salah timezones [QUERY] to search for specific timezones.",
+            )
             .unwrap();
-        writer.write(b"\n").unwrap();
+        writer.write_all(b"\n").unwrap();
         writer
-            .write(format!("\n{}:", "Timezones".underline()).as_bytes())
+            .write_all(format!("\n{}:", "Timezones".underline()).as_bytes())
             .unwrap();
         for line in timezones.lines() {
             if line != "\n" {
-                writer.write(format!("\n  {}", line).as_bytes()).unwrap();
+                writer
+                    .write_all(format!("\n  {}", line).as_bytes())
+                    .unwrap();
             }
         }
     } else {
-        writer.write(b"\n").unwrap();
+        writer.write_all(b"\n").unwrap();
         writer
-            .write(format!("\n{}: `{}`", "Query".underline(), query).as_bytes())
+            .write_all(format!("\n{}: `{}`", "Query".underline(), query).as_bytes())
             .unwrap();
-        writer.write(b"\n").unwrap();
+        writer.write_all(b"\n").unwrap();
         writer
-            .write(format!("\n{}:", "Results".underline()).as_bytes())
+            .write_all(format!("\n{}:", "Results".underline()).as_bytes())
             .unwrap();
 
         let space_separated: Vec<&str> = query.split(" ").collect();
@@ -424,14 +3518,52 @@ pub fn display_timezones(query: &String) {
 
         for line in timezones.lines() {
             if line.to_lowercase().contains(parsed_query.as_str()) {
-                writer.write(format!("\n  {}", line).as_bytes()).unwrap();
+                writer
+                    .write_all(format!("\n  {}", line).as_bytes())
+                    .unwrap();
                 num_found += 1;
             }
         }
         writer
-            .write(format!("\nFound {} result(s)", num_found).as_bytes())
+            .write_all(format!("\nFound {} result(s)", num_found).as_bytes())
             .unwrap();
     }
-    writer.write(b"\n").unwrap();
+    writer.write_all(b"\n").unwrap();
+    writer.flush().unwrap();
+}
+
+pub fn display_info() {
+    let mut writer = stdout_writer();
+    let features = crate::features();
+    let features = if features.is_empty() {
+        String::from("none")
+    } else {
+        features.join(", ")
+    };
+    writer
+        .write_all(format!("salah {} (features: {})", crate::version(), features).as_bytes())
+        .unwrap();
+    writer.write_all(b"\n").unwrap();
+    writer
+        .write_all(format!("\n{}:", "Defaults".underline()).as_bytes())
+        .unwrap();
+
+    let auth = types::Authority::from_str(DEFAULT_AUTHORITY).expect("Invalid default authority!");
+    writer
+        .write_all(
+            format!(
+                "\n  {:<width$}{} ({})",
+                "Authority",
+                auth.to_str(),
+                auth.name(),
+                width = 12
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    writer
+        .write_all(format!("\n  {:<width$}{}", "Timezone", DEFAULT_TIMEZONE, width = 12).as_bytes())
+        .unwrap();
+    writer.write_all(b"\n").unwrap();
     writer.flush().unwrap();
 }
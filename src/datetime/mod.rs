@@ -1,11 +1,155 @@
-use anyhow::{Context, Result};
-use chrono::{DateTime, NaiveDate, NaiveTime, Offset, TimeZone, Timelike, Utc};
-use chrono_tz::Tz;
+use crate::error::SalahError;
+use crate::math;
+use chrono::{
+    DateTime, Duration, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone,
+    Timelike, Utc,
+};
+use chrono_tz::{OffsetComponents, Tz};
+use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
 mod tests {
-    use crate::datetime::{hour2time, time2hour};
-    use chrono::{NaiveTime, Timelike};
+    use crate::datetime::{
+        hour2time, round_time, str2date_from, str2date_with_clock, time2hour, today,
+        tz_offset_for_date, tz_offset_with_clock, FixedClock, Rounding, TimezoneOffset,
+    };
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
+    use chrono_tz::Tz;
+
+    #[test]
+    fn test_from_local_standard() {
+        use crate::datetime::from_local_standard;
+
+        let toronto: Tz = "America/Toronto".parse().expect("Error!");
+        let utc = |local: NaiveDateTime| from_local_standard(toronto, local).naive_utc();
+        let at = |d: u32, m: u32, h: u32, min: u32| {
+            NaiveDate::from_ymd_opt(2024, m, d)
+                .expect("Error!")
+                .and_hms_opt(h, min, 0)
+                .expect("Error!")
+        };
+
+        assert_eq!(utc(at(10, 3, 1, 30)), at(10, 3, 6, 30));
+        // Skipped when the clocks spring forward, read as EST
+        assert_eq!(utc(at(10, 3, 2, 30)), at(10, 3, 7, 30));
+        assert_eq!(utc(at(10, 3, 3, 30)), at(10, 3, 7, 30));
+        // Repeated when the clocks fall back, EST is preferred over EDT
+        assert_eq!(utc(at(3, 11, 1, 30)), at(3, 11, 6, 30));
+        assert_eq!(utc(at(3, 11, 0, 30)), at(3, 11, 4, 30));
+    }
+
+    #[test]
+    fn test_clock() {
+        let toronto: Tz = "America/Toronto".parse().expect("Error!");
+        let kolkata: Tz = "Asia/Kolkata".parse().expect("Error!");
+
+        // 03:00 UTC is still the previous evening in Toronto, but the next morning in Kolkata
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 2, 12, 3, 0, 0).unwrap());
+        let feb = |d| NaiveDate::from_ymd_opt(2024, 2, d).expect("Error!");
+        assert_eq!(today(toronto, &clock), feb(11));
+        assert_eq!(today(kolkata, &clock), feb(12));
+        assert_eq!(TimezoneOffset::Fixed(-5.0).today(&clock), feb(11));
+        assert_eq!(TimezoneOffset::Fixed(5.5).today(&clock), feb(12));
+        assert_eq!(
+            str2date_with_clock("today", toronto, &clock).expect("Error!"),
+            feb(11)
+        );
+        assert_eq!(
+            str2date_with_clock("tomorrow", kolkata, &clock).expect("Error!"),
+            feb(13)
+        );
+        assert_eq!(
+            str2date_with_clock("2024-03-11", toronto, &clock).expect("Error!"),
+            NaiveDate::from_ymd_opt(2024, 3, 11).expect("Error!")
+        );
+
+        assert_eq!(tz_offset_with_clock(toronto, &clock), -5_f64);
+        let summer = FixedClock(Utc.with_ymd_and_hms(2024, 7, 1, 12, 0, 0).unwrap());
+        assert_eq!(tz_offset_with_clock(toronto, &summer), -4_f64);
+    }
+
+    #[test]
+    fn test_suggest_timezones() {
+        use crate::datetime::suggest_timezones;
+
+        let names = |query: &str| {
+            return suggest_timezones(query)
+                .iter()
+                .map(|tz| tz.name().to_string())
+                .collect::<Vec<String>>();
+        };
+        assert_eq!(names("america/toronto"), ["America/Toronto"]);
+        assert_eq!(names("Toronto"), ["America/Toronto"]);
+        assert_eq!(names("America/Torronto"), ["America/Toronto"]);
+        assert_eq!(names("torronto"), ["America/Toronto"]);
+        assert_eq!(names("new york"), ["America/New_York"]);
+        assert!(names("karachi").contains(&String::from("Asia/Karachi")));
+        assert!(suggest_timezones("Not/A/Timezone/At/All").is_empty());
+        assert!(suggest_timezones("").is_empty());
+    }
+
+    #[test]
+    fn test_tz_offset_for_date() {
+        let toronto: Tz = "America/Toronto".parse().expect("Error!");
+        let january = NaiveDate::from_ymd_opt(2024, 1, 15).expect("Error!");
+        let july = NaiveDate::from_ymd_opt(2024, 7, 15).expect("Error!");
+        assert_eq!(tz_offset_for_date(toronto, january), -5_f64);
+        assert_eq!(tz_offset_for_date(toronto, july), -4_f64);
+
+        // DST starts at 02:00 on 2024-03-10, so noon that day is already EDT
+        let transition = NaiveDate::from_ymd_opt(2024, 3, 10).expect("Error!");
+        assert_eq!(tz_offset_for_date(toronto, transition), -4_f64);
+
+        let kolkata: Tz = "Asia/Kolkata".parse().expect("Error!");
+        assert_eq!(tz_offset_for_date(kolkata, july), 5.5_f64);
+    }
+
+    #[test]
+    fn test_str2date_relative() {
+        let today = NaiveDate::from_ymd_opt(2024, 2, 28).expect("Error!");
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).expect("Error!");
+
+        assert_eq!(str2date_from("today", today).expect("Error!"), today);
+        assert_eq!(
+            str2date_from("tomorrow", today).expect("Error!"),
+            date(2024, 2, 29)
+        );
+        assert_eq!(
+            str2date_from("yesterday", today).expect("Error!"),
+            date(2024, 2, 27)
+        );
+        assert_eq!(
+            str2date_from("+3", today).expect("Error!"),
+            date(2024, 3, 2)
+        );
+        assert_eq!(
+            str2date_from("-2", today).expect("Error!"),
+            date(2024, 2, 26)
+        );
+        assert_eq!(str2date_from("+0", today).expect("Error!"), today);
+        assert_eq!(
+            str2date_from("+366", today).expect("Error!"),
+            date(2025, 2, 28)
+        );
+
+        // Absolute dates are unchanged
+        assert_eq!(
+            str2date_from("2024-03-11", today).expect("Error!"),
+            date(2024, 3, 11)
+        );
+
+        for invalid in [
+            "+",
+            "-",
+            "+3d",
+            "+-3",
+            "+99999999999999999999",
+            "+9999999999",
+        ] {
+            assert!(str2date_from(invalid, today).is_err(), "{}", invalid);
+        }
+    }
+
     #[test]
     fn test_time2hour() {
         let time = NaiveTime::from_hms_opt(17, 24, 0).expect("Error!");
@@ -16,11 +160,135 @@ mod tests {
     #[test]
     fn test_hour2time() {
         let hour = 17.4_f64;
-        let time = hour2time(hour, true).expect("Error!");
+        let time = hour2time(hour, Rounding::Minute).expect("Error!");
         assert_eq!(time.hour(), 17);
         assert_eq!(time.minute(), 24);
         assert_eq!(time.second(), 0);
     }
+
+    #[test]
+    fn test_time2hour_round_trip() {
+        // Deterministic pseudo-random times across the day (linear congruential generator)
+        let mut state: u64 = 2024;
+        for _ in 0..10_000 {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let seconds = ((state >> 33) % 86_400) as u32;
+            let time = NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0).expect("Error!");
+            assert_eq!(
+                hour2time(time2hour(time), Rounding::Seconds).expect("Error!"),
+                time
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_time() {
+        let time = NaiveTime::from_hms_milli_opt(17, 24, 29, 600).expect("Error!");
+        let expected = [
+            (Rounding::Seconds, (17, 24, 30)),
+            (Rounding::Minute, (17, 25, 0)),
+            (Rounding::Floor, (17, 24, 0)),
+            (Rounding::Ceil, (17, 25, 0)),
+        ];
+        for (rounding, (h, m, s)) in expected {
+            assert_eq!(
+                round_time(time, rounding),
+                NaiveTime::from_hms_opt(h, m, s).expect("Error!"),
+                "{:?}",
+                rounding
+            );
+        }
+        let late = NaiveTime::from_hms_opt(23, 59, 45).expect("Error!");
+        assert_eq!(round_time(late, Rounding::Minute), NaiveTime::MIN);
+
+        // Floor keeps a fraction of a second below the next minute in its minute
+        let below = NaiveTime::from_hms_milli_opt(17, 24, 59, 600).expect("Error!");
+        let floor = round_time(below, Rounding::Floor);
+        assert_eq!(floor, NaiveTime::from_hms_opt(17, 24, 0).expect("Error!"));
+    }
+
+    #[test]
+    fn test_hour2time_carry() {
+        // 12:59:59.5 (~12.999861) carries all the way into the hour
+        let time = hour2time(12_f64 + 3599.5 / 3600_f64, Rounding::Seconds).expect("Error!");
+        assert_eq!(time, NaiveTime::from_hms_opt(13, 0, 0).expect("Error!"));
+
+        // 12:59:30 rounds up into the hour when rounding to minutes
+        let time = hour2time(12_f64 + 3570_f64 / 3600_f64, Rounding::Minute).expect("Error!");
+        assert_eq!(time, NaiveTime::from_hms_opt(13, 0, 0).expect("Error!"));
+
+        // 23:59:59.9 carries into the next day
+        let time = hour2time(23_f64 + 3599.9 / 3600_f64, Rounding::Seconds).expect("Error!");
+        assert_eq!(time, NaiveTime::from_hms_opt(0, 0, 0).expect("Error!"));
+
+        // 17:24:59.6 only carries into the minute
+        let time = hour2time(17_f64 + 1499.6 / 3600_f64, Rounding::Seconds).expect("Error!");
+        assert_eq!(time, NaiveTime::from_hms_opt(17, 25, 0).expect("Error!"));
+    }
+
+    #[test]
+    fn test_hour2time_rounding() {
+        // 17.4083 hours is 17:24:29.88, which rounds to 17:24:30
+        let hour = 17.4083_f64;
+        let expected = [
+            (Rounding::Seconds, (17, 24, 30)),
+            (Rounding::Minute, (17, 25, 0)),
+            (Rounding::Floor, (17, 24, 0)),
+            (Rounding::Ceil, (17, 25, 0)),
+        ];
+        for (rounding, (h, m, s)) in expected {
+            assert_eq!(
+                hour2time(hour, rounding).expect("Error!"),
+                NaiveTime::from_hms_opt(h, m, s).expect("Error!"),
+                "{:?}",
+                rounding
+            );
+        }
+
+        // Whole minutes are unchanged by every mode
+        for rounding in [Rounding::Floor, Rounding::Ceil] {
+            let time = hour2time(17.4, rounding).expect("Error!");
+            assert_eq!(time, NaiveTime::from_hms_opt(17, 24, 0).expect("Error!"));
+        }
+
+        // Floor and ceil apply to the exact time, not the time rounded to the second
+        let hour = 17_f64 + 1499.6 / 3600_f64;
+        let floor = hour2time(hour, Rounding::Floor).expect("Error!");
+        assert_eq!(floor, NaiveTime::from_hms_opt(17, 24, 0).expect("Error!"));
+        let hour = 17_f64 + 1440.3 / 3600_f64;
+        let ceil = hour2time(hour, Rounding::Ceil).expect("Error!");
+        assert_eq!(ceil, NaiveTime::from_hms_opt(17, 25, 0).expect("Error!"));
+        assert_eq!(Rounding::default(), Rounding::Minute);
+    }
+
+    #[test]
+    fn test_hour2time_wraparound() {
+        let expected = [
+            (25.0, (1, 0, 0)),
+            (-0.5, (23, 30, 0)),
+            (-1.2, (22, 48, 0)),
+            (24.5, (0, 30, 0)),
+            (23.9999, (0, 0, 0)),
+            (48.25, (0, 15, 0)),
+        ];
+        for (hour, (h, m, s)) in expected {
+            for rounding in [Rounding::Seconds, Rounding::Minute] {
+                assert_eq!(
+                    hour2time(hour, rounding).expect("Error!"),
+                    NaiveTime::from_hms_opt(h, m, s).expect("Error!"),
+                    "{} {:?}",
+                    hour,
+                    rounding
+                );
+            }
+        }
+        assert_eq!(
+            hour2time(23.99, Rounding::Floor).expect("Error!"),
+            NaiveTime::from_hms_opt(23, 59, 0).expect("Error!")
+        );
+    }
 }
 
 /// Returns the Timezone offset from UTC in hours
@@ -28,56 +296,203 @@ mod tests {
 /// ### Arguments
 /// * `tz` - A `chrono_tz` Tz object
 pub fn tz_offset(tz: Tz) -> f64 {
-    let dt = tz.from_utc_datetime(&Utc::now().naive_utc());
+    return tz_offset_with_clock(tz, &SystemClock);
+}
+
+/// Returns the Timezone offset from UTC in hours at the clock's current time
+///
+/// ### Arguments
+/// * `tz` - A `chrono_tz` Tz object
+/// * `clock` - The source of the current time
+pub fn tz_offset_with_clock(tz: Tz, clock: &dyn Clock) -> f64 {
+    let dt = clock.now_utc().with_timezone(&tz);
     return get_tz_offset(dt);
 }
 
-fn get_tz_offset<Tz: TimeZone>(datetime: DateTime<Tz>) -> f64 {
-    return (datetime.offset().fix().local_minus_utc() as f64) / 3600.0;
+/// Returns today's date in a timezone at the clock's current time
+///
+/// ### Arguments
+/// * `tz` - A `chrono_tz` Tz object
+/// * `clock` - The source of the current time
+pub fn today(tz: Tz, clock: &dyn Clock) -> NaiveDate {
+    return clock.now_utc().with_timezone(&tz).date_naive();
 }
 
-/// Creates NaiveTime object from hour value
+/// Returns the Timezone offset from UTC in hours in effect on a date (at local noon),
+/// so daylight savings is applied for that date rather than for today
 ///
 /// ### Arguments
-/// * `hour` - A fractional value representing the hour of the day (0-24)
-/// * `round_seconds` - if `true`, minutes will be rounded by the seconds value and seconds will always be zero
-pub fn hour2time(hour: f64, round_seconds: bool) -> Result<NaiveTime> {
-    let mut h = hour.trunc() as u32;
-    let d = (hour - hour.trunc()) * 60.0;
-    let mut m = d as u32;
-    let mut s = ((d - d.trunc()) * 60.0).round() as u32;
+/// * `tz` - A `chrono_tz` Tz object
+/// * `date` - The date to get the offset for
+pub fn tz_offset_for_date(tz: Tz, date: NaiveDate) -> f64 {
+    let noon = date.and_hms_opt(12, 0, 0).expect("Invalid noon time!");
+    let dt = match tz.from_local_datetime(&noon).earliest() {
+        Some(dt) => dt,
+        // Noon skipped by a transition, fall back to noon UTC
+        None => tz.from_utc_datetime(&noon),
+    };
+    return get_tz_offset(dt);
+}
+
+/// Source of the current time, so time dependent behavior can be tested with a fixed clock
+pub trait Clock {
+    /// Returns the current date and time in UTC
+    fn now_utc(&self) -> DateTime<Utc>;
+}
+
+/// The system clock
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SystemClock;
 
-    if h == 24 {
-        h = 0;
+impl Clock for SystemClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        return Utc::now();
     }
+}
+
+/// A clock that is always at the same instant
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FixedClock(pub DateTime<Utc>);
 
-    if s >= 60 {
-        s -= 60;
-        m += 1;
+impl Clock for FixedClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        return self.0;
     }
+}
+
+/// The timezone the timings are output for, either a named timezone or a fixed offset from UTC
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TimezoneOffset {
+    /// A timezone from the timezone database, daylight savings is applied for each date
+    Named(Tz),
 
-    if m >= 60 {
-        m -= 60;
+    /// A fixed offset from UTC in hours (e.g. `-5.0` for EST, `5.5` for IST).
+    /// No timezone database lookup is needed, but daylight savings is never applied.
+    Fixed(f64),
+}
+
+impl TimezoneOffset {
+    /// Returns today's date in the timezone at the clock's current time
+    ///
+    /// ### Arguments
+    /// * `clock` - The source of the current time
+    pub fn today(&self, clock: &dyn Clock) -> NaiveDate {
+        return match self {
+            Self::Named(tz) => today(*tz, clock),
+            Self::Fixed(hours) => {
+                let offset = Duration::seconds((hours * 3600_f64).round() as i64);
+                (clock.now_utc() + offset).date_naive()
+            }
+        };
     }
 
-    if round_seconds {
-        if s >= 30 {
-            m += 1;
-        }
-        s = 0;
+    /// Returns the offset from UTC in hours in effect on a date
+    ///
+    /// ### Arguments
+    /// * `date` - The date to get the offset for
+    pub fn hours_for_date(&self, date: NaiveDate) -> f64 {
+        return match self {
+            Self::Named(tz) => tz_offset_for_date(*tz, date),
+            Self::Fixed(hours) => *hours,
+        };
     }
-    let time = match NaiveTime::from_hms_opt(h, m, s) {
-        None => Err(anyhow::anyhow!("datetime::hour2time (out of range)")),
-        Some(t) => Ok(t),
+}
+
+/// Resolves a local date and time in a timezone, preferring standard time when the local time is
+/// ambiguous (e.g. 01:30 when the clocks fall back) or doesn't exist (e.g. 02:30 when the clocks spring forward)
+///
+/// ### Arguments
+/// * `tz` - A `chrono_tz` Tz object
+/// * `local` - The local date and time
+pub fn from_local_standard(tz: Tz, local: NaiveDateTime) -> DateTime<Tz> {
+    return match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) => dt,
+        LocalResult::Ambiguous(first, second) => {
+            if first.offset().dst_offset() == Duration::zero() {
+                first
+            } else {
+                second
+            }
+        }
+        LocalResult::None => {
+            // Read the skipped local time with the standard offset from UTC
+            let standard = tz.offset_from_utc_datetime(&local).base_utc_offset();
+            tz.from_utc_datetime(&(local - standard))
+        }
+    };
+}
+
+fn get_tz_offset<Tz: TimeZone>(datetime: DateTime<Tz>) -> f64 {
+    return (datetime.offset().fix().local_minus_utc() as f64) / 3600.0;
+}
+
+/// How fractional hours are rounded when converted to a time (see `hour2time`)
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rounding {
+    /// To the nearest second, for exact astronomical times
+    Seconds,
+    /// To the nearest minute, 30 seconds and above rounds up
+    #[default]
+    Minute,
+    /// Down to the minute
+    Floor,
+    /// Up to the minute
+    Ceil,
+}
+
+/// Creates NaiveTime object from hour value
+///
+/// Hours outside of the day wrap around (e.g. `25.0` is 01:00 and `-0.5` is 23:30), which happens
+/// when the timezone offset is far from the longitude's meridian.
+/// Returns `SalahError::NoSunEvent` if `hour` is not finite (the sun never reaches the angle).
+///
+/// ### Arguments
+/// * `hour` - A fractional value representing the hour of the day, normalized to 0-24
+/// * `rounding` - How the time is rounded. Seconds are always zero except with `Rounding::Seconds`
+pub fn hour2time(hour: f64, rounding: Rounding) -> Result<NaiveTime, SalahError> {
+    if !hour.is_finite() {
+        return Err(SalahError::NoSunEvent);
     }
-    .with_context(|| {
-        format!(
-            "Cannot create NaiveTime with hour = `{}`, minute = `{}`, second = `{}`",
-            h, m, s
-        )
-    })?;
 
-    return Ok(time);
+    // Decompose from seconds so that rounding carries propagate seconds -> minutes -> hours
+    let hour = math::time::normalize_hour(hour);
+    return Ok(seconds2time(hour * 3600_f64, rounding));
+}
+
+/// Rounds a time as set by `rounding` (e.g. to the minute)
+///
+/// ### Arguments
+/// * `time` - The time to round
+/// * `rounding` - How the time is rounded
+pub fn round_time(time: NaiveTime, rounding: Rounding) -> NaiveTime {
+    let seconds =
+        time.num_seconds_from_midnight() as f64 + time.nanosecond() as f64 / 1_000_000_000_f64;
+    return seconds2time(seconds, rounding);
+}
+
+/// Creates a NaiveTime from fractional seconds since midnight, rounded as set by `rounding`.
+/// `Seconds` and `Minute` round to the nearest second first, `Floor` and `Ceil` apply to the exact seconds
+/// (17:24:59.6 floors to 17:24).
+fn seconds2time(seconds: f64, rounding: Rounding) -> NaiveTime {
+    // Hours carry float noise (e.g. 17:24:00 as 62640.00000000001 seconds) that must not floor or ceil a whole minute
+    let seconds = if (seconds - seconds.round()).abs() < 1e-6 {
+        seconds.round()
+    } else {
+        seconds
+    };
+    let whole = seconds.round() as i64;
+    let seconds = match rounding {
+        Rounding::Seconds => whole,
+        Rounding::Minute => (whole + 30).div_euclid(60) * 60,
+        Rounding::Floor => (seconds / 60_f64).floor() as i64 * 60,
+        Rounding::Ceil => (seconds / 60_f64).ceil() as i64 * 60,
+    };
+
+    // Rounding up from just before midnight carries into the next day
+    let seconds = seconds.rem_euclid(86_400) as u32;
+    return NaiveTime::from_num_seconds_from_midnight_opt(seconds, 0)
+        .expect("RangeError @ datetime.seconds2time");
 }
 
 /// Creates fractional hour from NaiveTime
@@ -97,27 +512,71 @@ pub fn time2hour(time: NaiveTime) -> f64 {
 /// Converts a string to a NaiveDate
 ///
 /// ### Arguments
-/// * `date` - A date in the form YYYY-MM-DD OR `today`
-/// * `timezone` - A chrono_tz timezone for creating the date if `today` is passed
-pub fn str2date(date: &String, timezone: Tz) -> Result<NaiveDate> {
-    if date == &String::from("today") {
-        let today = timezone
-            .from_utc_datetime(&Utc::now().naive_utc())
-            .date_naive();
-        return Ok(today);
+/// * `date` - A date in the form YYYY-MM-DD, `today`, `tomorrow`, `yesterday` or a signed day offset from today (e.g. `+3`, `-2`)
+/// * `timezone` - A chrono_tz timezone for determining today's date
+pub fn str2date(date: &str, timezone: Tz) -> Result<NaiveDate, SalahError> {
+    return str2date_with_clock(date, timezone, &SystemClock);
+}
+
+/// Converts a string to a NaiveDate, with relative dates counted from the clock's current date
+///
+/// ### Arguments
+/// * `date` - A date in the form YYYY-MM-DD, `today`, `tomorrow`, `yesterday` or a signed day offset from today (e.g. `+3`, `-2`)
+/// * `timezone` - A chrono_tz timezone for determining today's date
+/// * `clock` - The source of the current time
+pub fn str2date_with_clock(
+    date: &str,
+    timezone: Tz,
+    clock: &dyn Clock,
+) -> Result<NaiveDate, SalahError> {
+    return str2date_from(date, today(timezone, clock));
+}
+
+/// Converts a string to a NaiveDate, with relative dates counted from `today`
+///
+/// ### Arguments
+/// * `date` - A date in the form YYYY-MM-DD, `today`, `tomorrow`, `yesterday` or a signed day offset (e.g. `+3`, `-2`)
+/// * `today` - The date relative dates are counted from
+pub fn str2date_from(date: &str, today: NaiveDate) -> Result<NaiveDate, SalahError> {
+    let offset = match date {
+        "today" => Some(0),
+        "tomorrow" => Some(1),
+        "yesterday" => Some(-1),
+        _ if date.len() > 1
+            && date.starts_with(['+', '-'])
+            && date[1..].chars().all(|c| c.is_ascii_digit()) =>
+        {
+            match date.parse::<i64>() {
+                Ok(days) => Some(days),
+                Err(e) => {
+                    return Err(SalahError::InvalidDate(format!(
+                        "Failed to parse day offset = `{}` ({}).",
+                        date, e
+                    )))
+                }
+            }
+        }
+        _ => None,
+    };
+    if let Some(days) = offset {
+        return Duration::try_days(days)
+            .and_then(|d| today.checked_add_signed(d))
+            .ok_or_else(|| {
+                SalahError::InvalidDate(format!("`{}` days from {} is out of range!", days, today))
+            });
     }
 
     let parts: Vec<&str> = date.split('-').collect();
     if parts.len() != 3 {
-        return Err(anyhow::anyhow!(
-            "date must consist of 3 '-' separated parts!"
-        ));
+        return Err(SalahError::InvalidDate(String::from(
+            "date must consist of 3 '-' separated parts!",
+        )));
     }
 
     let year = match parts[0].parse::<i32>() {
         Ok(v) => v,
         Err(e) => {
-            return Err(anyhow::anyhow!(format!(
+            return Err(SalahError::InvalidDate(format!(
                 "Failed to parse year = `{}` ({}).",
                 parts[0], e
             )))
@@ -127,7 +586,7 @@ pub fn str2date(date: &String, timezone: Tz) -> Result<NaiveDate> {
     let month = match parts[1].parse::<u32>() {
         Ok(v) => v,
         Err(e) => {
-            return Err(anyhow::anyhow!(format!(
+            return Err(SalahError::InvalidDate(format!(
                 "Failed to parse month = `{}` ({}).",
                 parts[1], e
             )))
@@ -137,7 +596,7 @@ pub fn str2date(date: &String, timezone: Tz) -> Result<NaiveDate> {
     let day = match parts[2].parse::<u32>() {
         Ok(v) => v,
         Err(e) => {
-            return Err(anyhow::anyhow!(format!(
+            return Err(SalahError::InvalidDate(format!(
                 "Failed to parse day = `{}` ({}).",
                 parts[2], e
             )))
@@ -146,12 +605,81 @@ pub fn str2date(date: &String, timezone: Tz) -> Result<NaiveDate> {
 
     let naive = NaiveDate::from_ymd_opt(year, month, day);
     match naive {
-        None => Err(anyhow::anyhow!(
+        None => Err(SalahError::InvalidDate(format!(
             "Date: [year = {}, month = {}, day = {}] is out of range!",
-            year,
-            month,
-            day
-        )),
+            year, month, day
+        ))),
         Some(d) => Ok(d),
     }
 }
+
+/// Parses an IANA timezone name (e.g. `America/Toronto`)
+///
+/// ### Arguments
+/// * `name` - The timezone name
+pub fn parse_timezone(name: &str) -> Result<Tz, SalahError> {
+    return name
+        .parse::<Tz>()
+        .map_err(|_| SalahError::InvalidTimezone(name.to_owned()));
+}
+
+/// Returns up to 3 known timezones close to a name that failed to parse: a case-insensitive match, else the names
+/// containing it (e.g. `toronto`), else the names fewest typos away from it or from their city (e.g. `Torronto`)
+///
+/// ### Arguments
+/// * `name` - The timezone name as given
+pub fn suggest_timezones(name: &str) -> Vec<Tz> {
+    const MAX_SUGGESTIONS: usize = 3;
+    let query = name.trim().to_lowercase().replace(' ', "_");
+    if query.is_empty() {
+        return vec![];
+    }
+    let max_distance = (query.chars().count() / 4).max(1);
+
+    // (rank, distance) for each candidate, lower is closer
+    let mut candidates: Vec<((u8, usize), Tz)> = chrono_tz::TZ_VARIANTS
+        .iter()
+        .filter_map(|tz| {
+            let zone = tz.name().to_lowercase();
+            let city = zone.rsplit('/').next().unwrap_or(&zone);
+            if zone == query {
+                return Some(((0, 0), *tz));
+            }
+            if query.len() >= 3 && zone.contains(&query) {
+                return Some(((1, zone.len()), *tz));
+            }
+            let distance = levenshtein(&query, &zone).min(levenshtein(&query, city));
+            if distance <= max_distance {
+                return Some(((2, distance), *tz));
+            }
+            return None;
+        })
+        .collect();
+    candidates.sort_by_key(|(rank, tz)| (*rank, tz.name()));
+    let Some(&((best_rank, best_distance), _)) = candidates.first() else {
+        return vec![];
+    };
+    return candidates
+        .into_iter()
+        .filter(|((rank, distance), _)| {
+            return *rank == best_rank && (best_rank == 1 || *distance == best_distance);
+        })
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, tz)| tz)
+        .collect();
+}
+
+/// Number of single character insertions, deletions or substitutions to turn `a` into `b`
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    return previous[b.len()];
+}
@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use super::SalahError;
+    use crate::astro::Precision;
+    use crate::datetime::{hour2time, parse_timezone, str2date, Rounding};
+    use crate::times::types::{Authority, MidnightMethod, Timing, Twilight};
+
+    #[test]
+    fn test_variants() {
+        let tz = parse_timezone("America/Toronto").expect("Error!");
+        assert!(matches!(
+            str2date(&String::from("2024-13-01"), tz),
+            Err(SalahError::InvalidDate(_))
+        ));
+        assert!(matches!(
+            str2date(&String::from("someday"), tz),
+            Err(SalahError::InvalidDate(_))
+        ));
+        assert!(matches!(
+            parse_timezone("Mars/Olympus_Mons"),
+            Err(SalahError::InvalidTimezone(_))
+        ));
+        assert_eq!(
+            Authority::from_str("nasa"),
+            Err(SalahError::InvalidAuthority(String::from("nasa")))
+        );
+        assert_eq!(
+            Timing::from_str("brunch"),
+            Err(SalahError::InvalidTiming(String::from("brunch")))
+        );
+        assert!(matches!(
+            MidnightMethod::from_str("noon"),
+            Err(SalahError::InvalidMidnightMethod(_))
+        ));
+        assert!(matches!(
+            Twilight::from_str("golden"),
+            Err(SalahError::InvalidTwilight(_))
+        ));
+        assert_eq!(
+            Precision::from_str("medium"),
+            Err(SalahError::InvalidPrecision(String::from("medium")))
+        );
+        assert!(matches!(
+            hour2time(f64::NAN, Rounding::Minute),
+            Err(SalahError::NoSunEvent)
+        ));
+        assert!(matches!(
+            hour2time(f64::INFINITY, Rounding::Minute),
+            Err(SalahError::NoSunEvent)
+        ));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            SalahError::InvalidTiming(String::from("brunch")).to_string(),
+            "timing = `brunch` is not valid!"
+        );
+        assert_eq!(
+            SalahError::NoSunEvent.to_string(),
+            "the sun never reaches the required angle on this date"
+        );
+    }
+}
+
+/// Errors returned by the library, so callers can match on the failure instead of parsing messages
+#[derive(Debug, Clone, PartialEq)]
+pub enum SalahError {
+    /// A date string could not be parsed or is not a valid date
+    InvalidDate(String),
+
+    /// A timezone name is not in the timezone database
+    InvalidTimezone(String),
+
+    /// An authority name is not recognized (see `Authority::list`)
+    InvalidAuthority(String),
+
+    /// A timing name is not recognized (see `Timing::list`)
+    InvalidTiming(String),
+
+    /// A midnight method name is not recognized
+    InvalidMidnightMethod(String),
+
+    /// A school (madhab) name is not recognized (see `School::list`)
+    InvalidSchool(String),
+
+    /// A twilight name is not recognized (see `Twilight::list`)
+    InvalidTwilight(String),
+
+    /// A solar position model name is not recognized (see `Precision::from_str`)
+    InvalidPrecision(String),
+
+    /// A value is outside of its valid range
+    OutOfRange(String),
+
+    /// The sun never reaches the angle for a timing on the date (e.g. near the poles)
+    NoSunEvent,
+
+    /// An API request failed
+    Network(String),
+}
+
+impl std::fmt::Display for SalahError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidDate(msg) => write!(f, "invalid date: {}", msg),
+            Self::InvalidTimezone(name) => write!(f, "timezone = `{}` is not valid!", name),
+            Self::InvalidAuthority(name) => write!(f, "authority = `{}` is not valid!", name),
+            Self::InvalidTiming(name) => write!(f, "timing = `{}` is not valid!", name),
+            Self::InvalidMidnightMethod(name) => {
+                write!(f, "midnight method = `{}` is not valid!", name)
+            }
+            Self::InvalidSchool(name) => write!(f, "madhab = `{}` is not valid!", name),
+            Self::InvalidTwilight(name) => write!(f, "twilight = `{}` is not valid!", name),
+            Self::InvalidPrecision(name) => write!(f, "precision = `{}` is not valid!", name),
+            Self::OutOfRange(msg) => write!(f, "out of range: {}", msg),
+            Self::NoSunEvent => write!(f, "the sun never reaches the required angle on this date"),
+            Self::Network(msg) => write!(f, "network error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SalahError {}
+
+#[cfg(feature = "network")]
+impl From<crate::api::FetchError> for SalahError {
+    fn from(e: crate::api::FetchError) -> Self {
+        return SalahError::Network(e.to_string());
+    }
+}
@@ -0,0 +1,75 @@
+use crate::datetime::Rounding;
+use crate::times::types::{Authority, School, Timing};
+use crate::times::PrayerTimes;
+use chrono::{NaiveDate, NaiveTime};
+
+/// Toronto City Hall on 2024-02-11 in EST (UTC-5), with the default authority (ISNA) and school (Hanafi), the
+/// location most tests use. The calculation itself is checked against the published times of `raleigh_isna` and
+/// `raleigh_moonsighting`.
+pub fn toronto() -> PrayerTimes {
+    return PrayerTimes::new(43.6534817, -79.3839347)
+        .with_date(&NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!"))
+        .with_offset_hours(-5.0);
+}
+
+/// Raleigh, NC on a date in a fixed offset, to seconds so the result can be compared with published times
+///
+/// ### Arguments
+/// * `date` - The date to calculate the timings for
+/// * `offset_hours` - The UTC offset of the published times (-5 for EST, -4 for EDT)
+pub fn raleigh(date: NaiveDate, offset_hours: f64) -> PrayerTimes {
+    return PrayerTimes::new(35.7750, -78.6336)
+        .with_date(&date)
+        .with_offset_hours(offset_hours)
+        .with_rounding(&Rounding::Seconds);
+}
+
+/// A published reference: where and how the times were calculated, and the times to the minute
+pub struct Published {
+    pub pt: PrayerTimes,
+    pub times: Vec<(Timing, NaiveTime)>,
+}
+
+/// Times published for Raleigh, NC on 2015-07-12 (EDT) with ISNA and the Hanafi asr, the reference the Adhan
+/// prayer time libraries test against
+pub fn raleigh_isna() -> Published {
+    let date = NaiveDate::from_ymd_opt(2015, 7, 12).expect("Error!");
+    return Published {
+        pt: raleigh(date, -4.0)
+            .with_authority(&Authority::ISNA)
+            .with_school(&School::Hanafi),
+        times: published(&[
+            (Timing::Fajr, 4, 42),
+            (Timing::Sunrise, 6, 8),
+            (Timing::Dhuhr, 13, 21),
+            (Timing::Asr, 18, 22),
+            (Timing::Maghrib, 20, 32),
+            (Timing::Isha, 21, 57),
+        ]),
+    };
+}
+
+/// Times published by moonsighting.com for Raleigh, NC on 2016-01-31 (EST), the reference the Adhan libraries test
+/// their Moonsighting Committee method against. Only the twilight times, MCW also adds 5 min to dhuhr and 3 min to
+/// maghrib.
+pub fn raleigh_moonsighting() -> Published {
+    let date = NaiveDate::from_ymd_opt(2016, 1, 31).expect("Error!");
+    return Published {
+        pt: raleigh(date, -5.0).with_authority(&Authority::MoonsightingCommittee),
+        times: published(&[
+            (Timing::Fajr, 5, 48),
+            (Timing::Sunrise, 7, 16),
+            (Timing::Isha, 19, 5),
+        ]),
+    };
+}
+
+fn published(times: &[(Timing, u32, u32)]) -> Vec<(Timing, NaiveTime)> {
+    return times
+        .iter()
+        .map(|(timing, hour, minute)| {
+            let time = NaiveTime::from_hms_opt(*hour, *minute, 0).expect("Error!");
+            (*timing, time)
+        })
+        .collect();
+}
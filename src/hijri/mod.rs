@@ -0,0 +1,162 @@
+use crate::astro;
+use chrono::NaiveDate;
+
+#[cfg(test)]
+mod tests {
+    use super::Hijri;
+    use chrono::NaiveDate;
+
+    type Ymd = (i32, u32, u32);
+
+    // Reference pairs from the tabular (civil) Islamic calendar
+    const REFERENCE: [(Ymd, Ymd); 6] = [
+        ((622, 7, 19), (1, 1, 1)),
+        ((1979, 11, 21), (1400, 1, 1)),
+        ((2000, 1, 1), (1420, 9, 24)),
+        ((2024, 3, 11), (1445, 9, 1)),
+        ((2024, 7, 7), (1445, 12, 30)),
+        ((2024, 7, 8), (1446, 1, 1)),
+    ];
+
+    #[test]
+    fn test_from_gregorian() {
+        for ((y, m, d), (hy, hm, hd)) in REFERENCE {
+            let date = NaiveDate::from_ymd_opt(y, m, d).expect("Error!");
+            let expected = Hijri::new(hy, hm, hd).expect("Error!");
+            assert_eq!(Hijri::from_gregorian(date), expected, "{}", date);
+            assert_eq!(expected.to_gregorian(), Some(date), "{}", expected);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut date = NaiveDate::from_ymd_opt(1900, 1, 1).expect("Error!");
+        let end = NaiveDate::from_ymd_opt(2100, 12, 31).expect("Error!");
+        while date <= end {
+            let hijri = Hijri::from_gregorian(date);
+            assert!(hijri.day <= Hijri::month_length(hijri.year, hijri.month));
+            assert_eq!(hijri.to_gregorian(), Some(date), "{}", date);
+            date += chrono::Duration::days(1);
+        }
+    }
+
+    #[test]
+    fn test_leap_years() {
+        // 11 leap years in every 30 year cycle
+        let leap: Vec<i32> = (1..=30).filter(|y| Hijri::is_leap_year(*y)).collect();
+        assert_eq!(leap, vec![2, 5, 7, 10, 13, 16, 18, 21, 24, 26, 29]);
+        assert_eq!(Hijri::month_length(1445, 12), 30);
+        assert_eq!(Hijri::month_length(1446, 12), 29);
+        assert!(Hijri::new(1446, 12, 30).is_none());
+        assert!(Hijri::new(1445, 13, 1).is_none());
+    }
+
+    #[test]
+    fn test_display() {
+        let hijri = Hijri::new(1445, 9, 1).expect("Error!");
+        assert_eq!(hijri.to_string(), "1 Ramadan 1445 AH");
+    }
+}
+
+/// Julian day number of 1 Muharram 1 AH (16 July 622, Julian calendar)
+const EPOCH: i64 = 1_948_440;
+
+/// A date in the tabular (arithmetical) Islamic calendar.
+/// Months alternate between 30 and 29 days, with a 30th day added to Dhu al-Hijjah
+/// in 11 leap years of every 30 year cycle. The observed calendar can differ by a day or two.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Hijri {
+    /// Year after the Hijra (AH)
+    pub year: i32,
+
+    /// Month of the year (1 - 12)
+    pub month: u32,
+
+    /// Day of the month (1 - 30)
+    pub day: u32,
+}
+
+impl Hijri {
+    /// Creates a Hijri date, returns `None` if the month or day are out of range
+    ///
+    /// ### Arguments
+    /// * `year` - Year after the Hijra (AH)
+    /// * `month` - Month of the year (1 - 12)
+    /// * `day` - Day of the month (1 - 30)
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Hijri> {
+        if !(1..=12).contains(&month) || day < 1 || day > Self::month_length(year, month) {
+            return None;
+        }
+        return Some(Hijri { year, month, day });
+    }
+
+    /// Returns `true` if Dhu al-Hijjah has 30 days in the year
+    pub fn is_leap_year(year: i32) -> bool {
+        return (14 + 11 * (year as i64)).rem_euclid(30) < 11;
+    }
+
+    /// Returns the number of days in a month of the year
+    pub fn month_length(year: i32, month: u32) -> u32 {
+        if month % 2 == 1 || (month == 12 && Self::is_leap_year(year)) {
+            return 30;
+        }
+        return 29;
+    }
+
+    /// Converts a Gregorian date to the Hijri date
+    ///
+    /// ### Arguments
+    /// * `date` - The Gregorian date
+    pub fn from_gregorian(date: NaiveDate) -> Hijri {
+        let jdn = (astro::julian(date) + 0.5) as i64;
+        let year = (30 * (jdn - EPOCH) + 10_646).div_euclid(10_631) as i32;
+        let mut month = 1;
+        while month < 12 && jdn >= day_number(year, month + 1, 1) {
+            month += 1;
+        }
+        let day = (jdn - day_number(year, month, 1) + 1) as u32;
+        return Hijri { year, month, day };
+    }
+
+    /// Converts the Hijri date to the Gregorian date, `None` if it is out of the supported range
+    pub fn to_gregorian(&self) -> Option<NaiveDate> {
+        let jdn = day_number(self.year, self.month, self.day);
+        return astro::from_julian(jdn as f64 - 0.5);
+    }
+
+    /// Returns the name of the month
+    pub fn month_name(&self) -> &str {
+        match self.month {
+            1 => "Muharram",
+            2 => "Safar",
+            3 => "Rabi' al-Awwal",
+            4 => "Rabi' al-Thani",
+            5 => "Jumada al-Ula",
+            6 => "Jumada al-Thani",
+            7 => "Rajab",
+            8 => "Sha'ban",
+            9 => "Ramadan",
+            10 => "Shawwal",
+            11 => "Dhu al-Qi'dah",
+            _ => "Dhu al-Hijjah",
+        }
+    }
+}
+
+impl std::fmt::Display for Hijri {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return write!(f, "{} {} {} AH", self.day, self.month_name(), self.year);
+    }
+}
+
+/// Returns the Julian day number of a Hijri date
+fn day_number(year: i32, month: u32, day: u32) -> i64 {
+    let year = year as i64;
+    let month = month as i64;
+    return day as i64
+        + (59 * (month - 1) + 1) / 2
+        + (year - 1) * 354
+        + (3 + 11 * year).div_euclid(30)
+        + EPOCH
+        - 1;
+}
@@ -1,6 +1,54 @@
+#[cfg(feature = "network")]
 pub mod api;
 pub mod astro;
+pub mod cities;
+#[cfg(feature = "cli")]
 pub mod cli;
 pub mod datetime;
+pub mod error;
+#[cfg(test)]
+mod fixtures;
+pub mod hijri;
+pub mod log;
 pub mod math;
+pub mod qibla;
 pub mod times;
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_version() {
+        let version = crate::version();
+        assert_eq!(version, env!("CARGO_PKG_VERSION"));
+
+        let parts: Vec<&str> = version.split('.').collect();
+        assert_eq!(parts.len(), 3);
+        for part in parts {
+            assert!(part.parse::<u32>().is_ok(), "`{}` is not numeric", part);
+        }
+    }
+
+    #[test]
+    fn test_features() {
+        let features = crate::features();
+        assert_eq!(features.contains(&"cli"), cfg!(feature = "cli"));
+        assert_eq!(features.contains(&"network"), cfg!(feature = "network"));
+    }
+}
+
+/// Returns the version of the crate (from `Cargo.toml`)
+pub fn version() -> &'static str {
+    return env!("CARGO_PKG_VERSION");
+}
+
+/// Returns the optional features the crate was built with (e.g. `network`), in the order of `Cargo.toml`
+pub fn features() -> Vec<&'static str> {
+    return [
+        ("cli", cfg!(feature = "cli")),
+        ("network", cfg!(feature = "network")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(name, _)| name)
+    .collect();
+}
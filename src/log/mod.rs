@@ -0,0 +1,51 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[cfg(test)]
+mod tests {
+    use super::{set_quiet, warning_line};
+
+    #[test]
+    fn test_warning_line() {
+        set_quiet(false);
+        assert_eq!(
+            warning_line("cache is corrupted"),
+            Some(String::from("warning: cache is corrupted"))
+        );
+        set_quiet(true);
+        assert_eq!(warning_line("cache is corrupted"), None);
+        set_quiet(false);
+    }
+}
+
+/// Whether warnings are suppressed (`--quiet`)
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether warnings are suppressed. Errors are always reported.
+///
+/// ### Arguments
+/// * `quiet` - If `true`, `warn` prints nothing
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Returns the line `warn` prints for a message, `None` when warnings are suppressed
+///
+/// ### Arguments
+/// * `message` - The warning message
+pub fn warning_line(message: impl std::fmt::Display) -> Option<String> {
+    if QUIET.load(Ordering::Relaxed) {
+        return None;
+    }
+    return Some(format!("warning: {}", message));
+}
+
+/// Prints a warning to stderr, unless warnings are suppressed.
+/// Diagnostics never go to stdout, so they can't break machine readable output (e.g. `--json` or `--csv`).
+///
+/// ### Arguments
+/// * `message` - The warning message
+pub fn warn(message: impl std::fmt::Display) {
+    if let Some(line) = warning_line(message) {
+        eprintln!("{}", line);
+    }
+}
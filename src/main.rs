@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
 use salah::cli;
+use salah::datetime::{Clock, SystemClock};
 use salah::times;
+use std::io::IsTerminal;
 
 /// USEFUL LINKS:
 /// https://data.iana.org/time-zones/tzdb-2024a/zone1970.tab -> timezone names
@@ -13,34 +15,184 @@ async fn main() -> Result<()> {
         .with_context(|| "Failed to parse CLI arguments")?;
 
     match opts {
-        cli::ParsedOptions::Calculation {
+        cli::ParsedOptions::Calculation { ref output, .. } => {
+            let output = output.clone();
+            let rendered = calculate(opts).await?;
+            cli::write_output(output.as_deref(), &rendered)?;
+        }
+        cli::ParsedOptions::Batch { locations, output } => {
+            let mut blocks: Vec<String> = vec![];
+            for (name, location) in locations {
+                blocks.push(cli::render_labeled(&name, &calculate(location).await?));
+            }
+            cli::write_output(output.as_deref(), &blocks.join("\n"))?;
+        }
+        cli::ParsedOptions::Timings => cli::display_timings(),
+        cli::ParsedOptions::Authority => cli::display_authority(),
+        cli::ParsedOptions::Timezones { query } => cli::display_timezones(&query),
+        cli::ParsedOptions::Qibla { lat, lng } => println!("{}", cli::render_qibla(lat, lng)),
+        cli::ParsedOptions::Hijri { date } => println!("{}", cli::render_hijri(date)),
+        cli::ParsedOptions::Diff {
             date,
             timezone,
             lat,
             lng,
-            timings,
-            auth,
+            auths,
             school,
+            precision,
             format,
         } => {
             let pt = times::PrayerTimes::new(lat, lng)
                 .with_date(&date)
                 .with_timezone(&timezone)
-                .with_authority(&auth)
-                .with_school(&school);
-
-            for timing in &timings {
-                println!(
-                    "{} {}",
-                    timing.to_str(),
-                    pt.timing(timing).format(format.as_str())
-                );
-            }
+                .with_school(&school)
+                .with_precision(&precision);
+            println!(
+                "{}",
+                cli::render_diff(&pt, &auths[0], &auths[1], format.as_str())
+            );
         }
-        cli::ParsedOptions::Timings => cli::display_timings(),
-        cli::ParsedOptions::Authority => cli::display_authority(),
-        cli::ParsedOptions::Timezones { query } => cli::display_timezones(&query),
+        cli::ParsedOptions::Info => cli::display_info(),
     }
 
     return Ok(());
 }
+
+/// Computes the prayer times for the calculation options and renders them in the chosen output format.
+/// With `--watch`, the countdown runs until Ctrl-C and there is nothing left to write.
+async fn calculate(opts: cli::ParsedOptions) -> Result<String> {
+    let cli::ParsedOptions::Calculation {
+        date,
+        timezone,
+        lat,
+        lng,
+        timings,
+        sort,
+        locale,
+        auth,
+        fajr_angle_override,
+        isha_angle_override,
+        isha_interval,
+        school,
+        format,
+        precision,
+        tabular,
+        half_night,
+        hijri,
+        show_angles,
+        compare_now,
+        ramadan,
+        json,
+        ics,
+        csv,
+        output,
+        days,
+        watch,
+        dhuhr_offset,
+        elevation,
+        elevation_twilight,
+        local_mean_time,
+        adjustments,
+        snap,
+        place,
+        calendar,
+        next,
+    } = opts
+    else {
+        return Err(anyhow::anyhow!("Expected calculation options"));
+    };
+
+    let pt = times::PrayerTimes::new(lat, lng)
+        .with_date(&date)
+        .with_timezone(&timezone)
+        .with_authority(&auth)
+        .with_fajr_angle_override(fajr_angle_override)
+        .with_isha_angle_override(isha_angle_override)
+        .with_isha_interval(isha_interval)
+        .with_school(&school)
+        .with_precision(&precision)
+        .with_ramadan(ramadan)
+        .with_dhuhr_offset(&dhuhr_offset)
+        .with_elevation(elevation)
+        .with_elevation_twilight(elevation_twilight)
+        .with_local_mean_time(local_mean_time)
+        .with_adjustments(&adjustments);
+    let timings = sort.apply(&pt, &timings);
+
+    if watch {
+        cli::watch(&pt, &timezone, format.as_str()).await?;
+        return Ok(String::new());
+    }
+
+    let rendered = if next {
+        cli::render_next(&pt, &timezone, &SystemClock, format.as_str()) + "\n"
+    } else if csv {
+        let (start, end) = match calendar {
+            Some(month) => cli::month_bounds(month),
+            None => (date, date + chrono::Duration::days(days as i64 - 1)),
+        };
+        cli::render_csv(&pt, start, end, format.as_str(), &snap) + "\n"
+    } else if let Some(month) = calendar {
+        cli::render_calendar(&pt, month, format.as_str(), &snap) + "\n"
+    } else if tabular {
+        cli::render_tabular(&pt, format.as_str()) + "\n"
+    } else if ics {
+        cli::render_ics(&pt, &timings, days)
+    } else if json {
+        cli::render_json(&pt, &timings, half_night, format.as_str(), &snap) + "\n"
+    } else {
+        let mut lines: Vec<String> = vec![];
+        if let Some(place) = place {
+            lines.push(format!("location {}", place));
+        }
+        if hijri {
+            lines.push(cli::render_hijri(date));
+        }
+        if compare_now {
+            lines.push(cli::render_compare_now(
+                &pt,
+                &timings,
+                &timezone,
+                &SystemClock,
+                format.as_str(),
+                &snap,
+                &locale,
+            ));
+        } else if output.is_none() && std::io::stdout().is_terminal() {
+            // Local mean time is highlighted against the current local mean time
+            let now = if local_mean_time {
+                SystemClock
+                    .now_utc()
+                    .with_timezone(&pt.utc_offset())
+                    .naive_local()
+            } else {
+                SystemClock.now_utc().with_timezone(&timezone).naive_local()
+            };
+            lines.push(cli::render_table(
+                &pt,
+                &timings,
+                Some(now),
+                format.as_str(),
+                &snap,
+                &locale,
+                true,
+            ));
+        } else {
+            lines.push(cli::render_timings(
+                &pt,
+                &timings,
+                format.as_str(),
+                &snap,
+                &locale,
+            ));
+        }
+        if half_night {
+            lines.push(cli::render_half_night(&pt, format.as_str(), &snap));
+        }
+        if show_angles {
+            lines.push(cli::render_parameters(&pt));
+        }
+        lines.join("\n") + "\n"
+    };
+    return Ok(rendered);
+}
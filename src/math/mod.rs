@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use super::normalize;
+    use super::{is_valid_longitude, normalize, normalize_longitude};
 
     fn assert_close(a: f64, b: f64, tol: f64, message: &str) {
         if (a - b).abs() > tol {
@@ -26,6 +26,27 @@ mod tests {
         assert_close(test, expected, tolerance, "90 degrees to radians");
     }
 
+    #[test]
+    fn test_normalize_longitude() {
+        let tolerance = 0.001;
+        assert_close(normalize_longitude(270.0), -90.0, tolerance, "270");
+        assert_close(normalize_longitude(359.5), -0.5, tolerance, "359.5");
+        assert_close(normalize_longitude(-200.0), 160.0, tolerance, "-200");
+        assert_close(normalize_longitude(-79.38), -79.38, tolerance, "-79.38");
+        assert_close(normalize_longitude(180.0), 180.0, tolerance, "180");
+        assert_close(normalize_longitude(-180.0), -180.0, tolerance, "-180");
+    }
+
+    #[test]
+    fn test_is_valid_longitude() {
+        for lng in [-180.0, -79.38, 0.0, 180.0, 270.0, 359.5] {
+            assert!(is_valid_longitude(lng), "{}", lng);
+        }
+        for lng in [-180.5, 360.0, 400.0, f64::NAN, f64::INFINITY] {
+            assert!(!is_valid_longitude(lng), "{}", lng);
+        }
+    }
+
     #[test]
     fn test_normalize() {
         let tolerance = 0.001;
@@ -73,6 +94,28 @@ pub fn normalize(value: f64, max: f64) -> f64 {
     }
 }
 
+/// Normalizes a longitude to the east-positive convention in [-180, 180] used by the calculations
+/// (`astro::zenith` subtracts `lng / 15` hours, so east of Greenwich is positive).
+/// Longitudes in [-180, 180] are unchanged, others (e.g. 270 from a [0, 360) convention) are wrapped, so 270 becomes -90.
+///
+/// ### Arguments
+/// * `lng` - The longitude value in degrees
+pub fn normalize_longitude(lng: f64) -> f64 {
+    if (-180_f64..=180_f64).contains(&lng) {
+        return lng;
+    }
+    return normalize(lng + 180_f64, 360_f64) - 180_f64;
+}
+
+/// Returns `true` if a longitude is accepted as input: in [-180, 180] east-positive, or in [0, 360) east of Greenwich.
+/// Accepted longitudes are wrapped by `normalize_longitude`, others are rejected.
+///
+/// ### Arguments
+/// * `lng` - The longitude value in degrees
+pub fn is_valid_longitude(lng: f64) -> bool {
+    return (-180_f64..360_f64).contains(&lng);
+}
+
 pub mod deg {
     use crate::math::*;
     pub fn normalize_angle(angle: f64) -> f64 {
@@ -0,0 +1,48 @@
+use crate::math::*;
+
+#[cfg(test)]
+mod tests {
+    use super::qibla;
+
+    #[test]
+    fn test_qibla() {
+        // Reference bearings from true north (great-circle)
+        let cases = [
+            ("New York", 40.7128, -74.0060, 58.48),
+            ("London", 51.5074, -0.1278, 118.99),
+            ("Toronto", 43.6534817, -79.3839347, 54.58),
+            ("Jakarta", -6.2088, 106.8456, 295.15),
+        ];
+        for (name, lat, lng, expected) in cases {
+            let bearing = qibla(lat, lng);
+            assert!(
+                (bearing - expected).abs() < 0.05,
+                "qibla({}) = {}, expected {}",
+                name,
+                bearing,
+                expected
+            );
+        }
+    }
+}
+
+/// Latitude of the Kaaba in degrees
+pub const KAABA_LAT: f64 = 21.4225;
+
+/// Longitude of the Kaaba in degrees
+pub const KAABA_LNG: f64 = 39.8262;
+
+/// Gets the direction of the Kaaba (Qibla) as the initial great-circle bearing in degrees
+/// clockwise from true north (0 - 360)
+///
+/// ### Arguments
+/// * `lat` - The latitude value
+/// * `lng` - The longitude value
+pub fn qibla(lat: f64, lng: f64) -> f64 {
+    let d_lng = KAABA_LNG - lng;
+    let bearing = deg::atan2(
+        deg::sin(d_lng),
+        deg::cos(lat) * deg::tan(KAABA_LAT) - deg::sin(lat) * deg::cos(d_lng),
+    );
+    return deg::normalize_angle(bearing);
+}
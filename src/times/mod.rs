@@ -1,198 +1,3687 @@
 use crate::astro;
 use crate::datetime;
+use crate::error::SalahError;
 use crate::math;
-use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone,
+    Timelike, Utc,
+};
 use chrono_tz::Tz;
+use serde::Serialize;
 
+pub mod moonsighting;
 pub mod types;
 
-use types::{Authority, IshaParam, School, Timing};
+#[cfg(test)]
+mod tests {
+    use super::types::{
+        Adjustments, Authority, CalculationParameters, IshaParam, MidnightMethod, School, Timing,
+    };
+    use super::{PrayerTimes, Times};
+    use crate::astro::{self, Precision};
+    use crate::datetime::Rounding;
+    use crate::error::SalahError;
+    use crate::fixtures;
+    use crate::math;
+    use chrono::{DateTime, FixedOffset};
+    use chrono::{Duration, NaiveDate, NaiveTime, Timelike};
 
+    #[test]
+    fn test_with_offset_hours() {
+        let pt = fixtures::toronto();
+        assert_eq!(pt.utc_offset().local_minus_utc(), -5 * 3600);
+        // America/Toronto (EST) winter results
+        assert_eq!(pt.fajr(), NaiveTime::from_hms_opt(6, 3, 0).expect("Error!"));
+        assert_eq!(
+            pt.asr(),
+            NaiveTime::from_hms_opt(15, 57, 0).expect("Error!")
+        );
+    }
+
+    #[test]
+    fn test_with_timezone_offset() {
+        use crate::datetime::TimezoneOffset;
+
+        // Riyadh has no daylight savings, so a fixed +3 matches the named timezone all year
+        let riyadh: chrono_tz::Tz = "Asia/Riyadh".parse().expect("Error!");
+        for (month, day) in [(1, 15), (7, 15), (11, 3)] {
+            let date = NaiveDate::from_ymd_opt(2024, month, day).expect("Error!");
+            let named = PrayerTimes::new(21.4225, 39.8262)
+                .with_timezone_offset(&TimezoneOffset::Named(riyadh))
+                .with_date(&date);
+            let fixed = PrayerTimes::new(21.4225, 39.8262)
+                .with_timezone_offset(&TimezoneOffset::Fixed(3.0))
+                .with_date(&date);
+            assert_eq!(named.utc_offset(), fixed.utc_offset());
+            assert_eq!(named.all(), fixed.all());
+            assert_eq!(named.datetime(&Timing::Fajr), fixed.datetime(&Timing::Fajr));
+        }
+
+        let pt = PrayerTimes::new(21.4225, 39.8262).with_offset_hours(3.0);
+        assert_eq!(pt.timezone_offset(), TimezoneOffset::Fixed(3.0));
+        let east = FixedOffset::east_opt(3 * 3600).expect("Error!");
+        let fixed = PrayerTimes::new(21.4225, 39.8262).with_fixed_offset(&east);
+        assert_eq!(fixed.timezone_offset(), TimezoneOffset::Fixed(3.0));
+        let india = FixedOffset::east_opt(5 * 3600 + 1800).expect("Error!");
+        let fixed = PrayerTimes::new_with_fixed_offset(28.6139, 77.209, &india);
+        assert_eq!(fixed.timezone_offset(), TimezoneOffset::Fixed(5.5));
+        assert_eq!(fixed.utc_offset(), india);
+        let pt = pt.with_timezone(&riyadh);
+        assert_eq!(pt.timezone_offset(), TimezoneOffset::Named(riyadh));
+    }
+
+    #[test]
+    fn test_with_timezone_dst() {
+        let tz: chrono_tz::Tz = "America/Toronto".parse().expect("Error!");
+        let january = NaiveDate::from_ymd_opt(2024, 1, 15).expect("Error!");
+        let july = NaiveDate::from_ymd_opt(2024, 7, 15).expect("Error!");
+
+        let winter = PrayerTimes::new(43.6534817, -79.3839347)
+            .with_timezone(&tz)
+            .with_date(&january);
+        assert_eq!(winter.utc_offset().local_minus_utc(), -5 * 3600);
+
+        // The order of the setters does not matter
+        let summer = PrayerTimes::new(43.6534817, -79.3839347)
+            .with_date(&july)
+            .with_timezone(&tz);
+        assert_eq!(summer.utc_offset().local_minus_utc(), -4 * 3600);
+        assert_eq!(
+            winter.clone().with_date(&july).utc_offset(),
+            summer.utc_offset()
+        );
+
+        // A fixed offset is kept across dates
+        let fixed = winter.with_offset_hours(-5.0).with_date(&july);
+        assert_eq!(fixed.utc_offset().local_minus_utc(), -5 * 3600);
+
+        // Matches the fixed EST results in the winter
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347)
+            .with_date(&date)
+            .with_timezone(&tz);
+        assert_eq!(pt.fajr(), NaiveTime::from_hms_opt(6, 3, 0).expect("Error!"));
+    }
+
+    #[test]
+    fn test_isha_overlaps_fajr() {
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let north = PrayerTimes::new(58.0, 10.0)
+            .with_date(&solstice)
+            .with_offset_hours(2.0);
+        assert!(north.isha_overlaps_fajr());
+
+        let winter = NaiveDate::from_ymd_opt(2024, 1, 15).expect("Error!");
+        let mid = fixtures::toronto().with_date(&winter);
+        assert!(!mid.isha_overlaps_fajr());
+    }
+
+    #[test]
+    fn test_all_times() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).expect("Error!");
+        let pt = fixtures::toronto().with_date(&date);
+        let times = pt.all_times();
+        assert_eq!(times.len(), Timing::list().len());
+
+        let isha = times
+            .iter()
+            .find(|t| t.timing == Timing::Isha)
+            .expect("Error!");
+        assert_eq!(isha.time, pt.isha());
+        assert_eq!(isha.datetime.time(), isha.time);
+        assert_eq!(isha.datetime.date_naive(), date);
+
+        let midnight = times
+            .iter()
+            .find(|t| t.timing == Timing::Midnight)
+            .expect("Error!");
+        assert_eq!(midnight.datetime.time(), midnight.time);
+        assert_eq!(
+            midnight.datetime.date_naive(),
+            date.succ_opt().expect("Error!")
+        );
+        assert_eq!(midnight.datetime, pt.datetime(&Timing::Midnight));
+        assert_eq!(midnight.local, None);
+
+        // With a named timezone, the zoned date and time is the same instant, rolled over like `datetime`
+        let tz: chrono_tz::Tz = "America/Toronto".parse().expect("Error!");
+        let summer = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347)
+            .with_date(&summer)
+            .with_timezone(&tz);
+        for result in pt.all_times() {
+            let local = result.local.expect("Error!");
+            assert_eq!(local, result.datetime, "{}", result.timing);
+            assert_eq!(local.timezone(), tz);
+        }
+        let times = pt.all_times();
+        let midnight = times
+            .iter()
+            .find(|t| t.timing == Timing::Midnight)
+            .expect("Error!");
+        assert_eq!(
+            midnight.local.expect("Error!").date_naive(),
+            summer.succ_opt().expect("Error!")
+        );
+    }
+
+    #[test]
+    fn test_all() {
+        let toronto = fixtures::toronto();
+        let date = toronto.date();
+        let makkah = PrayerTimes::new(21.4225, 39.8262)
+            .with_date(&date)
+            .with_offset_hours(3.0)
+            .with_authority(&Authority::Makkah);
+        for pt in [toronto, makkah] {
+            let times = pt.all();
+            assert_eq!(times.imsak, pt.imsak());
+            assert_eq!(times.fajr, pt.fajr());
+            assert_eq!(times.sunrise, pt.sunrise());
+            assert_eq!(times.duha, pt.duha());
+            assert_eq!(times.dhuhr, pt.dhuhr());
+            assert_eq!(times.asr, pt.asr());
+            assert_eq!(times.maghrib, pt.maghrib());
+            assert_eq!(times.isha, pt.isha());
+            assert_eq!(times.midnight, pt.midnight());
+            assert_eq!(times.last_third, pt.last_third());
+        }
+    }
+
+    #[test]
+    fn test_next_and_current_prayer() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+
+        // Between asr and maghrib
+        let afternoon = NaiveTime::from_hms_opt(16, 30, 0).expect("Error!");
+        assert_eq!(pt.next_prayer(afternoon), (Timing::Maghrib, pt.maghrib()));
+        assert_eq!(pt.current_prayer(afternoon), (Timing::Asr, pt.asr()));
+
+        // Before fajr, the night belongs to the previous day's isha
+        let early = NaiveTime::from_hms_opt(4, 0, 0).expect("Error!");
+        let yesterday = pt.clone().with_date(&date.pred_opt().expect("Error!"));
+        assert_eq!(pt.next_prayer(early), (Timing::Fajr, pt.fajr()));
+        assert_eq!(pt.current_prayer(early), (Timing::Isha, yesterday.isha()));
+
+        // After isha, the next prayer is tomorrow's fajr
+        let late = NaiveTime::from_hms_opt(23, 0, 0).expect("Error!");
+        let tomorrow = pt.clone().with_date(&date.succ_opt().expect("Error!"));
+        assert_eq!(pt.next_prayer(late), (Timing::Fajr, tomorrow.fajr()));
+        assert_ne!(tomorrow.fajr(), pt.fajr());
+        assert_eq!(pt.current_prayer(late), (Timing::Isha, pt.isha()));
+
+        // Exactly on a prayer time, that prayer is current and the next one follows
+        assert_eq!(pt.current_prayer(pt.asr()), (Timing::Asr, pt.asr()));
+        assert_eq!(pt.next_prayer(pt.asr()).0, Timing::Maghrib);
+    }
+
+    #[test]
+    fn test_current_prayer_without_isha() {
+        // The sun stays above -17 degrees in London around the solstice, so MWL's isha (and fajr) have no solution
+        let date = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let pt = PrayerTimes::new(51.5074, -0.1278)
+            .with_date(&date)
+            .with_offset_hours(1.0)
+            .with_authority(&Authority::MWL);
+        assert!(pt.try_timing(&Timing::Isha).is_err());
+
+        let late = NaiveTime::from_hms_opt(23, 30, 0).expect("Error!");
+        assert_eq!(pt.current_prayer(late), (Timing::Maghrib, pt.maghrib()));
+        let early = NaiveTime::from_hms_opt(1, 0, 0).expect("Error!");
+        let yesterday = pt.clone().with_date(&date.pred_opt().expect("Error!"));
+        assert_eq!(
+            pt.current_prayer(early),
+            (Timing::Maghrib, yesterday.maghrib())
+        );
+        assert_eq!(pt.next_prayer(early), (Timing::Dhuhr, pt.dhuhr()));
+    }
+
+    #[test]
+    fn test_next_and_current_prayer_midnight_sun() {
+        // Fajr, maghrib and isha have no solution, the days go from dhuhr to asr
+        let date = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let pt = PrayerTimes::new(78.2232, 15.6267)
+            .with_date(&date)
+            .with_offset_hours(2.0)
+            .with_authority(&Authority::MWL);
+        let tomorrow = pt.clone().with_date(&date.succ_opt().expect("Error!"));
+        assert!(pt.isha_overlaps_fajr());
+
+        let late = NaiveTime::from_hms_opt(23, 0, 0).expect("Error!");
+        assert_eq!(pt.current_prayer(late), (Timing::Asr, pt.asr()));
+        assert_eq!(pt.next_prayer(late), (Timing::Dhuhr, tomorrow.dhuhr()));
+
+        let early = NaiveTime::from_hms_opt(5, 0, 0).expect("Error!");
+        let yesterday = pt.clone().with_date(&date.pred_opt().expect("Error!"));
+        assert_eq!(pt.current_prayer(early), (Timing::Asr, yesterday.asr()));
+        assert_eq!(pt.next_prayer(early), (Timing::Dhuhr, pt.dhuhr()));
+
+        // Isha 90 minutes after maghrib has a solution, but overlaps the next fajr which has none
+        let stockholm = PrayerTimes::new(59.3293, 18.0686)
+            .with_date(&date)
+            .with_offset_hours(2.0)
+            .with_authority(&Authority::Makkah);
+        assert!(stockholm.try_timing(&Timing::Isha).is_ok());
+        assert!(stockholm.isha_overlaps_fajr());
+        let night = stockholm.maghrib() + Duration::minutes(100);
+        assert_eq!(
+            stockholm.current_prayer(night),
+            (Timing::Maghrib, stockholm.maghrib())
+        );
+        assert_eq!(stockholm.next_prayer(night).0, Timing::Dhuhr);
+    }
+
+    #[test]
+    fn test_nearest() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+
+        // Just after maghrib (17:41), maghrib is closer than isha
+        let evening = pt.maghrib() + Duration::minutes(5);
+        assert_eq!(pt.nearest(evening), (Timing::Maghrib, pt.maghrib()));
+
+        // At 03:00, the coming fajr is closer than the previous night's isha
+        let night = NaiveTime::from_hms_opt(3, 0, 0).expect("Error!");
+        assert_eq!(pt.nearest(night), (Timing::Fajr, pt.fajr()));
+
+        // At 00:30, the previous day's isha is closer than fajr
+        let yesterday = pt.clone().with_date(&date.pred_opt().expect("Error!"));
+        let midnight = NaiveTime::from_hms_opt(0, 30, 0).expect("Error!");
+        assert_eq!(pt.nearest(midnight), (Timing::Isha, yesterday.isha()));
+
+        // Late at night, isha is closer than tomorrow's fajr
+        let late = NaiveTime::from_hms_opt(23, 50, 0).expect("Error!");
+        assert_eq!(pt.nearest(late), (Timing::Isha, pt.isha()));
+
+        // Exactly on a prayer time
+        assert_eq!(pt.nearest(pt.asr()), (Timing::Asr, pt.asr()));
+
+        // Midnight sun in Longyearbyen (78°N), fajr, maghrib and isha have no solution and are skipped
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let polar = PrayerTimes::new(78.2232, 15.6267)
+            .with_date(&solstice)
+            .with_offset_hours(2.0)
+            .with_authority(&Authority::MWL);
+        assert!(polar.try_timing(&Timing::Isha).is_err());
+        assert_eq!(polar.nearest(late), (Timing::Asr, polar.asr()));
+        assert_eq!(polar.nearest(night), (Timing::Dhuhr, polar.dhuhr()));
+        assert_eq!(polar.nearest(midnight), (Timing::Dhuhr, polar.dhuhr()));
+    }
+
+    #[test]
+    fn test_time_until_next() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+
+        let afternoon = date.and_hms_opt(16, 30, 0).expect("Error!");
+        assert_eq!(
+            pt.time_until_next(afternoon),
+            date.and_time(pt.maghrib()) - afternoon
+        );
+
+        // Exactly on asr counts down to maghrib
+        let asr = date.and_time(pt.asr());
+        assert_eq!(pt.time_until_next(asr), pt.maghrib() - pt.asr());
+
+        // After isha the countdown crosses midnight into tomorrow's fajr
+        let tomorrow = date.succ_opt().expect("Error!");
+        let next_fajr = pt.clone().with_date(&tomorrow).fajr();
+        let late = date.and_hms_opt(23, 0, 0).expect("Error!");
+        let until = pt.time_until_next(late);
+        assert_eq!(until, tomorrow.and_time(next_fajr) - late);
+        assert!(until > Duration::hours(6) && until < Duration::hours(8));
+
+        // The configured date follows `now`
+        let early = tomorrow.and_hms_opt(4, 0, 0).expect("Error!");
+        assert_eq!(
+            pt.time_until_next(early),
+            tomorrow.and_time(next_fajr) - early
+        );
+    }
+
+    #[test]
+    fn test_custom_authority() {
+        let mwl = fixtures::toronto().with_authority(&Authority::MWL);
+        let custom = mwl.clone().with_authority(&Authority::Custom {
+            fajr_angle: 18.0,
+            isha: IshaParam::Angle(17.0),
+        });
+        assert_eq!(custom.all(), mwl.all());
+
+        let interval = mwl.clone().with_authority(&Authority::Custom {
+            fajr_angle: 18.0,
+            isha: IshaParam::Duration(std::time::Duration::from_secs(75 * 60)),
+        });
+        assert_eq!((interval.isha() - interval.maghrib()).num_minutes(), 75);
+    }
+
+    #[test]
+    fn test_duha() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+        assert_eq!((pt.duha() - pt.sunrise()).num_minutes(), 20);
+        assert_eq!(pt.timing(&Timing::Duha), pt.duha());
+        assert!(pt.duha() < pt.dhuhr());
+
+        let offset = std::time::Duration::from_secs(15 * 60);
+        let custom = pt.clone().with_duha_offset(&offset);
+        assert_eq!(
+            (custom.duha() - custom.sunrise()).to_std().expect("Error!"),
+            offset
+        );
+        assert_eq!(custom.all().duha, custom.duha());
+        assert_eq!(custom.datetime(&Timing::Duha).date_naive(), date);
+    }
+
+    #[test]
+    fn test_imsak() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).expect("Error!");
+        let pt = PrayerTimes::new(21.4225, 39.8262)
+            .with_date(&date)
+            .with_offset_hours(3.0);
+        assert_eq!((pt.fajr() - pt.imsak()).num_minutes(), 10);
+        assert_eq!(pt.timing(&Timing::Imsak), pt.imsak());
+
+        let offset = std::time::Duration::from_secs(15 * 60);
+        let custom = pt.clone().with_imsak_offset(&offset);
+        assert_eq!(
+            (custom.fajr() - custom.imsak()).to_std().expect("Error!"),
+            offset
+        );
+        assert_eq!(custom.fajr(), pt.fajr());
+    }
+
+    #[test]
+    fn test_with_elevation() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).expect("Error!");
+        let sea_level = PrayerTimes::new(21.4225, 39.8262)
+            .with_date(&date)
+            .with_offset_hours(3.0);
+        let raised = sea_level.clone().with_elevation(1000.0);
+
+        // ~1.1 degrees of horizon dip, a few minutes near the equinox at this latitude
+        let later = (raised.maghrib() - sea_level.maghrib()).num_minutes();
+        assert!((3..=8).contains(&later), "{}", later);
+        let earlier = (sea_level.sunrise() - raised.sunrise()).num_minutes();
+        assert!((3..=8).contains(&earlier), "{}", earlier);
+
+        assert_eq!(raised.dhuhr(), sea_level.dhuhr());
+        assert_eq!(raised.asr(), sea_level.asr());
+        assert_eq!(raised.fajr(), sea_level.fajr());
+
+        // Below sea level the horizon does not rise
+        let below = sea_level.clone().with_elevation(-400.0);
+        assert_eq!(below.maghrib(), sea_level.maghrib());
+    }
+
+    #[test]
+    fn test_with_elevation_twilight() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).expect("Error!");
+        let sea_level = PrayerTimes::new(21.4225, 39.8262)
+            .with_date(&date)
+            .with_offset_hours(3.0)
+            .with_authority(&Authority::MWL);
+        let horizon_only = sea_level.clone().with_elevation(1000.0);
+        let twilight = horizon_only.clone().with_elevation_twilight(true);
+
+        // By default only the horizon events move
+        assert_eq!(horizon_only.fajr(), sea_level.fajr());
+        assert_eq!(horizon_only.isha(), sea_level.isha());
+        assert!(horizon_only.maghrib() > sea_level.maghrib());
+
+        // Applied to the twilight angles too, fajr is earlier and isha later
+        let earlier = (sea_level.fajr() - twilight.fajr()).num_minutes();
+        assert!((3..=8).contains(&earlier), "{}", earlier);
+        let later = (twilight.isha() - sea_level.isha()).num_minutes();
+        assert!((3..=8).contains(&later), "{}", later);
+        assert_eq!(twilight.maghrib(), horizon_only.maghrib());
+        assert_eq!(twilight.sunrise(), horizon_only.sunrise());
+
+        // Nothing to apply at sea level
+        let flat = sea_level.clone().with_elevation_twilight(true);
+        assert_eq!(flat.fajr(), sea_level.fajr());
+        assert_eq!(flat.isha(), sea_level.isha());
+    }
+
+    #[test]
+    fn test_with_rounding() {
+        let pt = fixtures::toronto();
+        let exact = pt.clone().with_rounding(&Rounding::Seconds);
+        let floor = pt.clone().with_rounding(&Rounding::Floor);
+        let ceil = pt.clone().with_rounding(&Rounding::Ceil);
+
+        let times = [pt.all(), exact.all(), floor.all(), ceil.all()];
+        assert!(times[1] != times[0]);
+        for (minute, exact) in [(times[0].fajr, times[1].fajr), (times[0].asr, times[1].asr)] {
+            assert!((minute - exact).num_seconds().abs() <= 30);
+        }
+        assert!(times[2].fajr <= times[1].fajr && times[1].fajr <= times[3].fajr);
+        assert_eq!(times[2].fajr.second(), 0);
+        assert_eq!((times[3].maghrib - times[2].maghrib).num_minutes(), 1);
+    }
+
+    #[test]
+    fn test_with_dhuhr_offset() {
+        let pt = fixtures::toronto();
+        assert_eq!(
+            pt.clone()
+                .with_dhuhr_offset(&std::time::Duration::ZERO)
+                .dhuhr(),
+            pt.dhuhr()
+        );
+
+        let offset = pt
+            .clone()
+            .with_dhuhr_offset(&std::time::Duration::from_secs(60));
+        assert_eq!((offset.dhuhr() - pt.dhuhr()).num_minutes(), 1);
+        assert_eq!(offset.all().dhuhr, offset.dhuhr());
+        assert_eq!(offset.asr(), pt.asr());
+    }
+
+    #[test]
+    fn test_with_adjustments() {
+        let pt = fixtures::toronto();
+        let adjusted = pt.clone().with_adjustments(&Adjustments {
+            dhuhr: 5,
+            ..Default::default()
+        });
+
+        assert_eq!((adjusted.dhuhr() - pt.dhuhr()).num_minutes(), 5);
+        let (base, times) = (pt.all(), adjusted.all());
+        assert_eq!(times.dhuhr, adjusted.dhuhr());
+        assert_eq!(
+            Times {
+                dhuhr: base.dhuhr,
+                ..times
+            },
+            base
+        );
+
+        // Every getter agrees with all(), and only the adjusted timing moves
+        let adjusted = pt.clone().with_adjustments(&Adjustments {
+            fajr: -2,
+            sunrise: 1,
+            dhuhr: 3,
+            asr: 4,
+            maghrib: 3,
+            isha: -5,
+        });
+        assert_eq!(
+            adjusted.all(),
+            Times {
+                imsak: adjusted.imsak(),
+                fajr: adjusted.fajr(),
+                sunrise: adjusted.sunrise(),
+                duha: adjusted.duha(),
+                dhuhr: adjusted.dhuhr(),
+                asr: adjusted.asr(),
+                maghrib: adjusted.maghrib(),
+                isha: adjusted.isha(),
+                midnight: adjusted.midnight(),
+                last_third: adjusted.last_third(),
+            }
+        );
+        assert_eq!((pt.fajr() - adjusted.fajr()).num_minutes(), 2);
+        assert_eq!((pt.isha() - adjusted.isha()).num_minutes(), 5);
+        assert_eq!((pt.fajr() - adjusted.imsak()).num_minutes(), 12);
+        assert_eq!(adjusted.midnight(), pt.midnight());
+        assert_eq!(adjusted.last_third(), pt.last_third());
+    }
+
+    #[test]
+    fn test_with_parameters() {
+        let adjustments = Adjustments {
+            fajr: 2,
+            isha: -3,
+            ..Default::default()
+        };
+        let params = CalculationParameters {
+            authority: Authority::MWL,
+            school: School::Shafi,
+            night_fraction_clamp: Some(1_f64 / 7_f64),
+            adjustments,
+            rounding: Rounding::Seconds,
+        };
+        let base = fixtures::toronto();
+        let loaded = base.clone().with_parameters(&params);
+        let chained = base
+            .with_authority(&Authority::MWL)
+            .with_school(&School::Shafi)
+            .with_night_fraction_clamp(Some(1_f64 / 7_f64))
+            .with_adjustments(&adjustments)
+            .with_rounding(&Rounding::Seconds);
+
+        assert_eq!(loaded.parameters(), params);
+        assert_eq!(chained.parameters(), params);
+        assert_eq!(loaded.all(), chained.all());
+    }
+
+    #[test]
+    fn test_with_local_mean_time() {
+        let civil = fixtures::toronto().with_rounding(&Rounding::Seconds);
+        let lmt = civil.clone().with_local_mean_time(true);
+
+        // In local mean time, the sun crosses the meridian at 12:00 corrected by the equation of time
+        assert!((lmt.zenith() - (12_f64 - lmt.eqt)).abs() < 1e-9);
+        assert_eq!(
+            lmt.dhuhr(),
+            NaiveTime::from_hms_opt(12, 14, 12).expect("Error!")
+        );
+
+        // Toronto is 79.38° W, so local mean time is 17.5 minutes behind EST (75° W)
+        let diff = civil.dhuhr() - lmt.dhuhr();
+        assert!((diff.num_seconds() - (17 * 60 + 32)).abs() <= 1, "{}", diff);
+        assert_eq!(
+            lmt.utc_offset().local_minus_utc(),
+            (-79.3839347_f64 / 15_f64 * 3600_f64).round() as i32
+        );
+        // The same instants, on different clocks
+        assert!(
+            (lmt.to_utc(lmt.dhuhr()) - civil.to_utc(civil.dhuhr()))
+                .num_seconds()
+                .abs()
+                <= 1
+        );
+        assert!(lmt.to_local(lmt.dhuhr()).is_none());
+    }
+
+    #[test]
+    fn test_sun_altitude() {
+        let pt = fixtures::toronto()
+            .with_rounding(&Rounding::Seconds)
+            .with_refinement(true);
+
+        // Sunrise is when the upper limb rises, with refraction, 0.833° below the horizon
+        let sunrise = pt.sun_altitude(pt.sunrise());
+        assert!((sunrise + 0.833).abs() < 0.02, "{}", sunrise);
+        let fajr = pt.sun_altitude(pt.fajr());
+        assert!((fajr + 15_f64).abs() < 0.02, "{}", fajr);
+
+        // The sun is highest at dhuhr
+        let dhuhr = pt.sun_altitude(pt.dhuhr());
+        let (_, decl) = pt.sun_coords_at(12.5);
+        assert!(
+            (dhuhr - (90_f64 - 43.6534817 + decl)).abs() < 0.05,
+            "{}",
+            dhuhr
+        );
+        for minutes in [-30, -5, 5, 30] {
+            assert!(pt.sun_altitude(pt.dhuhr() + Duration::minutes(minutes)) < dhuhr);
+        }
+        assert!(pt.sun_altitude(NaiveTime::MIN) < -55_f64);
+    }
+
+    #[test]
+    fn test_last_third() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+        let next_fajr = pt
+            .clone()
+            .with_date(&date.succ_opt().expect("Error!"))
+            .datetime(&Timing::Fajr);
+
+        let last_third = pt.datetime(&Timing::LastThird);
+        assert_eq!(last_third.time(), pt.last_third());
+        assert_eq!(last_third.date_naive(), date.succ_opt().expect("Error!"));
+        assert!(last_third > pt.datetime(&Timing::Midnight));
+        assert!(last_third < next_fajr);
+
+        // Two thirds of the night from maghrib is one third of the night before sunrise
+        let night = pt.datetime(&Timing::Midnight) - pt.datetime(&Timing::Maghrib);
+        let third = last_third - pt.datetime(&Timing::Midnight);
+        assert!((night.num_minutes() / 3 - third.num_minutes()).abs() <= 1);
+    }
+
+    #[test]
+    fn test_with_midnight_method() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let standard = PrayerTimes::new(35.6892, 51.389)
+            .with_date(&date)
+            .with_offset_hours(3.5);
+        let jafari = standard
+            .clone()
+            .with_midnight_method(&MidnightMethod::Jafari);
+
+        // The night ends at fajr rather than sunrise, so midnight is earlier
+        assert_ne!(standard.midnight(), jafari.midnight());
+        assert!(jafari.datetime(&Timing::Midnight) < standard.datetime(&Timing::Midnight));
+        assert_eq!(jafari.midnight(), jafari.half_night());
+
+        // The Jafari authority defaults to the fajr based midnight
+        let authority = standard.clone().with_authority(&Authority::Jafari);
+        assert_eq!(authority.midnight(), authority.half_night());
+        assert_eq!(
+            authority
+                .clone()
+                .with_midnight_method(&MidnightMethod::Standard)
+                .midnight(),
+            standard.midnight()
+        );
+    }
+
+    #[test]
+    fn test_moonsighting_committee() {
+        // Near the equator the 18 degree times fall within the seasonal limits
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let makkah = PrayerTimes::new(21.4225, 39.8262)
+            .with_date(&date)
+            .with_offset_hours(3.0)
+            .with_authority(&Authority::Karachi);
+        let moonsighting = makkah
+            .clone()
+            .with_authority(&Authority::MoonsightingCommittee);
+        assert_eq!(moonsighting.fajr(), makkah.fajr());
+        assert_eq!(moonsighting.isha(), makkah.isha());
+
+        // London in June never reaches 18 degrees, the seasonal intervals are used instead
+        // (fajr ~120 min before sunrise, isha ~80 min after sunset, 182 days since the solstice)
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let london = PrayerTimes::new(51.5074, -0.1278)
+            .with_date(&solstice)
+            .with_offset_hours(1.0)
+            .with_authority(&Authority::MoonsightingCommittee);
+        assert_eq!(london.days_since_solstice(), 182);
+        assert!(london
+            .clone()
+            .with_authority(&Authority::Karachi)
+            .fajr_hour()
+            .is_nan());
+        let fajr_gap = (london.sunrise() - london.fajr()).num_minutes();
+        let isha_gap = (london.isha() - london.maghrib()).num_minutes();
+        assert!(
+            (fajr_gap - 120).abs() <= 1,
+            "fajr {} min before sunrise",
+            fajr_gap
+        );
+        assert!(
+            (isha_gap - 80).abs() <= 1,
+            "isha {} min after sunset",
+            isha_gap
+        );
+
+        // Above 55 degrees a seventh of the night is used
+        let oslo = PrayerTimes::new(59.9139, 10.7522)
+            .with_date(&date)
+            .with_offset_hours(1.0)
+            .with_authority(&Authority::MoonsightingCommittee);
+        let night = oslo.night_hours();
+        assert!((oslo.sunrise_hour() - oslo.fajr_hour() - night / 7.0).abs() < 1e-9);
+        assert!((oslo.isha_hour() - oslo.sunset_hour() - night / 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_published_references() {
+        // Published times are rounded to the minute (up to 30s off), and implementations differ by up to a minute
+        for reference in [fixtures::raleigh_isna(), fixtures::raleigh_moonsighting()] {
+            for (timing, published) in reference.times {
+                let diff = (reference.pt.timing(&timing) - published)
+                    .num_seconds()
+                    .abs();
+                assert!(diff <= 90, "{} is {}s from {}", timing, diff, published);
+            }
+        }
+    }
+
+    #[test]
+    fn test_day_and_night_duration() {
+        let pt = fixtures::toronto().with_rounding(&Rounding::Seconds);
+        let (day, night) = (pt.day_duration(), pt.night_duration());
+        assert!((day + night - Duration::hours(24)).num_seconds().abs() <= 1);
+        // Winter nights are longer than the days
+        assert!(night > day);
+        assert!(((pt.maghrib() - pt.sunrise()) - day).num_seconds().abs() <= 1);
+
+        let summer = pt
+            .clone()
+            .with_date(&NaiveDate::from_ymd_opt(2024, 6, 21).expect("Error!"))
+            .with_offset_hours(-4.0);
+        assert!(summer.day_duration() > summer.night_duration());
+
+        // Tromsø has no sunrise in December and no sunset in June
+        let tromso = PrayerTimes::new(69.6492, 18.9553).with_offset_hours(1.0);
+        let polar_night = tromso
+            .clone()
+            .with_date(&NaiveDate::from_ymd_opt(2024, 12, 21).expect("Error!"));
+        assert_eq!(polar_night.day_duration(), Duration::zero());
+        assert_eq!(polar_night.night_duration(), Duration::hours(24));
+        let midnight_sun = tromso.with_date(&NaiveDate::from_ymd_opt(2024, 6, 21).expect("Error!"));
+        assert_eq!(midnight_sun.day_duration(), Duration::hours(24));
+    }
+
+    #[test]
+    fn test_minutes_of_day() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+
+        // Dhuhr is around noon (12:32)
+        let dhuhr = pt.minutes_of_day(&Timing::Dhuhr);
+        assert!((690..=780).contains(&dhuhr));
+        assert_eq!(dhuhr, 12 * 60 + 32);
+        assert_eq!(pt.minutes_of_day(&Timing::Fajr), 6 * 60 + 3);
+
+        // Midnight (00:32) counts from the start of the next day
+        assert_eq!(pt.minutes_of_day(&Timing::Midnight), 32);
+        assert_eq!(
+            pt.datetime(&Timing::Midnight).date_naive(),
+            date.succ_opt().expect("Error!")
+        );
+
+        // Seconds are dropped
+        let exact = pt.clone().with_rounding(&Rounding::Seconds);
+        let time = exact.dhuhr();
+        assert_eq!(
+            exact.minutes_of_day(&Timing::Dhuhr),
+            time.hour() * 60 + time.minute()
+        );
+        for timing in Timing::list() {
+            assert!(pt.minutes_of_day(&timing) < 24 * 60);
+            assert_eq!(
+                pt.try_minutes_of_day(&timing),
+                Ok(pt.minutes_of_day(&timing))
+            );
+        }
+        assert_eq!(pt.try_minutes_of_day(&Timing::Midnight), Ok(32));
+
+        // Midnight sun in Longyearbyen (78°N), errors instead of panicking
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let polar = PrayerTimes::new(78.2232, 15.6267)
+            .with_date(&solstice)
+            .with_offset_hours(2.0)
+            .with_authority(&Authority::MWL);
+        assert_eq!(
+            polar.try_minutes_of_day(&Timing::Isha),
+            Err(SalahError::NoSunEvent)
+        );
+        let dhuhr = polar.dhuhr();
+        assert_eq!(
+            polar.try_minutes_of_day(&Timing::Dhuhr),
+            Ok(dhuhr.hour() * 60 + dhuhr.minute())
+        );
+    }
+
+    #[test]
+    fn test_times_pretty() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+        let times = pt.all();
+        let pretty = times.pretty(date, &pt.utc_offset(), &Timing::list(), "%H:%M", true);
+        for timing in Timing::list() {
+            assert!(pretty.contains(timing.to_str()), "{:?}", timing);
+        }
+        assert_eq!(pretty.lines().nth(1), Some("fajr 06:03"));
+        assert_eq!(pretty.lines().last(), Some("last-third 02:49"));
+
+        let unlabeled = times.pretty(
+            date,
+            &pt.utc_offset(),
+            &[Timing::Dhuhr, Timing::Midnight],
+            "%Y-%m-%d %I:%M %p %z",
+            false,
+        );
+        assert_eq!(
+            unlabeled,
+            "2024-02-11 12:32 PM -0500\n2024-02-12 12:32 AM -0500"
+        );
+
+        // Timings with no solution are printed as `missing`, midnight sun in Longyearbyen (78°N)
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let polar = PrayerTimes::new(78.2232, 15.6267)
+            .with_date(&solstice)
+            .with_offset_hours(2.0)
+            .with_authority(&Authority::MWL);
+        let checked = polar.all_checked();
+        let pretty = checked.pretty(
+            solstice,
+            &polar.utc_offset(),
+            &[Timing::Fajr, Timing::Dhuhr],
+            "%H:%M",
+            true,
+            "N/A",
+        );
+        let dhuhr = polar.dhuhr().format("%H:%M");
+        assert_eq!(pretty, format!("fajr N/A\ndhuhr {}", dhuhr));
+    }
+
+    #[test]
+    fn test_date_line() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let at = |h: u32, m: u32| NaiveTime::from_hms_opt(h, m, 0).expect("Error!");
+        // Kiritimati is at UTC+14 but 157°W, so its solar noon is 36.5 hours after UTC midnight before normalization.
+        // Baker Island is at UTC-12 near 176°W, right before the date line.
+        for (lat, lng, offset, sunrise, dhuhr, maghrib) in [
+            (1.87, -157.4, 14.0, at(6, 42), at(12, 44), at(18, 45)),
+            (0.19, -176.48, -12.0, at(5, 57), at(12, 0), at(18, 3)),
+        ] {
+            for refine in [false, true] {
+                let pt = PrayerTimes::new(lat, lng)
+                    .with_date(&date)
+                    .with_offset_hours(offset)
+                    .with_refinement(refine);
+                assert!((pt.sunrise() - sunrise).num_minutes().abs() <= 1, "{}", lng);
+                assert!((pt.dhuhr() - dhuhr).num_minutes().abs() <= 1, "{}", lng);
+                assert!((pt.maghrib() - maghrib).num_minutes().abs() <= 1, "{}", lng);
+
+                // Every timing is on the calculation date, in order, and dhuhr is the sun's highest point
+                let datetimes: Vec<DateTime<FixedOffset>> =
+                    pt.iter().map(|(timing, _)| pt.datetime(&timing)).collect();
+                assert!(datetimes.windows(2).all(|pair| pair[0] <= pair[1]));
+                assert_eq!(pt.datetime(&Timing::Fajr).date_naive(), date);
+                assert_eq!(pt.datetime(&Timing::Isha).date_naive(), date);
+                let noon = pt.sun_altitude(pt.dhuhr());
+                assert!(noon > pt.sun_altitude(pt.dhuhr() - Duration::minutes(10)));
+                assert!(noon > pt.sun_altitude(pt.dhuhr() + Duration::minutes(10)));
+            }
+        }
+
+        // Kiritimati's date is the previous date at UTC-10 (its timezone before 1995), so with the sun
+        // coordinates evaluated at the right instants the hours are the same
+        let yesterday = NaiveDate::from_ymd_opt(2024, 2, 10).expect("Error!");
+        let east = PrayerTimes::new(1.87, -157.4)
+            .with_date(&date)
+            .with_offset_hours(14.0)
+            .with_refinement(true);
+        let west = PrayerTimes::new(1.87, -157.4)
+            .with_date(&yesterday)
+            .with_offset_hours(-10.0)
+            .with_refinement(true);
+        for (east_hour, west_hour) in [
+            (east.sunrise_hour(), west.sunrise_hour()),
+            (east.zenith(), west.zenith()),
+            (east.sunset_hour(), west.sunset_hour()),
+        ] {
+            let diff =
+                math::time::normalize_hour(east_hour) - math::time::normalize_hour(west_hour);
+            assert!(diff.abs() * 3600_f64 < 0.01, "{} seconds", diff * 3600_f64);
+        }
+
+        // Solar noon in Kiritimati is late the previous evening in UTC
+        let kiritimati = PrayerTimes::new(1.87, -157.4)
+            .with_date(&date)
+            .with_offset_hours(14.0);
+        assert_eq!(
+            kiritimati.datetime(&Timing::Dhuhr).naive_utc().date(),
+            NaiveDate::from_ymd_opt(2024, 2, 10).expect("Error!")
+        );
+    }
+
+    #[test]
+    fn test_longitude_convention() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        // 270° in a [0, 360) convention is 90° west, i.e. -90° east-positive
+        let west = PrayerTimes::new(30.0, -90.0)
+            .with_date(&date)
+            .with_offset_hours(-6.0);
+        let wrapped = PrayerTimes::new(30.0, 270.0)
+            .with_date(&date)
+            .with_offset_hours(-6.0);
+        assert_eq!(wrapped.lng(), -90.0);
+        for timing in Timing::list() {
+            assert_eq!(
+                west.timing(&timing),
+                wrapped.timing(&timing),
+                "{:?}",
+                timing
+            );
+        }
+        let relocated = PrayerTimes::new(0.0, 0.0)
+            .with_location(30.0, 270.0)
+            .with_date(&date)
+            .with_offset_hours(-6.0);
+        assert_eq!(relocated.dhuhr(), west.dhuhr());
+
+        let built = PrayerTimes::builder(30.0, 270.0)
+            .with_date(&date)
+            .with_offset_hours(-6.0)
+            .build()
+            .expect("Error!");
+        assert_eq!(built.dhuhr(), west.dhuhr());
+
+        // Longitudes outside of [-180, 360) are not wrapped, and rejected when built
+        assert_eq!(PrayerTimes::new(30.0, 400.0).lng(), 400.0);
+        assert_eq!(PrayerTimes::new(30.0, -200.0).lng(), -200.0);
+        assert_eq!(
+            PrayerTimes::new(30.0, 0.0).with_location(30.0, 359.0).lng(),
+            -1.0
+        );
+        assert!(PrayerTimes::builder(30.0, 400.0).build().is_err());
+    }
+
+    #[test]
+    fn test_iter() {
+        let pt = fixtures::toronto();
+        let timings: Vec<(Timing, NaiveTime)> = pt.iter().collect();
+        assert_eq!(timings.len(), Timing::list().len());
+        // Isha (19:01) comes before midnight (00:32), which is on the next day
+        let position = |timing: Timing| timings.iter().position(|(t, _)| *t == timing);
+        assert!(position(Timing::Isha) < position(Timing::Midnight));
+        assert_eq!(timings.last().map(|(t, _)| *t), Some(Timing::LastThird));
+
+        // In London around the summer solstice, isha is past midnight on the clock but still follows maghrib
+        let london = PrayerTimes::new(51.5074, -0.1278)
+            .with_date(&NaiveDate::from_ymd_opt(2024, 6, 21).expect("Error!"))
+            .with_offset_hours(1.0);
+        let timings: Vec<(Timing, NaiveTime)> = london.iter().collect();
+        let datetimes: Vec<DateTime<FixedOffset>> =
+            timings.iter().map(|(t, _)| london.datetime(t)).collect();
+        assert!(datetimes.windows(2).all(|pair| pair[0] <= pair[1]));
+        let isha = timings
+            .iter()
+            .find(|(t, _)| *t == Timing::Isha)
+            .map(|(_, time)| *time)
+            .expect("Error!");
+        assert!(isha < london.maghrib());
+        let order: Vec<Timing> = timings.iter().map(|(t, _)| *t).collect();
+        let maghrib = order.iter().position(|t| *t == Timing::Maghrib);
+        assert!(maghrib < order.iter().position(|t| *t == Timing::Isha));
+    }
+
+    #[test]
+    fn test_maliki_hanbali_asr() {
+        let shafi = fixtures::toronto().with_school(&School::Shafi);
+        for school in [School::Maliki, School::Hanbali] {
+            let pt = shafi.clone().with_school(&school);
+            assert_eq!(pt.all(), shafi.all(), "{}", school.to_str());
+        }
+        let hanafi = shafi.clone().with_school(&School::Hanafi);
+        assert_ne!(hanafi.asr(), shafi.asr());
+    }
+
+    #[test]
+    fn test_isha_interval() {
+        let interval = std::time::Duration::from_secs(75 * 60);
+        for auth in Authority::list() {
+            let pt = fixtures::toronto()
+                .with_authority(&auth)
+                .with_ramadan(true)
+                .with_isha_angle_override(Some(17_f64))
+                .with_isha_interval(Some(interval));
+            let expected = Duration::minutes(75);
+            assert_eq!(pt.isha() - pt.maghrib(), expected, "{}", auth);
+            assert_eq!(pt.all().isha - pt.all().maghrib, expected, "{}", auth);
+            let checked = pt.all_checked();
+            assert_eq!(
+                checked.isha.expect("Error!") - checked.maghrib.expect("Error!"),
+                expected
+            );
+        }
+
+        // Unset, the authority's isha is used again
+        let isna = fixtures::toronto();
+        let reset = isna
+            .clone()
+            .with_isha_interval(Some(interval))
+            .with_isha_interval(None);
+        assert_eq!(reset.isha(), isna.isha());
+    }
+
+    #[test]
+    fn test_maghrib_delay() {
+        let isna = fixtures::toronto();
+        let jafari = isna.clone().with_authority(&Authority::Jafari);
+
+        assert!(jafari.maghrib() > isna.maghrib());
+        assert_eq!(jafari.maghrib() - isna.maghrib(), Duration::minutes(4));
+        assert_eq!(jafari.all().maghrib, jafari.maghrib());
+        assert_eq!(jafari.all_checked().maghrib, Some(jafari.maghrib()));
+
+        let no_delay = jafari
+            .clone()
+            .with_maghrib_delay(&std::time::Duration::ZERO);
+        assert_eq!(no_delay.maghrib(), isna.maghrib());
+        // Midnight is still measured from sunset
+        assert_eq!(jafari.midnight(), no_delay.midnight());
+
+        // Interval based isha follows the delayed maghrib
+        let makkah = isna
+            .clone()
+            .with_authority(&Authority::Makkah)
+            .with_maghrib_delay(&std::time::Duration::from_secs(5 * 60));
+        assert_eq!(makkah.maghrib() - isna.maghrib(), Duration::minutes(5));
+        assert_eq!(makkah.isha() - makkah.maghrib(), Duration::minutes(90));
+        assert_eq!(makkah.all().isha, makkah.isha());
+        assert_eq!(makkah.all_checked().isha, Some(makkah.isha()));
+    }
+
+    #[test]
+    fn test_to_utc() {
+        use chrono::{NaiveDateTime, Utc};
+
+        // Clocks spring forward at 02:00 in Toronto
+        let date = NaiveDate::from_ymd_opt(2024, 3, 10).expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347)
+            .with_date(&date)
+            .with_timezone(&"America/Toronto".parse().expect("Error!"));
+        let at = |d: NaiveDate, h: u32, m: u32| -> NaiveDateTime {
+            return d.and_hms_opt(h, m, 0).expect("Error!");
+        };
+
+        let fajr = pt.fajr();
+        assert_eq!(
+            pt.to_utc(fajr).naive_utc(),
+            date.and_time(fajr) + Duration::hours(4)
+        );
+        assert_eq!(
+            pt.to_local(fajr).expect("Error!").with_timezone(&Utc),
+            pt.to_utc(fajr)
+        );
+        // Before the change, and skipped by it (read as EST)
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).expect("Error!");
+        assert_eq!(pt.to_utc(time(1, 30)).naive_utc(), at(date, 6, 30));
+        assert_eq!(pt.to_utc(time(2, 30)).naive_utc(), at(date, 7, 30));
+
+        let next_day = date.succ_opt().expect("Error!");
+        let all = pt.all_utc();
+        assert_eq!(all.len(), Timing::list().len());
+        for (timing, utc) in &all {
+            assert_eq!(*utc, pt.datetime(timing).with_timezone(&Utc), "{}", timing);
+        }
+        let midnight = all
+            .iter()
+            .find(|(timing, _)| *timing == Timing::Midnight)
+            .expect("Error!");
+        assert_eq!(midnight.1.date_naive(), next_day);
+
+        // Fixed offsets need no timezone
+        let fixed = pt.with_offset_hours(5.5);
+        assert!(fixed.to_local(time(12, 0)).is_none());
+        assert_eq!(fixed.to_utc(time(12, 0)).naive_utc(), at(date, 6, 30));
+    }
+
+    #[test]
+    fn test_far_west_of_timezone() {
+        use chrono::Timelike;
+
+        // Kashgar in far-western Xinjiang keeps Beijing time, about 3 hours ahead of its solar time
+        let summer = NaiveDate::from_ymd_opt(2024, 6, 21).expect("Error!");
+        let pt = PrayerTimes::new(39.4704, 75.9898)
+            .with_date(&summer)
+            .with_timezone(&"Asia/Shanghai".parse().expect("Error!"));
+        let times = pt.all();
+        assert!(times.fajr < times.sunrise && times.sunrise < times.dhuhr);
+        assert_eq!(times.dhuhr.hour(), 14);
+        // Isha is past midnight, on the next day
+        assert!(times.isha < times.fajr);
+        assert_eq!(
+            pt.datetime(&Timing::Isha).date_naive(),
+            summer.succ_opt().expect("Error!")
+        );
+        assert!(!pt.isha_overlaps_fajr());
+
+        // An odd offset for the longitude wraps the morning timings back before midnight
+        let winter = NaiveDate::from_ymd_opt(2024, 12, 21).expect("Error!");
+        let pt = PrayerTimes::new(39.4704, 75.9898)
+            .with_date(&winter)
+            .with_offset_hours(-3.0);
+        let times = pt.all();
+        assert!(times.fajr > times.dhuhr);
+        assert!(times.fajr < times.sunrise);
+        let datetime = |timing: Timing| pt.datetime(&timing).naive_local();
+        assert_eq!(
+            datetime(Timing::Fajr).date(),
+            winter.pred_opt().expect("Error!")
+        );
+        assert_eq!(datetime(Timing::Dhuhr).date(), winter);
+        assert!(datetime(Timing::Fajr) < datetime(Timing::Sunrise));
+        assert!(datetime(Timing::Sunrise) < datetime(Timing::Dhuhr));
+        assert!(datetime(Timing::Dhuhr) < datetime(Timing::Maghrib));
+        assert_eq!(
+            times.datetime(winter, &Timing::Fajr),
+            datetime(Timing::Fajr)
+        );
+    }
+
+    #[test]
+    fn test_angle_overrides() {
+        let isna = fixtures::toronto().with_authority(&Authority::ISNA);
+        let mwl = isna.clone().with_authority(&Authority::MWL);
+
+        // Overriding fajr to 18 on ISNA only changes fajr (to MWL's, which also uses 18)
+        let fajr_18 = isna.clone().with_fajr_angle_override(Some(18_f64));
+        assert_eq!(fajr_18.fajr(), mwl.fajr());
+        assert_ne!(fajr_18.fajr(), isna.fajr());
+        assert_eq!(fajr_18.isha(), isna.isha());
+        assert_eq!(fajr_18.sunrise(), isna.sunrise());
+        assert_eq!(fajr_18.with_fajr_angle_override(None).fajr(), isna.fajr());
+
+        let isha_17 = isna.clone().with_isha_angle_override(Some(17_f64));
+        assert_eq!(isha_17.isha(), mwl.isha());
+        assert_eq!(isha_17.fajr(), isna.fajr());
+
+        // Replaces Makkah's interval isha, but keeps its fajr
+        let makkah = isna.clone().with_authority(&Authority::Makkah);
+        let overridden = makkah.clone().with_isha_angle_override(Some(17_f64));
+        assert_eq!(overridden.isha(), mwl.isha());
+        assert_eq!(overridden.fajr(), makkah.fajr());
+    }
+
+    #[test]
+    fn test_new_with_clock() {
+        use crate::datetime::FixedClock;
+        use chrono::{TimeZone, Utc};
+
+        // 2024-02-12 03:00 UTC is 22:00 on the 11th in Toronto
+        let clock = FixedClock(Utc.with_ymd_and_hms(2024, 2, 12, 3, 0, 0).unwrap());
+        let pt = PrayerTimes::new_with_clock(43.6534817, -79.3839347, &clock);
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        assert_eq!(pt.date(), date);
+        assert_eq!(pt.utc_offset().local_minus_utc(), -5 * 3600);
+        assert_eq!(pt.fajr(), NaiveTime::from_hms_opt(6, 3, 0).expect("Error!"));
+        // The clock's instant is the default for next and current prayer
+        assert_eq!(
+            pt.next_prayer(None),
+            (
+                Timing::Fajr,
+                pt.clone()
+                    .with_date(&date.succ_opt().expect("Error!"))
+                    .fajr()
+            )
+        );
+        assert_eq!(pt.current_prayer(None), (Timing::Isha, pt.isha()));
+    }
+
+    #[test]
+    fn test_with_datetime() {
+        use chrono::TimeZone;
+
+        let tz: chrono_tz::Tz = "America/Toronto".parse().expect("Error!");
+        let now = tz
+            .with_ymd_and_hms(2024, 2, 11, 16, 30, 0)
+            .single()
+            .expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347).with_datetime(&now);
+        assert_eq!(pt.date(), now.date_naive());
+        assert_eq!(pt.utc_offset().local_minus_utc(), -5 * 3600);
+        assert_eq!(pt.next_prayer(None), (Timing::Maghrib, pt.maghrib()));
+        assert_eq!(pt.current_prayer(None), (Timing::Asr, pt.asr()));
+        // An explicit time still takes precedence
+        let early = NaiveTime::from_hms_opt(4, 0, 0).expect("Error!");
+        assert_eq!(pt.next_prayer(early), (Timing::Fajr, pt.fajr()));
+
+        // The instant is the same moment in another timezone, 21:30 UTC is still before maghrib (22:41 UTC)
+        let utc = pt.with_offset_hours(0.0);
+        assert_eq!(
+            utc.next_prayer(None),
+            (
+                Timing::Maghrib,
+                NaiveTime::from_hms_opt(22, 41, 0).expect("Error!")
+            )
+        );
+
+        // 21:30 EST is already the next day in UTC, after the configured date's isha (00:01 UTC)
+        let evening = tz
+            .with_ymd_and_hms(2024, 2, 11, 21, 30, 0)
+            .single()
+            .expect("Error!");
+        let utc = PrayerTimes::new(43.6534817, -79.3839347)
+            .with_datetime(&evening)
+            .with_offset_hours(0.0);
+        let next_day = utc
+            .clone()
+            .with_date(&NaiveDate::from_ymd_opt(2024, 2, 12).expect("Error!"));
+        assert_eq!(utc.current_prayer(None), (Timing::Isha, utc.isha()));
+        assert_eq!(utc.next_prayer(None), (Timing::Fajr, next_day.fajr()));
+    }
+
+    #[test]
+    fn test_with_location() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let pt = PrayerTimes::default()
+            .with_date(&date)
+            .with_offset_hours(-5.0)
+            .with_location(43.6534817, -79.3839347);
+        let expected = fixtures::toronto();
+        assert_eq!(pt.all(), expected.all());
+        assert_eq!(pt.fajr(), NaiveTime::from_hms_opt(6, 3, 0).expect("Error!"));
+
+        // Moving the location recomputes the timings
+        let makkah = pt.clone().with_location(21.4225, 39.8262);
+        assert_ne!(makkah.fajr(), pt.fajr());
+        assert_eq!(
+            makkah.with_location(43.6534817, -79.3839347).all(),
+            pt.all()
+        );
+    }
+
+    #[test]
+    fn test_with_refinement() {
+        // Near the equinox the declination changes fastest, and at 60°N fajr and isha are far from noon
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).expect("Error!");
+        let single = PrayerTimes::new(60.1699, 24.9384)
+            .with_date(&date)
+            .with_offset_hours(2.0)
+            .with_authority(&Authority::MWL)
+            .with_rounding(&Rounding::Seconds);
+        let refined = single.clone().with_refinement(true);
+
+        for (single_hour, refined_hour) in [
+            (single.fajr_hour(), refined.fajr_hour()),
+            (single.sunrise_hour(), refined.sunrise_hour()),
+            (single.sunset_hour(), refined.sunset_hour()),
+            (single.isha_hour(), refined.isha_hour()),
+        ] {
+            let diff = (single_hour - refined_hour).abs() * 3600_f64;
+            assert!(diff > 1_f64 && diff < 300_f64, "{} seconds", diff);
+        }
+
+        // The second pass converges, a third one moves the event by well under a second
+        let sunset = refined.sunset_hour();
+        let (eqt, decl) = refined.sun_coords_at(sunset);
+        let third = astro::horizon_hour_angle_from_decl(
+            refined.horizon_angle(),
+            decl,
+            astro::zenith_from_eqt(eqt, refined.lng, refined.tz_offset),
+            refined.lat,
+            astro::HorizonDirection::Sunset,
+        );
+        assert!((third - sunset).abs() * 3600_f64 < 0.5);
+
+        // Dhuhr is at noon, so both agree closely
+        let dhuhr_diff = (single.zenith() - refined.zenith()).abs() * 3600_f64;
+        assert!(dhuhr_diff < 60_f64);
+    }
+
+    #[test]
+    fn test_builder() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let pt = PrayerTimes::builder(43.6534817, -79.3839347)
+            .with_date(&date)
+            .with_offset_hours(-5.0)
+            .with_school(&School::Shafi)
+            .build()
+            .expect("Error!");
+        assert_eq!(pt.date(), date);
+        assert_eq!(pt.fajr(), NaiveTime::from_hms_opt(6, 3, 0).expect("Error!"));
+        assert_eq!(
+            pt.asr(),
+            NaiveTime::from_hms_opt(15, 16, 0).expect("Error!")
+        );
+
+        for (lat, lng) in [
+            (200.0, 0.0),
+            (-90.5, 0.0),
+            (0.0, 360.0),
+            (0.0, -180.5),
+            (f64::NAN, 0.0),
+            (0.0, f64::INFINITY),
+        ] {
+            assert!(
+                matches!(
+                    PrayerTimes::builder(lat, lng).build(),
+                    Err(SalahError::OutOfRange(_))
+                ),
+                "({}, {})",
+                lat,
+                lng
+            );
+        }
+        assert!(PrayerTimes::builder(90.0, -180.0).build().is_ok());
+
+        for hours in [15.0, -24.0, f64::NAN] {
+            assert!(matches!(
+                PrayerTimes::builder(0.0, 0.0)
+                    .with_offset_hours(hours)
+                    .build(),
+                Err(SalahError::OutOfRange(_))
+            ));
+        }
+        assert!(matches!(
+            PrayerTimes::builder(0.0, 0.0)
+                .with_elevation(f64::NAN)
+                .build(),
+            Err(SalahError::OutOfRange(_))
+        ));
+        let ancient = NaiveDate::from_ymd_opt(-100, 1, 1).expect("Error!");
+        assert!(matches!(
+            PrayerTimes::builder(0.0, 0.0).with_date(&ancient).build(),
+            Err(SalahError::InvalidDate(_))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not in [-14, 14]")]
+    fn test_with_offset_hours_out_of_range() {
+        let _ = PrayerTimes::new(0.0, 0.0).with_offset_hours(24.0);
+    }
+
+    #[test]
+    fn test_try_timing() {
+        let pt = fixtures::toronto().with_authority(&Authority::Jafari);
+        for timing in Timing::list() {
+            assert_eq!(
+                pt.try_timing(&timing),
+                Ok(pt.timing(&timing)),
+                "{:?}",
+                timing
+            );
+        }
+
+        // Midnight sun in Longyearbyen (78°N), errors instead of panicking
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let polar = PrayerTimes::new(78.2232, 15.6267)
+            .with_date(&solstice)
+            .with_offset_hours(2.0)
+            .with_authority(&Authority::MWL);
+        assert_eq!(polar.try_timing(&Timing::Fajr), Err(SalahError::NoSunEvent));
+        assert_eq!(
+            polar.try_timing(&Timing::Maghrib),
+            Err(SalahError::NoSunEvent)
+        );
+        assert_eq!(
+            polar.try_timing(&Timing::Midnight),
+            Err(SalahError::NoSunEvent)
+        );
+        assert!(polar.try_timing(&Timing::Dhuhr).is_ok());
+        let checked = polar.all_checked();
+        for timing in Timing::list() {
+            assert_eq!(
+                polar.try_timing(&timing).ok(),
+                checked.get(&timing),
+                "{:?}",
+                timing
+            );
+        }
+
+        // Polar night
+        let winter = NaiveDate::from_ymd_opt(2024, 12, 21).expect("Error!");
+        let night = polar.clone().with_date(&winter);
+        assert_eq!(
+            night.try_timing(&Timing::Sunrise),
+            Err(SalahError::NoSunEvent)
+        );
+        assert_eq!(night.try_timing(&Timing::Asr), Err(SalahError::NoSunEvent));
+    }
+
+    #[test]
+    fn test_all_checked() {
+        let pt = fixtures::toronto();
+        let all = pt.all();
+        let checked = pt.all_checked();
+        for timing in Timing::list() {
+            assert_eq!(
+                checked.get(&timing),
+                Some(all.get(&timing)),
+                "{}",
+                timing.to_str()
+            );
+        }
+
+        // Midnight sun in Longyearbyen (78°N), the sun never sets
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let polar = PrayerTimes::new(78.2232, 15.6267)
+            .with_date(&solstice)
+            .with_offset_hours(2.0)
+            .with_authority(&Authority::MWL);
+        let checked = polar.all_checked();
+        assert!(checked.dhuhr.is_some());
+        assert!(checked.asr.is_some());
+        assert_eq!(checked.sunrise, None);
+        assert_eq!(checked.duha, None);
+        assert_eq!(checked.maghrib, None);
+        assert_eq!(checked.fajr, None);
+        assert_eq!(checked.imsak, None);
+        assert_eq!(checked.isha, None);
+        assert_eq!(checked.midnight, None);
+        assert_eq!(checked.last_third, None);
+
+        // Polar night, the sun never rises
+        let winter = NaiveDate::from_ymd_opt(2024, 12, 21).expect("Error!");
+        let checked = polar.clone().with_date(&winter).all_checked();
+        assert_eq!(checked.sunrise, None);
+        assert_eq!(checked.asr, None);
+        assert!(checked.dhuhr.is_some());
+    }
+
+    #[test]
+    fn test_all_matches_try_timing() {
+        let pt = fixtures::toronto();
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let configs = [
+            pt.clone(),
+            pt.clone()
+                .with_midnight_method(&MidnightMethod::Jafari)
+                .with_isha_interval(Some(std::time::Duration::from_secs(90 * 60))),
+            PrayerTimes::new(78.2232, 15.6267)
+                .with_date(&solstice)
+                .with_offset_hours(2.0)
+                .with_authority(&Authority::MWL),
+        ];
+        for pt in configs {
+            let checked = pt.all_checked();
+            for timing in Timing::list() {
+                assert_eq!(
+                    checked.get(&timing),
+                    pt.try_timing(&timing).ok(),
+                    "{}",
+                    timing.to_str()
+                );
+            }
+        }
+        let all = pt.all();
+        for timing in Timing::list() {
+            assert_eq!(all.get(&timing), pt.timing(&timing), "{}", timing.to_str());
+        }
+    }
+
+    #[test]
+    fn test_year_extremes() {
+        use chrono::Datelike;
+
+        let pt = PrayerTimes::new(43.6534817, -79.3839347).with_offset_hours(-5.0);
+        let summary = pt.year_extremes(2024);
+        assert_eq!(summary.year, 2024);
+        assert_eq!(summary.days, 366);
+        assert_eq!(pt.year_extremes(2023).days, 365);
+
+        // The longest fast is near the summer solstice and the shortest near the winter solstice
+        let near = |date: NaiveDate, month: u32, day: u32| {
+            let solstice = NaiveDate::from_ymd_opt(2024, month, day).expect("Error!");
+            return (date - solstice).num_days().abs() <= 10;
+        };
+        assert!(
+            near(summary.longest_fast.0, 6, 21),
+            "{:?}",
+            summary.longest_fast
+        );
+        assert!(
+            near(summary.shortest_fast.0, 12, 21),
+            "{:?}",
+            summary.shortest_fast
+        );
+        assert!(summary.longest_fast.1 > Duration::hours(17));
+        assert!(summary.shortest_fast.1 < Duration::hours(11));
+
+        // Fajr is earliest in the summer and isha latest
+        assert_eq!(summary.earliest_fajr.0.month(), 6);
+        assert!(summary.latest_isha.0.month() == 6 || summary.latest_isha.0.month() == 7);
+        assert!(summary.earliest_fajr.1 < summary.latest_fajr.1);
+        assert!(summary.earliest_isha.1 < summary.latest_isha.1);
+
+        let fajr = pt.clone().with_date(&summary.earliest_fajr.0).fajr();
+        assert_eq!(summary.earliest_fajr.1, fajr);
+    }
+
+    #[test]
+    fn test_range() {
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1).expect("Error!");
+        let end = NaiveDate::from_ymd_opt(2024, 3, 31).expect("Error!");
+        let pt = PrayerTimes::new(43.6534817, -79.3839347).with_offset_hours(-5.0);
+
+        let days: Vec<(NaiveDate, Times)> = pt.range(start, end).collect();
+        assert_eq!(days.len(), 31);
+        assert_eq!(days[0].0, start);
+        assert_eq!(days[30].0, end);
+        assert_eq!(days[10].1, pt.clone().with_date(&days[10].0).all());
+        assert_eq!(days[10].1.get(&Timing::Fajr), days[10].1.fajr);
+        assert_eq!(days[10].1.get(&Timing::LastThird), days[10].1.last_third);
+        assert_eq!(
+            days[10].1.datetime(days[10].0, &Timing::Midnight),
+            days[11].0.and_time(days[10].1.midnight)
+        );
+        assert_eq!(
+            days[10].1.datetime(days[10].0, &Timing::Fajr),
+            days[10].0.and_time(days[10].1.fajr)
+        );
+
+        // Fajr gets earlier every day in march
+        for pair in days.windows(2) {
+            assert!(
+                pair[1].1.fajr <= pair[0].1.fajr,
+                "{} -> {}",
+                pair[0].0,
+                pair[1].0
+            );
+        }
+        assert!((days[0].1.fajr - days[30].1.fajr).num_minutes() > 30);
+
+        assert_eq!(pt.range(end, start).count(), 0);
+        assert_eq!(pt.range(start, start).count(), 1);
+    }
+
+    #[test]
+    fn test_with_asr_shadow_start() {
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let baseline = PrayerTimes::new(50.0, 0.0)
+            .with_date(&solstice)
+            .with_offset_hours(0.0);
+        let pure = baseline.clone().with_asr_shadow_start(false);
+
+        // The noon shadow is added on top, so the baseline convention is always later
+        assert!(baseline.asr() > pure.asr());
+        let diff = baseline.asr() - pure.asr();
+        assert!(diff.num_minutes() > 15 && diff.num_minutes() < 60);
+
+        // Dhuhr is unaffected
+        assert_eq!(baseline.dhuhr(), pure.dhuhr());
+
+        // In Mumbai (19°N) the sun passes north of the zenith in June. The noon shadow points south but is still
+        // added as a length, so the shadow at asr is the noon shadow plus the object length
+        let mumbai = PrayerTimes::new(19.076, 72.8777)
+            .with_date(&solstice)
+            .with_offset_hours(5.5)
+            .with_school(&School::Shafi)
+            .with_rounding(&Rounding::Seconds);
+        let noon_shadow = 1.0 / math::deg::tan(mumbai.sun_altitude(mumbai.dhuhr()));
+        let shadow = 1.0 / math::deg::tan(mumbai.sun_altitude(mumbai.asr()));
+        assert!(noon_shadow > 0.0);
+        assert!((shadow - (1.0 + noon_shadow)).abs() < 0.01, "{}", shadow);
+    }
+
+    #[test]
+    fn test_half_night() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+        let next_fajr = pt
+            .clone()
+            .with_date(&date.succ_opt().expect("Error!"))
+            .datetime(&Timing::Fajr);
+
+        let half_night = pt.half_night_datetime();
+        assert_eq!(half_night.time(), pt.half_night());
+        assert!(half_night > pt.datetime(&Timing::Maghrib));
+        assert!(half_night < next_fajr);
+
+        // The night is shorter when it ends at fajr instead of sunrise
+        assert_ne!(pt.half_night(), pt.midnight());
+        assert!(half_night < pt.datetime(&Timing::Midnight));
+    }
+
+    #[test]
+    fn test_night_point_rounding() {
+        // Half of 01:59:59 after 17:00:00 is 17:59:59.5, just below a minute boundary
+        let start = NaiveTime::from_hms_opt(17, 0, 0).expect("Error!");
+        let end = NaiveTime::from_hms_opt(18, 59, 59).expect("Error!");
+        let expected = [
+            (Rounding::Seconds, (18, 0, 0)),
+            (Rounding::Minute, (18, 0, 0)),
+            (Rounding::Floor, (17, 59, 0)),
+            (Rounding::Ceil, (18, 0, 0)),
+        ];
+        for (rounding, (h, m, s)) in expected {
+            assert_eq!(
+                super::night_point(start, end, 0.5, rounding),
+                NaiveTime::from_hms_opt(h, m, s).expect("Error!"),
+                "{:?}",
+                rounding
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_night_fraction_clamp() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 10).expect("Error!");
+        let raw = PrayerTimes::new(60.0, 25.0)
+            .with_date(&date)
+            .with_offset_hours(3.0)
+            .with_authority(&Authority::MWL);
+        let clamped = raw.clone().with_night_fraction_clamp(Some(1.0 / 7.0));
+
+        // MWL's 18 degrees is never reached at 60N in June
+        assert!(raw.fajr_hour().is_nan());
+        assert!(raw.isha_overlaps_fajr());
+
+        let night = clamped.night_hours();
+        let fajr_gap = clamped.sunrise_hour() - clamped.fajr_hour();
+        let isha_gap = clamped.isha_hour() - clamped.sunset_hour();
+        assert!((fajr_gap - night / 7.0).abs() < 1e-9);
+        assert!((isha_gap - night / 7.0).abs() < 1e-9);
+        assert!(clamped.fajr() < clamped.sunrise());
+        assert!(clamped.isha() > clamped.maghrib());
+
+        // Times already inside the clamp are untouched
+        let winter = NaiveDate::from_ymd_opt(2024, 1, 15).expect("Error!");
+        let mid = fixtures::toronto().with_date(&winter);
+        let mid_clamped = mid.clone().with_night_fraction_clamp(Some(1.0 / 7.0));
+        assert_eq!(mid.fajr(), mid_clamped.fajr());
+        assert_eq!(mid.isha(), mid_clamped.isha());
+    }
+
+    #[test]
+    fn test_compute() {
+        let pt = fixtures::toronto();
+        let date = pt.date();
+        let built = pt.compute().expect("Error!");
+        assert_eq!(built.date(), date);
+        assert_eq!(built.all().len(), Timing::list().len());
+        for timing in Timing::list() {
+            assert_eq!(built.timing(&timing), pt.timing(&timing));
+            assert_eq!(built.datetime(&timing), pt.datetime(&timing));
+        }
+        assert_eq!(
+            built.fajr(),
+            NaiveTime::from_hms_opt(6, 3, 0).expect("Error!")
+        );
+
+        let json = serde_json::to_value(&built).expect("Error!");
+        assert_eq!(json["times"][0]["timing"], "imsak");
+        assert_eq!(json["times"][0]["time"], "05:53:00");
+        assert_eq!(json["times"][1]["timing"], "fajr");
+        assert_eq!(json["times"][1]["time"], "06:03:00");
+
+        // Out of range coordinates
+        assert!(matches!(
+            PrayerTimes::new(200.0, 0.0).compute(),
+            Err(SalahError::OutOfRange(_))
+        ));
+        assert!(PrayerTimes::new(0.0, f64::NAN).compute().is_err());
+        assert!(PrayerTimes::new(0.0, 360.0).compute().is_err());
+        assert!(PrayerTimes::new(0.0, -200.0).compute().is_err());
+        // Longitudes in [0, 360) are wrapped
+        assert!(PrayerTimes::new(0.0, 270.0).compute().is_ok());
+        assert!(PrayerTimes::new(0.0, 0.0)
+            .with_location(0.0, 400.0)
+            .compute()
+            .is_err());
+
+        // No solution for fajr at 60N in June with MWL
+        let summer = NaiveDate::from_ymd_opt(2024, 6, 10).expect("Error!");
+        let polar = PrayerTimes::new(60.0, 25.0)
+            .with_date(&summer)
+            .with_offset_hours(3.0)
+            .with_authority(&Authority::MWL);
+        assert_eq!(polar.compute().err(), Some(SalahError::NoSunEvent));
+
+        // The Jafari midnight needs the next day's fajr: on the last night fajr has a solution, every timing of the
+        // day does but midnight doesn't
+        let spring = NaiveDate::from_ymd_opt(2024, 4, 1).expect("Error!");
+        let last_night = spring
+            .iter_days()
+            .map(|date| {
+                return PrayerTimes::new(50.0, 10.0)
+                    .with_date(&date)
+                    .with_offset_hours(2.0)
+                    .with_authority(&Authority::MWL)
+                    .with_midnight_method(&MidnightMethod::Jafari);
+            })
+            .find(|pt| pt.try_next_fajr().is_err())
+            .expect("Error!");
+        assert!(last_night.try_timing(&Timing::Fajr).is_ok());
+        assert!(last_night.try_timing(&Timing::Midnight).is_err());
+        assert_eq!(last_night.compute().err(), Some(SalahError::NoSunEvent));
+    }
+
+    #[test]
+    fn test_makkah_isha() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 11).expect("Error!");
+        let pt = PrayerTimes::new(21.4225, 39.8262)
+            .with_date(&date)
+            .with_offset_hours(3.0)
+            .with_authority(&Authority::Makkah);
+        assert_eq!((pt.isha() - pt.maghrib()).num_minutes(), 90);
+        assert_eq!(
+            pt.isha(),
+            NaiveTime::from_hms_opt(19, 46, 0).expect("Error!")
+        );
+    }
+
+    #[test]
+    fn test_with_ramadan() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).expect("Error!");
+        let makkah = PrayerTimes::new(21.4225, 39.8262)
+            .with_date(&date)
+            .with_offset_hours(3.0)
+            .with_authority(&Authority::Makkah);
+        let ramadan = makkah.clone().with_ramadan(true);
+        assert_eq!((ramadan.isha() - ramadan.maghrib()).num_minutes(), 120);
+        assert_eq!((ramadan.isha() - makkah.isha()).num_minutes(), 30);
+        assert_eq!(ramadan.fajr(), makkah.fajr());
+
+        // Other authorities are unaffected
+        let mwl = makkah.clone().with_authority(&Authority::MWL);
+        assert_eq!(mwl.isha(), mwl.clone().with_ramadan(true).isha());
+    }
+
+    #[test]
+    fn test_with_precision() {
+        let solstice = NaiveDate::from_ymd_opt(2024, 6, 20).expect("Error!");
+        let low = PrayerTimes::new(43.6534817, -79.3839347)
+            .with_date(&solstice)
+            .with_offset_hours(-4.0);
+        let high = low.clone().with_precision(&Precision::High);
+
+        let dhuhr_diff = (low.zenith() - high.zenith()).abs();
+        assert!(dhuhr_diff > 0.0 && dhuhr_diff < 1.0 / 60.0);
+
+        let fajr_diff = (low.fajr_hour() - high.fajr_hour()).abs();
+        assert!(fajr_diff > 0.0 && fajr_diff < 1.0 / 60.0);
+    }
+}
+
+use types::{
+    Adjustments, AngleSet, Authority, CalculationParameters, IshaParam, MidnightMethod, School,
+    Timing,
+};
+
+#[derive(Debug, Clone)]
 pub struct PrayerTimes {
-    /// timezone
-    tz: Tz,
+    /// Timezone, named or a fixed offset from GMT
+    timezone: datetime::TimezoneOffset,
+
+    /// Latitude
+    lat: f64,
+
+    /// Longitude
+    lng: f64,
+
+    // Date
+    date: NaiveDate,
+
+    // Timezone offset from GMT in hours for the date
+    tz_offset: f64,
+
+    // Whether the times are in local mean time (offset of `lng / 15` hours) instead of the timezone's civil time
+    local_mean_time: bool,
+
+    // Julian date
+    jd: f64,
+
+    // Equation of time (hours) and declination (degrees) of the sun for the date, computed once per date
+    eqt: f64,
+    decl: f64,
+
+    // Calculation authority, school, high latitude rule, adjustments and rounding
+    params: CalculationParameters,
+
+    // Fajr and isha angles replacing only that component of the authority's parameters
+    fajr_angle_override: Option<f64>,
+    isha_angle_override: Option<f64>,
+
+    // Interval after maghrib for isha, replacing the authority's isha (and any isha angle override)
+    isha_interval: Option<std::time::Duration>,
+
+    // Solar position model
+    precision: astro::Precision,
+
+    // Whether the sun coordinates are re-evaluated at each event's approximate time
+    refine: bool,
+
+    // Whether the asr shadow length is measured on top of the noon shadow
+    asr_noon_shadow: bool,
+
+    // Whether the date is in Ramadan
+    ramadan: bool,
+
+    // Interval before fajr for imsak
+    imsak_offset: std::time::Duration,
+
+    // Interval after sunrise for duha
+    duha_offset: std::time::Duration,
+
+    // Interval after solar noon for dhuhr
+    dhuhr_offset: std::time::Duration,
+
+    // How the night is measured for midnight, the authority's method when not set
+    midnight_method: Option<MidnightMethod>,
+
+    // Interval after sunset for maghrib, the authority's delay when not set
+    maghrib_delay: Option<std::time::Duration>,
+
+    // Height of the observer above sea level in meters
+    elevation: f64,
+
+    // Whether the horizon dip of the elevation also applies to the fajr and isha angles, not only sunrise and sunset
+    elevation_twilight: bool,
+
+    // Instant `next_prayer` and `current_prayer` look from when not given a time
+    instant: DateTime<Utc>,
+}
+
+impl PrayerTimes {
+    // =================== Constructors ============================
+    /// Creates a new prayer time struct with default parameters.
+    /// The inputs are not validated, use `PrayerTimes::builder` to reject out of range values up front.
+    ///
+    /// ### Arguments
+    /// * `lat` - The latitude value to calculate for
+    /// * `lng` - The longitude value to calculate for
+    ///
+    /// ### Defaults
+    /// * `tz` - Timezone default is set to `America/Toronto`
+    /// * `date` - Date defaults to today's date
+    /// * `auth` - Calculation authority defaults to Islamic Society of North America (ISNA) (used for fajr and isha time)
+    /// * `school` - School of thought for jurisprudence (used for asr timing) defaults to Hanafi
+    /// * `precision` - Solar position model defaults to the low precision (fast) approximation
+    /// * `refine` - The sun coordinates are evaluated once for the date (no two-pass refinement)
+    /// * `asr_noon_shadow` - Asr shadow is measured on top of the noon shadow (shadow = noon shadow + factor) by default
+    /// * `night_fraction` - Fajr and isha are not clamped by default
+    /// * `ramadan` - The date is assumed not to be in Ramadan
+    /// * `imsak_offset` - Imsak is 10 minutes before fajr
+    /// * `duha_offset` - Duha is 20 minutes after sunrise
+    /// * `dhuhr_offset` - Dhuhr is at solar noon
+    /// * `rounding` - Times are rounded to the nearest minute
+    /// * `midnight_method` - Midnight follows the authority (sunset to fajr for Jafari, sunset to sunrise otherwise)
+    /// * `elevation` - The observer is at sea level
+    /// * `elevation_twilight` - The elevation only moves sunrise and sunset, not fajr and isha
+    /// * `adjustments` - No timings are adjusted
+    /// * `local_mean_time` - Times are in the civil time of the timezone
+    /// * `instant` - `next_prayer` and `current_prayer` look from the time of creation by default
+    pub fn new(lat: f64, lng: f64) -> PrayerTimes {
+        return PrayerTimes::new_with_clock(lat, lng, &datetime::SystemClock);
+    }
+
+    /// Creates a new prayer time struct with default parameters (see `new`), where the default date
+    /// is today's date at the clock's current time
+    ///
+    /// ### Arguments
+    /// * `lat` - The latitude value to calculate for
+    /// * `lng` - The longitude value to calculate for
+    /// * `clock` - The source of the current time
+    pub fn new_with_clock(lat: f64, lng: f64, clock: &dyn datetime::Clock) -> PrayerTimes {
+        const DEFAULT_TZ: &str = "America/Toronto";
+        let tz: Tz = DEFAULT_TZ.parse().expect("Invalid time zone!");
+        return PrayerTimes::new_in(lat, lng, &datetime::TimezoneOffset::Named(tz), clock);
+    }
+
+    /// Creates a new prayer time struct with default parameters (see `new`) in a fixed offset from UTC instead of
+    /// `America/Toronto`, where the default date is today's date in that offset. The timezone database is never
+    /// looked up, at the cost of never applying daylight savings (see `with_offset_hours`).
+    ///
+    /// ### Arguments
+    /// * `lat` - The latitude value to calculate for
+    /// * `lng` - The longitude value to calculate for
+    /// * `offset` - The offset from UTC, in [-14, 14] hours
+    pub fn new_with_fixed_offset(lat: f64, lng: f64, offset: &FixedOffset) -> PrayerTimes {
+        let hours = offset.local_minus_utc() as f64 / 3600_f64;
+        return PrayerTimes::new_in(
+            lat,
+            lng,
+            &datetime::TimezoneOffset::Fixed(hours),
+            &datetime::SystemClock,
+        );
+    }
+
+    fn new_in(
+        lat: f64,
+        lng: f64,
+        timezone: &datetime::TimezoneOffset,
+        clock: &dyn datetime::Clock,
+    ) -> PrayerTimes {
+        let default_date = timezone.today(clock);
+
+        let pt = PrayerTimes {
+            instant: clock.now_utc(),
+            timezone: *timezone,
+            lat,
+            lng: accepted_longitude(lng),
+            date: default_date,
+            tz_offset: timezone.hours_for_date(default_date),
+            local_mean_time: false,
+            jd: astro::julian(default_date),
+            eqt: 0_f64,
+            decl: 0_f64,
+            params: CalculationParameters::default(),
+            fajr_angle_override: None,
+            isha_angle_override: None,
+            isha_interval: None,
+            precision: astro::Precision::Low,
+            refine: false,
+            asr_noon_shadow: true,
+            ramadan: false,
+            imsak_offset: std::time::Duration::from_secs(10 * 60),
+            duha_offset: std::time::Duration::from_secs(20 * 60),
+            dhuhr_offset: std::time::Duration::ZERO,
+            midnight_method: None,
+            maghrib_delay: None,
+            elevation: 0_f64,
+            elevation_twilight: false,
+        };
+        return pt.with_timezone_offset(timezone).with_sun_coords();
+    }
+
+    /// Creates a builder that validates the inputs when built, unlike `new` and the `with_*` setters
+    /// (e.g. a latitude of 200 is rejected instead of silently producing NaN times).
+    ///
+    /// ### Arguments
+    /// * `lat` - The latitude value to calculate for, in [-90, 90]
+    /// * `lng` - The longitude value to calculate for, in [-180, 180] or [0, 360)
+    pub fn builder(lat: f64, lng: f64) -> PrayerTimesBuilder {
+        return PrayerTimesBuilder {
+            lat,
+            lng,
+            date: None,
+            tz: None,
+            offset_hours: None,
+            auth: None,
+            school: None,
+            elevation: 0_f64,
+        };
+    }
+
+    // =============== Setters =================
+    /// Sets the location to compute timings for
+    ///
+    /// ### Arguments
+    /// * `lat` - The latitude value
+    /// * `lng` - The longitude value, east-positive in [-180, 180] or in [0, 360) (see `math::is_valid_longitude`)
+    pub fn with_location(mut self, lat: f64, lng: f64) -> Self {
+        self.lat = lat;
+        self.lng = accepted_longitude(lng);
+        return self;
+    }
+
+    /// Sets the date to compute timings for
+    pub fn with_date(mut self, date: &NaiveDate) -> Self {
+        self.date = *date;
+        self.jd = astro::julian(*date);
+        self.tz_offset = self.timezone.hours_for_date(self.date);
+        return self.with_sun_coords();
+    }
+
+    /// Sets the timezone
+    pub fn with_timezone(self, tz: &Tz) -> Self {
+        return self.with_timezone_offset(&datetime::TimezoneOffset::Named(*tz));
+    }
+
+    /// Sets a fixed timezone offset from GMT in hours, bypassing the timezone database.
+    /// Daylight savings is not applied, the offset is used as is for every date.
+    /// Panics if the offset is not in [-14, 14], use `PrayerTimes::builder` to get an error instead.
+    ///
+    /// ### Arguments
+    /// * `hours` - The offset from GMT in hours (e.g. `-5.0` for EST, `5.5` for IST)
+    pub fn with_offset_hours(self, hours: f64) -> Self {
+        return self.with_timezone_offset(&datetime::TimezoneOffset::Fixed(hours));
+    }
+
+    /// Sets a fixed timezone offset from UTC like `with_offset_hours`, e.g. the offset of a `DateTime<FixedOffset>`
+    ///
+    /// ### Arguments
+    /// * `offset` - The offset from UTC, in [-14, 14] hours
+    pub fn with_fixed_offset(self, offset: &FixedOffset) -> Self {
+        return self.with_offset_hours(offset.local_minus_utc() as f64 / 3600_f64);
+    }
+
+    /// Sets the timezone as either a named timezone or a fixed offset from GMT.
+    /// A fixed offset needs no timezone database lookups (e.g. for embedded consumers that know their offset),
+    /// at the cost of never applying daylight savings. Panics if a fixed offset is not in [-14, 14] hours.
+    ///
+    /// ### Arguments
+    /// * `timezone` - The named timezone or fixed offset
+    pub fn with_timezone_offset(mut self, timezone: &datetime::TimezoneOffset) -> Self {
+        if let datetime::TimezoneOffset::Fixed(hours) = timezone {
+            assert!(
+                (-14_f64..=14_f64).contains(hours),
+                "RangeError @ PrayerTime.with_timezone_offset: offset = `{}` is not in [-14, 14]",
+                hours
+            );
+        }
+        self.timezone = *timezone;
+        self.tz_offset = timezone.hours_for_date(self.date);
+        return self;
+    }
+
+    /// Sets the date and timezone from an exact instant, which `next_prayer` and `current_prayer` then look from
+    /// when not given a time (e.g. "right now" for an integration, without passing it to every call).
+    ///
+    /// ### Arguments
+    /// * `datetime` - The instant, its local date is the date to compute timings for
+    pub fn with_datetime(self, datetime: &DateTime<Tz>) -> Self {
+        let mut pt = self
+            .with_date(&datetime.date_naive())
+            .with_timezone(&datetime.timezone());
+        pt.instant = datetime.to_utc();
+        return pt;
+    }
+
+    /// Sets whether the times are in local mean time, the sun based time of the longitude (an offset of `lng / 15` hours
+    /// from GMT), instead of the civil time of the timezone. Dhuhr is then at 12:00 corrected by the equation of time.
+    /// The timezone is still used to determine today's date, and `to_local` returns `None`.
+    ///
+    /// ### Arguments
+    /// * `lmt` - If `true`, the times are in local mean time
+    pub fn with_local_mean_time(mut self, lmt: bool) -> Self {
+        self.local_mean_time = lmt;
+        return self;
+    }
+
+    /// Sets the authority, school, high latitude rule (night fraction clamp), adjustments and rounding at once,
+    /// e.g. from settings persisted as JSON
+    pub fn with_parameters(mut self, params: &CalculationParameters) -> Self {
+        self.params = *params;
+        return self;
+    }
+
+    /// Sets the calculation authority
+    pub fn with_authority(mut self, auth: &Authority) -> Self {
+        self.params.authority = *auth;
+        return self;
+    }
+
+    /// Sets the school of thought
+    pub fn with_school(mut self, school: &School) -> Self {
+        self.params.school = *school;
+        return self;
+    }
+
+    /// Overrides only the fajr angle of the authority, keeping the rest of its parameters
+    /// (isha, midnight method and any authority specific adjustments).
+    ///
+    /// ### Arguments
+    /// * `angle` - Angle of the sun below the horizon for fajr in degrees, `None` uses the authority's angle
+    pub fn with_fajr_angle_override(mut self, angle: Option<f64>) -> Self {
+        self.fajr_angle_override = angle;
+        return self;
+    }
+
+    /// Overrides only the isha of the authority with an angle, keeping the rest of its parameters.
+    /// The override also replaces interval based isha (e.g. Makkah's 90 minutes after maghrib).
+    ///
+    /// ### Arguments
+    /// * `angle` - Angle of the sun below the horizon for isha in degrees, `None` uses the authority's isha
+    pub fn with_isha_angle_override(mut self, angle: Option<f64>) -> Self {
+        self.isha_angle_override = angle;
+        return self;
+    }
+
+    /// Replaces the isha of the authority with a fixed interval after maghrib, e.g. at high latitudes where
+    /// the sun barely reaches the isha angle. Takes precedence over `with_isha_angle_override`.
+    ///
+    /// ### Arguments
+    /// * `interval` - The interval after maghrib, `None` uses the authority's isha
+    pub fn with_isha_interval(mut self, interval: Option<std::time::Duration>) -> Self {
+        self.isha_interval = interval;
+        return self;
+    }
+
+    /// Sets the solar position model.
+    /// `Precision::High` gets closer to published tables (seconds) at a small computational cost.
+    pub fn with_precision(mut self, precision: &astro::Precision) -> Self {
+        self.precision = *precision;
+        return self.with_sun_coords();
+    }
+
+    /// Sets whether asr starts when the shadow equals the noon shadow plus the school's factor (`true`, default)
+    /// or when the shadow is exactly the school's factor times the object length (`false`).
+    /// The two conventions drift apart with latitude, and the pure factor may have no solution in high latitude winters.
+    /// The noon shadow is a length, so between the tropics it is added even when the sun passes on the other side of
+    /// the zenith. Asr is later there than with the signed `tan(lat - decl)` the calculation used before.
+    pub fn with_asr_shadow_start(mut self, noon_shadow: bool) -> Self {
+        self.asr_noon_shadow = noon_shadow;
+        return self;
+    }
+
+    /// Clamps fajr and isha so they are never further than a fraction of the night from sunrise and sunset.
+    /// e.g. `1.0 / 7.0` -> fajr is no earlier than a seventh of the night before sunrise.
+    /// This is a safety net for high latitudes, where the angle based times drift towards (or past) midnight
+    /// and may not exist at all. It is applied after the raw times are computed.
+    ///
+    /// ### Arguments
+    /// * `fraction` - Fraction of the night (0 - 1), `None` disables the clamp
+    pub fn with_night_fraction_clamp(mut self, fraction: Option<f64>) -> Self {
+        self.params.night_fraction_clamp = fraction;
+        return self;
+    }
+
+    /// Sets whether the date is in Ramadan.
+    /// Only affects authorities with a Ramadan specific isha (Makkah uses 120 minutes after maghrib instead of 90).
+    pub fn with_ramadan(mut self, ramadan: bool) -> Self {
+        self.ramadan = ramadan;
+        return self;
+    }
+
+    /// Sets the interval before fajr for imsak (the time to stop eating before a fast)
+    ///
+    /// ### Arguments
+    /// * `offset` - The interval before fajr, 10 minutes by default
+    pub fn with_imsak_offset(mut self, offset: &std::time::Duration) -> Self {
+        self.imsak_offset = *offset;
+        return self;
+    }
+
+    /// Sets the interval after sunrise for duha (the forenoon prayer)
+    ///
+    /// ### Arguments
+    /// * `offset` - The interval after sunrise, 20 minutes by default (15 - 20 minutes is common)
+    pub fn with_duha_offset(mut self, offset: &std::time::Duration) -> Self {
+        self.duha_offset = *offset;
+        return self;
+    }
+
+    /// Sets the interval after solar noon for dhuhr, a buffer so the sun has clearly passed the meridian.
+    /// Unlike `with_adjustments`, this is part of the astronomical definition of dhuhr.
+    ///
+    /// ### Arguments
+    /// * `offset` - The interval after solar noon, none by default (1 - 2 minutes is common)
+    pub fn with_dhuhr_offset(mut self, offset: &std::time::Duration) -> Self {
+        self.dhuhr_offset = *offset;
+        return self;
+    }
+
+    /// Sets how the computed times are rounded, `Rounding::Seconds` keeps the exact astronomical times
+    pub fn with_rounding(mut self, rounding: &datetime::Rounding) -> Self {
+        self.params.rounding = *rounding;
+        return self;
+    }
+
+    /// Sets how the night is measured for midnight, overriding the authority's default.
+    /// `MidnightMethod::Jafari` ends the night at fajr instead of sunrise.
+    pub fn with_midnight_method(mut self, method: &MidnightMethod) -> Self {
+        self.midnight_method = Some(*method);
+        return self;
+    }
+
+    /// Sets the interval after sunset for maghrib, overriding the authority's delay
+    /// (about 4 minutes for Tehran and Jafari, none for the others). Interval based isha follows the delayed maghrib,
+    /// while midnight is still measured from sunset.
+    ///
+    /// ### Arguments
+    /// * `delay` - The interval after sunset
+    pub fn with_maghrib_delay(mut self, delay: &std::time::Duration) -> Self {
+        self.maghrib_delay = Some(*delay);
+        return self;
+    }
+
+    /// Sets whether each solar event is refined with a second pass. The sun coordinates are first evaluated
+    /// at local noon, then again at the first estimate of the event, instead of once for the whole date.
+    /// This moves events far from noon (e.g. fajr and isha at high latitudes) by tens of seconds to a few minutes.
+    ///
+    /// ### Arguments
+    /// * `refine` - If `true`, events are computed in two passes
+    pub fn with_refinement(mut self, refine: bool) -> Self {
+        self.refine = refine;
+        return self;
+    }
+
+    /// Sets the height of the observer above sea level. The horizon dips with elevation,
+    /// so sunrise is earlier and maghrib later than at sea level. Fajr and isha are unaffected unless
+    /// `with_elevation_twilight` is set.
+    ///
+    /// ### Arguments
+    /// * `meters` - The elevation in meters, negative values are treated as sea level
+    pub fn with_elevation(mut self, meters: f64) -> Self {
+        self.elevation = meters;
+        return self;
+    }
+
+    /// Sets whether the horizon dip of the elevation (see `with_elevation`) also applies to fajr and isha.
+    /// By default it only applies to the horizon events (sunrise and maghrib, the sun at 0.833° below the horizon), and
+    /// the fajr and isha angles are measured from the sea level horizon as authorities define them. When set, the dip is
+    /// added to the fajr and isha angles too, so fajr is earlier and isha later at higher elevations.
+    ///
+    /// ### Arguments
+    /// * `twilight` - If `true`, the elevation applies to the fajr and isha angles as well
+    pub fn with_elevation_twilight(mut self, twilight: bool) -> Self {
+        self.elevation_twilight = twilight;
+        return self;
+    }
+
+    /// Sets signed minute offsets applied to each computed timing, e.g. `+2` on fajr to match a local mosque.
+    /// Only the adjusted timing moves: midnight and the last third are still measured from the actual sunset and sunrise.
+    /// Imsak follows the adjusted fajr.
+    pub fn with_adjustments(mut self, adjustments: &Adjustments) -> Self {
+        self.params.adjustments = *adjustments;
+        return self;
+    }
+
+    // ================= Getters =======================
+    /// Returns the date the timings are calculated for
+    pub fn date(&self) -> NaiveDate {
+        return self.date;
+    }
+
+    /// Returns the longitude the timings are calculated for, east-positive in [-180, 180]
+    pub fn lng(&self) -> f64 {
+        return self.lng;
+    }
+
+    /// Returns the authority, school, high latitude rule, adjustments and rounding used for the calculation
+    pub fn parameters(&self) -> CalculationParameters {
+        return self.params;
+    }
+
+    /// Returns the timezone the timings are calculated for
+    pub fn timezone_offset(&self) -> datetime::TimezoneOffset {
+        return self.timezone;
+    }
+
+    /// Returns the fajr and isha parameters actually used, the authority's with any overrides applied
+    /// (see `with_fajr_angle_override`, `with_isha_angle_override` and `with_isha_interval`)
+    pub fn angles(&self) -> AngleSet {
+        return AngleSet::from_params(self.fajr_angle(), self.isha_param());
+    }
+
+    /// Returns the interval after sunset for maghrib, the authority's unless overridden
+    pub fn maghrib_delay(&self) -> std::time::Duration {
+        return self
+            .maghrib_delay
+            .unwrap_or(self.params.authority.maghrib_delay());
+    }
+
+    /// Returns the interval after solar noon for dhuhr
+    pub fn dhuhr_offset(&self) -> std::time::Duration {
+        return self.dhuhr_offset;
+    }
+
+    // ================= Private Methods =======================
+    /// Recomputes the cached sun coordinates, must be called whenever the date or precision changes
+    fn with_sun_coords(mut self) -> Self {
+        let (eqt, decl) = self.precision.sun_coords(self.jd);
+        self.eqt = eqt;
+        self.decl = decl;
+        return self;
+    }
+
+    fn zenith(&self) -> f64 {
+        return self.event_hour(|zenith, _| zenith);
+    }
+
+    /// Offset from GMT in hours of the computed times, the longitude's in local mean time
+    fn offset_hours(&self) -> f64 {
+        if self.local_mean_time {
+            return self.lng / 15_f64;
+        }
+        return self.tz_offset;
+    }
+
+    /// Sun coordinates (equation of time and declination) at a local hour of the date
+    fn sun_coords_at(&self, hour: f64) -> (f64, f64) {
+        return self
+            .precision
+            .sun_coords(self.jd + (hour - self.offset_hours()) / 24_f64);
+    }
+
+    /// Evaluates the hour of a solar event from the zenith and declination of the sun.
+    /// Without refinement the sun coordinates of the date are used. With refinement, the event is
+    /// computed at local noon first and then again with the sun coordinates at that first estimate.
+    ///
+    /// ### Arguments
+    /// * `event` - Computes the event hour from the zenith hour and the declination in degrees
+    fn event_hour(&self, event: impl Fn(f64, f64) -> f64) -> f64 {
+        if !self.refine {
+            return event(
+                astro::zenith_from_eqt(self.eqt, self.lng, self.offset_hours()),
+                self.decl,
+            );
+        }
+        let (eqt, decl) = self.sun_coords_at(12_f64);
+        let first = event(
+            astro::zenith_from_eqt(eqt, self.lng, self.offset_hours()),
+            decl,
+        );
+        if !first.is_finite() {
+            return first;
+        }
+        let (eqt, decl) = self.sun_coords_at(first);
+        return event(
+            astro::zenith_from_eqt(eqt, self.lng, self.offset_hours()),
+            decl,
+        );
+    }
+
+    /// Hour the sun is at `angle` degrees below the horizon, NaN if it never is
+    fn horizon_hour(&self, angle: f64, direction: astro::HorizonDirection) -> f64 {
+        return self.event_hour(|zenith, decl| {
+            return astro::horizon_hour_angle_from_decl(angle, decl, zenith, self.lat, direction);
+        });
+    }
+
+    /// Angle of the sun below the horizon at sunrise and sunset in degrees.
+    /// Refraction and the sun's radius give 0.833, plus the dip of the horizon for the elevation.
+    fn horizon_angle(&self) -> f64 {
+        return 0.833 + self.horizon_dip();
+    }
+
+    /// Dip of the horizon below the sea level horizon in degrees for the elevation
+    fn horizon_dip(&self) -> f64 {
+        return 0.0347 * self.elevation.max(0_f64).sqrt();
+    }
+
+    /// Extra angle below the horizon for fajr and isha, the horizon dip only if it applies to them
+    fn twilight_dip(&self) -> f64 {
+        if self.elevation_twilight {
+            return self.horizon_dip();
+        }
+        return 0_f64;
+    }
+
+    /// Fractional hour of sunrise, NaN if the sun never rises
+    fn sunrise_hour(&self) -> f64 {
+        return self.horizon_hour(self.horizon_angle(), astro::HorizonDirection::Sunrise);
+    }
+
+    /// Fractional hour of sunset, NaN if the sun never sets
+    fn sunset_hour(&self) -> f64 {
+        return self.horizon_hour(self.horizon_angle(), astro::HorizonDirection::Sunset);
+    }
+
+    /// Fractional hour of asr, NaN if the shadow never reaches the school's length
+    fn asr_hour(&self) -> f64 {
+        return self.event_hour(|zenith, decl| {
+            return astro::shadow_length_hour_from_decl(
+                self.params.school.shadow_length(),
+                decl,
+                zenith,
+                self.lat,
+                self.asr_noon_shadow,
+            );
+        });
+    }
+
+    /// Applies the configured minute offset of a timing
+    fn adjusted(&self, timing: &Timing, time: NaiveTime) -> NaiveTime {
+        return time + Duration::minutes(self.params.adjustments.minutes(timing) as i64);
+    }
+
+    /// Unadjusted fajr time, `SalahError::NoSunEvent` if the sun never reaches the fajr angle
+    fn try_fajr_time(&self) -> Result<NaiveTime, SalahError> {
+        return datetime::hour2time(self.fajr_hour(), self.params.rounding);
+    }
+
+    /// Unadjusted sunrise time, `SalahError::NoSunEvent` if the sun never rises or sets
+    fn try_sunrise_time(&self) -> Result<NaiveTime, SalahError> {
+        return datetime::hour2time(self.sunrise_hour(), self.params.rounding);
+    }
+
+    /// Unadjusted maghrib (sunset) time, `SalahError::NoSunEvent` if the sun never rises or sets
+    fn try_maghrib_time(&self) -> Result<NaiveTime, SalahError> {
+        return datetime::hour2time(self.sunset_hour(), self.params.rounding);
+    }
+
+    /// Length of the day (sunrise to sunset) in hours, 24 if the sun never sets and 0 if it never rises
+    fn day_hours(&self) -> f64 {
+        let (sunrise, sunset) = (self.sunrise_hour(), self.sunset_hour());
+        if sunrise.is_finite() && sunset.is_finite() {
+            return sunset - sunrise;
+        }
+        // The sun doesn't cross the horizon, it is either up or down all day
+        let noon = astro::sun_altitude(0_f64, self.decl, 0_f64, self.lat);
+        if noon > -self.horizon_angle() {
+            return 24_f64;
+        }
+        return 0_f64;
+    }
+
+    /// Length of the night (sunset to the next sunrise) in hours
+    fn night_hours(&self) -> f64 {
+        return 24_f64 - self.day_hours();
+    }
+
+    /// Fajr angle of the authority, unless overridden
+    fn fajr_angle(&self) -> f64 {
+        return self
+            .fajr_angle_override
+            .unwrap_or(self.params.authority.fajr_angle());
+    }
+
+    /// Isha parameter of the authority (for Ramadan if set), unless overridden
+    fn isha_param(&self) -> IshaParam {
+        return match (self.isha_interval, self.isha_angle_override) {
+            (Some(interval), _) => IshaParam::Duration(interval),
+            (None, Some(angle)) => IshaParam::Angle(angle),
+            (None, None) => self.params.authority.isha_param_with_ramadan(self.ramadan),
+        };
+    }
+
+    /// Fractional hour of fajr, NaN if the sun never reaches the fajr angle (and no clamp is set)
+    fn fajr_hour(&self) -> f64 {
+        let hour = self.horizon_hour(
+            self.fajr_angle() + self.twilight_dip(),
+            astro::HorizonDirection::Sunrise,
+        );
+        let hour = match self.params.authority {
+            Authority::MoonsightingCommittee => self.moonsighting_fajr_hour(hour),
+            _ => hour,
+        };
+        return match self.params.night_fraction_clamp {
+            Some(fraction) => {
+                let earliest = self.sunrise_hour() - fraction * self.night_hours();
+                if hour.is_nan() || hour < earliest {
+                    earliest
+                } else {
+                    hour
+                }
+            }
+            None => hour,
+        };
+    }
+
+    /// Fractional hour of isha, NaN if the sun never reaches the isha angle (and no clamp is set)
+    fn isha_hour(&self) -> f64 {
+        let hour = match self.isha_param() {
+            IshaParam::Angle(a) => {
+                self.horizon_hour(a + self.twilight_dip(), astro::HorizonDirection::Sunset)
+            }
+            IshaParam::Duration(d) => {
+                self.sunset_hour() + (self.maghrib_delay() + d).as_secs_f64() / 3600_f64
+            }
+        };
+        let hour = match self.params.authority {
+            Authority::MoonsightingCommittee => self.moonsighting_isha_hour(hour),
+            _ => hour,
+        };
+        return match self.params.night_fraction_clamp {
+            Some(fraction) => {
+                let latest = self.sunset_hour() + fraction * self.night_hours();
+                if hour.is_nan() || hour > latest {
+                    latest
+                } else {
+                    hour
+                }
+            }
+            None => hour,
+        };
+    }
+
+    /// Days since the winter solstice of the hemisphere, for the seasonal Moonsighting Committee intervals
+    fn days_since_solstice(&self) -> u32 {
+        let day = astro::day_of_year(self.jd).expect("RangeError @ PrayerTime.days_since_solstice");
+        return moonsighting::days_since_solstice(day, self.date.year(), self.lat);
+    }
+
+    /// Moonsighting Committee fajr: no earlier than the seasonal interval before sunrise,
+    /// and a seventh of the night before sunrise above 55 degrees
+    fn moonsighting_fajr_hour(&self, hour: f64) -> f64 {
+        if self.lat.abs() >= 55_f64 {
+            return self.sunrise_hour() - self.night_hours() / 7_f64;
+        }
+        let minutes = moonsighting::morning_twilight_minutes(self.lat, self.days_since_solstice());
+        let earliest = self.sunrise_hour() - minutes / 60_f64;
+        if hour.is_nan() || hour < earliest {
+            return earliest;
+        }
+        return hour;
+    }
+
+    /// Moonsighting Committee isha: no later than the seasonal interval after sunset,
+    /// and a seventh of the night after sunset above 55 degrees
+    fn moonsighting_isha_hour(&self, hour: f64) -> f64 {
+        if self.lat.abs() >= 55_f64 {
+            return self.sunset_hour() + self.night_hours() / 7_f64;
+        }
+        let minutes = moonsighting::evening_twilight_minutes(self.lat, self.days_since_solstice());
+        let latest = self.sunset_hour() + minutes / 60_f64;
+        if hour.is_nan() || hour > latest {
+            return latest;
+        }
+        return hour;
+    }
+
+    // ================= Public Methods ========================
+    /// Returns a local time on the date in the named timezone. Ambiguous and skipped local times around
+    /// daylight savings changes are read as standard time. `None` if the timezone is a fixed offset (see `to_utc`).
+    ///
+    /// ### Arguments
+    /// * `time` - The local time, e.g. a computed timing
+    pub fn to_local(&self, time: NaiveTime) -> Option<DateTime<Tz>> {
+        return self.zoned(self.date.and_time(time));
+    }
+
+    /// A local date and time in the named timezone, `None` for a fixed offset or local mean time
+    fn zoned(&self, local: NaiveDateTime) -> Option<DateTime<Tz>> {
+        if self.local_mean_time {
+            return None;
+        }
+        return match self.timezone {
+            datetime::TimezoneOffset::Named(tz) => Some(datetime::from_local_standard(tz, local)),
+            datetime::TimezoneOffset::Fixed(_) => None,
+        };
+    }
+
+    /// Returns the absolute UTC date and time of a local time on the date, e.g. for schedulers.
+    /// Ambiguous and skipped local times around daylight savings changes are read as standard time.
+    /// The time is always on the date, use `all_utc` for the timings that roll over to the next day.
+    ///
+    /// ### Arguments
+    /// * `time` - The local time, e.g. a computed timing
+    pub fn to_utc(&self, time: NaiveTime) -> DateTime<Utc> {
+        return self.local_to_utc(self.date.and_time(time));
+    }
+
+    fn local_to_utc(&self, local: NaiveDateTime) -> DateTime<Utc> {
+        if self.local_mean_time {
+            return Utc.from_utc_datetime(&(local - self.utc_offset()));
+        }
+        return match self.timezone {
+            datetime::TimezoneOffset::Named(tz) => {
+                datetime::from_local_standard(tz, local).with_timezone(&Utc)
+            }
+            datetime::TimezoneOffset::Fixed(hours) => {
+                let offset = Duration::seconds((hours * 3600_f64).round() as i64);
+                Utc.from_utc_datetime(&(local - offset))
+            }
+        };
+    }
+
+    /// Returns every timing as an absolute UTC date and time, in the order of `Timing::list`.
+    /// Evening timings past midnight are on the next day, like `datetime`.
+    pub fn all_utc(&self) -> Vec<(Timing, DateTime<Utc>)> {
+        return self
+            .all_times()
+            .into_iter()
+            .map(|result| {
+                (
+                    result.timing,
+                    self.local_to_utc(result.datetime.naive_local()),
+                )
+            })
+            .collect();
+    }
+
+    /// Returns the offset from GMT used for the calculations as a chrono FixedOffset
+    /// (the longitude's offset in local mean time, see `with_local_mean_time`)
+    pub fn utc_offset(&self) -> FixedOffset {
+        let seconds = (self.offset_hours() * 3600_f64).round() as i32;
+        return FixedOffset::east_opt(seconds).expect("RangeError @ PrayerTime.utc_offset");
+    }
+
+    /// Returns the altitude of the sun above the horizon in degrees at a local time on the date, negative when it is
+    /// below the horizon (e.g. about -0.833 at sunrise and -18 at an 18° fajr). Useful for sun path charts.
+    ///
+    /// ### Arguments
+    /// * `time` - The local time, in the timezone (or local mean time) the timings are computed in
+    pub fn sun_altitude(&self, time: NaiveTime) -> f64 {
+        let hour = datetime::time2hour(time);
+        let (eqt, decl) = self.sun_coords_at(hour);
+        let zenith = astro::zenith_from_eqt(eqt, self.lng, self.offset_hours());
+        return astro::sun_altitude(hour, decl, zenith, self.lat);
+    }
+
+    /// Returns the time of a timing, or an error instead of panicking when it can't be computed:
+    /// `SalahError::NoSunEvent` when the sun never reaches the timing's angle on the date (e.g. fajr near the poles
+    /// in the summer), which the getters like `fajr` panic on. See `all_checked` for every timing at once.
+    ///
+    /// ### Arguments
+    /// * `timing` - The timing to compute
+    pub fn try_timing(&self, timing: &Timing) -> Result<NaiveTime, SalahError> {
+        return self.timing_from(timing, &self.sun_events());
+    }
+
+    /// Unadjusted fajr, sunrise and maghrib times, the events most timings are derived from
+    fn sun_events(&self) -> SunEvents {
+        return SunEvents {
+            fajr: self.try_fajr_time(),
+            sunrise: self.try_sunrise_time(),
+            maghrib: self.try_maghrib_time(),
+        };
+    }
+
+    /// Time of a timing given the already computed sun events, shared by `try_timing`, `all` and `all_checked`
+    fn timing_from(&self, timing: &Timing, events: &SunEvents) -> Result<NaiveTime, SalahError> {
+        let fajr = || events.fajr.clone();
+        let sunrise = || events.sunrise.clone();
+        let maghrib = || events.maghrib.clone();
+        return match timing {
+            Timing::Imsak => Ok(self.timing_from(&Timing::Fajr, events)? - self.imsak_offset),
+            Timing::Fajr => Ok(self.adjusted(timing, fajr()?)),
+            Timing::Sunrise => Ok(self.adjusted(timing, sunrise()?)),
+            Timing::Duha => Ok(sunrise()? + self.duha_offset),
+            Timing::Dhuhr => {
+                let noon = datetime::hour2time(self.zenith(), self.params.rounding)?;
+                Ok(self.adjusted(timing, noon + self.dhuhr_offset))
+            }
+            Timing::Asr => {
+                let asr = datetime::hour2time(self.asr_hour(), self.params.rounding)?;
+                Ok(self.adjusted(timing, asr))
+            }
+            Timing::Maghrib => Ok(self.adjusted(timing, maghrib()? + self.maghrib_delay())),
+            Timing::Isha => {
+                let isha = match self.isha_param() {
+                    // Offset the actual maghrib time so isha is exactly the interval after it
+                    IshaParam::Duration(d) if self.params.night_fraction_clamp.is_none() => {
+                        maghrib()? + self.maghrib_delay() + d
+                    }
+                    _ => datetime::hour2time(self.isha_hour(), self.params.rounding)?,
+                };
+                Ok(self.adjusted(timing, isha))
+            }
+            Timing::Midnight => {
+                let end = match self
+                    .midnight_method
+                    .unwrap_or(self.params.authority.midnight_method())
+                {
+                    MidnightMethod::Standard => sunrise()?,
+                    MidnightMethod::Jafari => self.try_next_fajr()?,
+                };
+                Ok(night_point(maghrib()?, end, 0.5, self.params.rounding))
+            }
+            Timing::LastThird => Ok(night_point(
+                maghrib()?,
+                sunrise()?,
+                2_f64 / 3_f64,
+                self.params.rounding,
+            )),
+        };
+    }
+
+    /// Returns the imsak time, the configured interval before fajr
+    pub fn imsak(&self) -> NaiveTime {
+        return self
+            .try_timing(&Timing::Imsak)
+            .expect("RangeError @ PrayerTime.imsak");
+    }
+
+    /// Returns the fajr (dusk) prayer time.
+    /// Like every timing, this is a clock time in [0, 24): far west of the timezone's meridian the computed hour
+    /// can fall before midnight (or after it for evening timings) and wraps around, see `datetime` for the full date.
+    pub fn fajr(&self) -> NaiveTime {
+        return self
+            .try_timing(&Timing::Fajr)
+            .expect("RangeError @ PrayerTime.fajr");
+    }
+
+    /// Returns `true` if the isha window never ends before the next day's fajr.
+    ///
+    /// This happens at high latitudes around the summer solstice, where the night is so short
+    /// that the sun never reaches the isha/fajr angles or isha falls after the next fajr.
+    /// The window from isha to the next fajr should be treated as collapsed when this is `true`.
+    pub fn isha_overlaps_fajr(&self) -> bool {
+        let isha = self.isha_hour();
+        let next_day = self
+            .date
+            .succ_opt()
+            .expect("RangeError @ PrayerTime.isha_overlaps_fajr");
+        let next_fajr = self.clone().with_date(&next_day).fajr_hour() + 24_f64;
+        if isha.is_nan() || next_fajr.is_nan() {
+            return true;
+        }
+        return isha >= next_fajr;
+    }
+
+    /// Returns the dhuhr (mid-day) prayer time
+    pub fn dhuhr(&self) -> NaiveTime {
+        return self
+            .try_timing(&Timing::Dhuhr)
+            .expect("RangeError @ PrayerTime.dhuhr");
+    }
+
+    /// Returns the asr (evening) prayer time
+    pub fn asr(&self) -> NaiveTime {
+        return self
+            .try_timing(&Timing::Asr)
+            .expect("RangeError @ PrayerTime.asr");
+    }
+
+    /// Returns the maghrib (sunset) prayer time
+    pub fn maghrib(&self) -> NaiveTime {
+        return self
+            .try_timing(&Timing::Maghrib)
+            .expect("RangeError @ PrayerTime.maghrib");
+    }
+
+    /// Returns the isha (night) prayer time
+    pub fn isha(&self) -> NaiveTime {
+        return self
+            .try_timing(&Timing::Isha)
+            .expect("RangeError @ PrayerTime.isha");
+    }
+
+    /// Returns the sunrise time
+    pub fn sunrise(&self) -> NaiveTime {
+        return self
+            .try_timing(&Timing::Sunrise)
+            .expect("RangeError @ PrayerTime.sunrise");
+    }
+
+    /// Returns the duha (forenoon) prayer time, the configured interval after the actual sunrise
+    pub fn duha(&self) -> NaiveTime {
+        return self
+            .try_timing(&Timing::Duha)
+            .expect("RangeError @ PrayerTime.duha");
+    }
+
+    /// Returns the length of the day, from sunrise to sunset.
+    /// Near the poles, this is 24 hours when the sun never sets (midnight sun) and zero when it never rises (polar night).
+    pub fn day_duration(&self) -> Duration {
+        return Duration::milliseconds((self.day_hours() * 3_600_000_f64).round() as i64);
+    }
+
+    /// Returns the length of the night, from sunset to the next sunrise (the rest of the 24 hours, see `day_duration`).
+    /// Midnight, the last third and the seventh of the night used at high latitudes are fractions of it.
+    pub fn night_duration(&self) -> Duration {
+        return Duration::milliseconds((self.night_hours() * 3_600_000_f64).round() as i64);
+    }
+
+    /// Returns the midnight time
+    pub fn midnight(&self) -> NaiveTime {
+        return self
+            .try_timing(&Timing::Midnight)
+            .expect("RangeError @ PrayerTime.midnight");
+    }
+
+    /// Returns the start of the last third of the night (sunset to sunrise), the preferred time for Tahajjud
+    pub fn last_third(&self) -> NaiveTime {
+        return self
+            .try_timing(&Timing::LastThird)
+            .expect("RangeError @ PrayerTime.last_third");
+    }
+
+    /// Returns the half of the night time, the midpoint between maghrib and the next day's fajr.
+    /// Some communities end the preferred isha time here rather than at midnight (sunset to sunrise).
+    pub fn half_night(&self) -> NaiveTime {
+        return self
+            .try_half_night()
+            .expect("RangeError @ PrayerTime.half_night");
+    }
+
+    /// Returns the half of the night like `half_night`, `SalahError::NoSunEvent` if the sun never sets
+    /// or never reaches the fajr angle the next day
+    pub fn try_half_night(&self) -> Result<NaiveTime, SalahError> {
+        return Ok(night_point(
+            self.try_maghrib_time()?,
+            self.try_next_fajr()?,
+            0.5,
+            self.params.rounding,
+        ));
+    }
+
+    /// Unadjusted fajr time of the following day, an error if there is none
+    fn try_next_fajr(&self) -> Result<NaiveTime, SalahError> {
+        let next_day = self.date.succ_opt().ok_or_else(|| {
+            return SalahError::InvalidDate(format!("{} has no following day", self.date));
+        })?;
+        return self.clone().with_date(&next_day).try_fajr_time();
+    }
+
+    /// Returns the half of the night with its full date, rolled over to the next day when past midnight
+    pub fn half_night_datetime(&self) -> DateTime<FixedOffset> {
+        return self
+            .try_half_night_datetime()
+            .expect("RangeError @ PrayerTime.half_night_datetime");
+    }
+
+    /// Returns the half of the night with its full date like `half_night_datetime`, or an error if there is none
+    pub fn try_half_night_datetime(&self) -> Result<DateTime<FixedOffset>, SalahError> {
+        let half_night = self.try_half_night()?;
+        return Ok(self.rollover_datetime_from(&Timing::Midnight, half_night, self.dhuhr()));
+    }
+
+    pub fn timing(&self, timing: &Timing) -> NaiveTime {
+        match timing {
+            Timing::Imsak => self.imsak(),
+            Timing::Fajr => self.fajr(),
+            Timing::Sunrise => self.sunrise(),
+            Timing::Duha => self.duha(),
+            Timing::Dhuhr => self.dhuhr(),
+            Timing::Asr => self.asr(),
+            Timing::Maghrib => self.maghrib(),
+            Timing::Isha => self.isha(),
+            Timing::Midnight => self.midnight(),
+            Timing::LastThird => self.last_third(),
+        }
+    }
 
-    /// Latitude
-    lat: f64,
+    /// Returns a timing as whole minutes since local midnight (0 - 1439), e.g. for alarm apps and bitmap schedulers.
+    /// Seconds are dropped. The minutes count from the midnight of the day the timing falls on, so the midnight
+    /// timing (and isha or the last third past midnight) are early on the next day, see `datetime` for the date.
+    /// Panics if the timing has no solution, see `try_minutes_of_day`.
+    ///
+    /// ### Arguments
+    /// * `timing` - The timing to get
+    pub fn minutes_of_day(&self, timing: &Timing) -> u32 {
+        return self
+            .try_minutes_of_day(timing)
+            .expect("RangeError @ PrayerTime.minutes_of_day");
+    }
 
-    /// Longitude
-    lng: f64,
+    /// Returns a timing as whole minutes since local midnight like `minutes_of_day`, or an error instead of
+    /// panicking when it has no solution (see `try_timing`)
+    ///
+    /// ### Arguments
+    /// * `timing` - The timing to get
+    pub fn try_minutes_of_day(&self, timing: &Timing) -> Result<u32, SalahError> {
+        let time = self.try_timing(timing)?;
+        return Ok(time.hour() * 60 + time.minute());
+    }
 
-    // Date
-    date: NaiveDate,
+    /// Returns the full date and time (with the GMT offset used for the calculations) of a timing.
+    /// Evening timings that fall past midnight (e.g. isha in the summer, midnight) are rolled over to the next day,
+    /// and morning timings that wrap back before midnight (far from the timezone's meridian) to the previous day.
+    pub fn datetime(&self, timing: &Timing) -> DateTime<FixedOffset> {
+        let time = self.timing(timing);
+        return self.rollover_datetime(timing, time);
+    }
 
-    // Timezone offset from GMT
-    tz_offset: f64,
+    /// Returns the full date and time of a timing like `datetime`, or an error instead of panicking
+    /// when it has no solution (see `try_timing`)
+    ///
+    /// ### Arguments
+    /// * `timing` - The timing to compute
+    pub fn try_datetime(&self, timing: &Timing) -> Result<DateTime<FixedOffset>, SalahError> {
+        let time = self.try_timing(timing)?;
+        return Ok(self.rollover_datetime(timing, time));
+    }
 
-    // Julian date
-    jd: f64,
+    /// Returns every timing at once.
+    /// The sun coordinates and zenith are shared, and fajr, sunrise and maghrib are computed once for the timings
+    /// derived from them.
+    pub fn all(&self) -> Times {
+        let events = self.sun_events();
+        let get = |timing: Timing| {
+            return self
+                .timing_from(&timing, &events)
+                .expect("RangeError @ PrayerTime.all");
+        };
+        return Times {
+            imsak: get(Timing::Imsak),
+            fajr: get(Timing::Fajr),
+            sunrise: get(Timing::Sunrise),
+            duha: get(Timing::Duha),
+            dhuhr: get(Timing::Dhuhr),
+            asr: get(Timing::Asr),
+            maghrib: get(Timing::Maghrib),
+            isha: get(Timing::Isha),
+            midnight: get(Timing::Midnight),
+            last_third: get(Timing::LastThird),
+        };
+    }
+
+    /// Returns every timing at once like `all`, with `None` for the timings that have no solution
+    /// instead of panicking. Near the poles the sun may never set (or rise) or never reach the
+    /// fajr/isha angles, and the timings that depend on those events are `None`.
+    pub fn all_checked(&self) -> CheckedTimes {
+        let events = self.sun_events();
+        let get = |timing: Timing| self.timing_from(&timing, &events).ok();
+        return CheckedTimes {
+            imsak: get(Timing::Imsak),
+            fajr: get(Timing::Fajr),
+            sunrise: get(Timing::Sunrise),
+            duha: get(Timing::Duha),
+            dhuhr: get(Timing::Dhuhr),
+            asr: get(Timing::Asr),
+            maghrib: get(Timing::Maghrib),
+            isha: get(Timing::Isha),
+            midnight: get(Timing::Midnight),
+            last_third: get(Timing::LastThird),
+        };
+    }
+
+    /// Returns an iterator over every timing with a solution on the date as `(Timing, NaiveTime)`, in chronological order
+    /// rather than the order of `Timing::list`. Timings past midnight (e.g. midnight, or isha in a high latitude summer)
+    /// come last, and timings with no solution (see `all_checked`) are skipped.
+    pub fn iter(&self) -> TimingsIter {
+        let checked = self.all_checked();
+        let dhuhr = checked.dhuhr.unwrap_or_else(|| self.dhuhr());
+        let mut timings: Vec<(Timing, NaiveTime)> = Timing::list()
+            .into_iter()
+            .filter_map(|timing| checked.get(&timing).map(|time| (timing, time)))
+            .collect();
+        timings.sort_by_key(|(timing, time)| (rollover_days(timing, *time, dhuhr), *time));
+        return TimingsIter {
+            timings: timings.into_iter(),
+        };
+    }
+
+    /// Returns a lazy iterator over every date from `start` to `end` (inclusive) with its timings.
+    /// The configuration is cloned, so only the date changes. Yields nothing if `start` is after `end`.
+    ///
+    /// ### Arguments
+    /// * `start` - The first date
+    /// * `end` - The last date
+    pub fn range(&self, start: NaiveDate, end: NaiveDate) -> TimesRange {
+        return TimesRange {
+            pt: self.clone(),
+            next: Some(start),
+            end,
+        };
+    }
+
+    /// Computes the timings for every day of a year (366 days in leap years) and summarizes the extremes:
+    /// the earliest and latest fajr and isha, and the longest and shortest fasts (fajr to maghrib).
+    /// Times are compared on the wall clock, so daylight savings shifts them.
+    /// Like `all`, this panics if a timing has no solution on some day (e.g. near the poles).
+    ///
+    /// ### Arguments
+    /// * `year` - The year to summarize
+    pub fn year_extremes(&self, year: i32) -> YearSummary {
+        let start =
+            NaiveDate::from_ymd_opt(year, 1, 1).expect("RangeError @ PrayerTime.year_extremes");
+        let end =
+            NaiveDate::from_ymd_opt(year, 12, 31).expect("RangeError @ PrayerTime.year_extremes");
+
+        let mut days: u32 = 0;
+        let mut fajrs: Vec<(NaiveDate, NaiveDateTime)> = vec![];
+        let mut ishas: Vec<(NaiveDate, NaiveDateTime)> = vec![];
+        let mut fasts: Vec<(NaiveDate, Duration)> = vec![];
+        for (date, times) in self.range(start, end) {
+            days += 1;
+            let fajr = times.datetime(date, &Timing::Fajr);
+            fajrs.push((date, fajr));
+            ishas.push((date, times.datetime(date, &Timing::Isha)));
+            fasts.push((date, times.datetime(date, &Timing::Maghrib) - fajr));
+        }
+
+        // Compare the times from the start of their date, so isha past midnight is the latest
+        let since_midnight = |(date, datetime): &(NaiveDate, NaiveDateTime)| {
+            *datetime - date.and_time(NaiveTime::MIN)
+        };
+        let extreme = |values: &[(NaiveDate, NaiveDateTime)], latest: bool| {
+            let found = if latest {
+                values.iter().max_by_key(|v| since_midnight(v))
+            } else {
+                values.iter().min_by_key(|v| since_midnight(v))
+            };
+            let (date, datetime) = found.expect("RangeError @ PrayerTime.year_extremes");
+            return (*date, datetime.time());
+        };
+        let longest_fast = fasts.iter().max_by_key(|(_, span)| *span);
+        let shortest_fast = fasts.iter().min_by_key(|(_, span)| *span);
+
+        return YearSummary {
+            year,
+            days,
+            earliest_fajr: extreme(&fajrs, false),
+            latest_fajr: extreme(&fajrs, true),
+            earliest_isha: extreme(&ishas, false),
+            latest_isha: extreme(&ishas, true),
+            longest_fast: *longest_fast.expect("RangeError @ PrayerTime.year_extremes"),
+            shortest_fast: *shortest_fast.expect("RangeError @ PrayerTime.year_extremes"),
+        };
+    }
+
+    /// Returns every available timing with both its wall clock time and its full date and time
+    pub fn all_times(&self) -> Vec<TimingResult> {
+        let dhuhr = self.dhuhr();
+        return Timing::list()
+            .into_iter()
+            .map(|timing| {
+                let time = self.timing(&timing);
+                let datetime = self.rollover_datetime_from(&timing, time, dhuhr);
+                TimingResult {
+                    timing,
+                    time,
+                    datetime,
+                    local: self.zoned(datetime.naive_local()),
+                }
+            })
+            .collect();
+    }
+
+    /// Returns the next fardh prayer after `now` on the configured date.
+    /// After isha, the next prayer is the following day's fajr. Prayers with no solution are skipped, as is an isha
+    /// that overlaps the next fajr (see `isha_overlaps_fajr`).
+    ///
+    /// ### Arguments
+    /// * `now` - The local wall clock time to look from, `None` for the time of the stored instant (see `with_datetime`)
+    pub fn next_prayer(&self, now: impl Into<Option<NaiveTime>>) -> (Timing, NaiveTime) {
+        let (timing, datetime) = self.next_prayer_datetime(self.local_now(now.into()));
+        return (timing, datetime.time());
+    }
+
+    /// Returns the fardh prayer whose time has most recently started at `now` on the configured date.
+    /// Before fajr, the current prayer is the previous day's isha. Prayers with no solution are skipped, as is an isha
+    /// that overlaps the next fajr (see `isha_overlaps_fajr`).
+    ///
+    /// ### Arguments
+    /// * `now` - The local wall clock time to look from, `None` for the time of the stored instant (see `with_datetime`)
+    pub fn current_prayer(&self, now: impl Into<Option<NaiveTime>>) -> (Timing, NaiveTime) {
+        let now = self.local_now(now.into());
+        let prev_day = self
+            .date
+            .pred_opt()
+            .expect("RangeError @ PrayerTime.current_prayer");
+        // The previous day's dhuhr always has a solution
+        let (timing, datetime) = self
+            .clone()
+            .with_date(&prev_day)
+            .fardh_datetimes()
+            .into_iter()
+            .chain(self.fardh_datetimes())
+            .filter(|(_, datetime)| *datetime <= now)
+            .max_by_key(|(_, datetime)| *datetime)
+            .expect("RangeError @ PrayerTime.current_prayer");
+        return (timing, datetime.time());
+    }
+
+    /// Returns the fardh prayer closest to `now` on the configured date, before or after it.
+    /// The previous day's isha and the next day's fajr are included, so late night times can be
+    /// nearest to either. Ties go to the earlier prayer, and prayers with no solution are skipped.
+    ///
+    /// ### Arguments
+    /// * `now` - The local wall clock time to look from
+    pub fn nearest(&self, now: NaiveTime) -> (Timing, NaiveTime) {
+        let now = self.date.and_time(now);
+        let prev_day = self
+            .date
+            .pred_opt()
+            .expect("RangeError @ PrayerTime.nearest");
+        let next_day = self
+            .date
+            .succ_opt()
+            .expect("RangeError @ PrayerTime.nearest");
+
+        let yesterday = self.clone().with_date(&prev_day);
+        let tomorrow = self.clone().with_date(&next_day);
+        let candidates = std::iter::once((&yesterday, Timing::Isha))
+            .chain(
+                Timing::fardh_list()
+                    .into_iter()
+                    .map(|timing| (self, timing)),
+            )
+            .chain(std::iter::once((&tomorrow, Timing::Fajr)));
 
-    // Calculation authority
-    auth: Authority,
+        // Dhuhr always has a solution, so there is at least one candidate
+        let (timing, datetime) = candidates
+            .filter_map(|(pt, timing)| {
+                let datetime = pt.try_datetime(&timing).ok()?;
+                return Some((timing, datetime.naive_local()));
+            })
+            .min_by_key(|(_, datetime)| (*datetime - now).num_seconds().abs())
+            .expect("RangeError @ PrayerTime.nearest");
+        return (timing, datetime.time());
+    }
+
+    /// Returns the time left until the next fardh prayer after `now`, crossing into the next day's fajr after isha.
+    /// If `now` is exactly on a prayer time, the duration to the following prayer is returned.
+    ///
+    /// ### Arguments
+    /// * `now` - The local date and time to count from, the configured date is replaced by its date
+    pub fn time_until_next(&self, now: NaiveDateTime) -> Duration {
+        let (_, next) = self
+            .clone()
+            .with_date(&now.date())
+            .next_prayer_datetime(now);
+        return next - now;
+    }
+
+    /// Next fardh prayer strictly after `now` (on the configured date) with its full date and time
+    fn next_prayer_datetime(&self, now: NaiveDateTime) -> (Timing, NaiveDateTime) {
+        let next_day = self
+            .date
+            .succ_opt()
+            .expect("RangeError @ PrayerTime.next_prayer");
+        // The next day's dhuhr always has a solution
+        return self
+            .fardh_datetimes()
+            .into_iter()
+            .chain(self.clone().with_date(&next_day).fardh_datetimes())
+            .filter(|(_, datetime)| *datetime > now)
+            .min_by_key(|(_, datetime)| *datetime)
+            .expect("RangeError @ PrayerTime.next_prayer");
+    }
+
+    /// Fardh prayers of the configured date with their full date and time. Prayers with no solution are left out,
+    /// as is isha when its window collapses into the next fajr (see `isha_overlaps_fajr`).
+    fn fardh_datetimes(&self) -> Vec<(Timing, NaiveDateTime)> {
+        let collapsed = self.isha_overlaps_fajr();
+        return Timing::fardh_list()
+            .into_iter()
+            .filter(|timing| !(collapsed && *timing == Timing::Isha))
+            .filter_map(|timing| {
+                let datetime = self.try_datetime(&timing).ok()?;
+                return Some((timing, datetime.naive_local()));
+            })
+            .collect();
+    }
+
+    /// Validates the configuration and computes every timing once into an immutable `BuiltTimes`.
+    ///
+    /// Returns `SalahError::OutOfRange` if the location or offset is out of range, and `SalahError::NoSunEvent` if
+    /// any timing has no solution for the configured date and location (e.g. high latitudes in the summer),
+    /// including a midnight that depends on the next day's fajr.
+    pub fn compute(&self) -> Result<BuiltTimes, SalahError> {
+        if !(-90_f64..=90_f64).contains(&self.lat) {
+            return Err(SalahError::OutOfRange(format!(
+                "latitude = `{}` is not in [-90, 90]",
+                self.lat
+            )));
+        }
+        // Accepted longitudes were wrapped into [-180, 180] when set, the others were kept
+        if !(-180_f64..=180_f64).contains(&self.lng) {
+            return Err(SalahError::OutOfRange(format!(
+                "longitude = `{}` is not in [-180, 180] or [0, 360)",
+                self.lng
+            )));
+        }
+        if !(-14_f64..=14_f64).contains(&self.tz_offset) {
+            return Err(SalahError::OutOfRange(format!(
+                "timezone offset = `{}` is not in [-14, 14]",
+                self.tz_offset
+            )));
+        }
+
+        let events = self.sun_events();
+        let dhuhr = self.timing_from(&Timing::Dhuhr, &events)?;
+        let times = Timing::list()
+            .into_iter()
+            .map(|timing| {
+                let time = self.timing_from(&timing, &events)?;
+                let datetime = self.rollover_datetime_from(&timing, time, dhuhr);
+                return Ok(TimingResult {
+                    timing,
+                    time,
+                    datetime,
+                    local: self.zoned(datetime.naive_local()),
+                });
+            })
+            .collect::<Result<Vec<TimingResult>, SalahError>>()?;
+
+        return Ok(BuiltTimes {
+            date: self.date,
+            times,
+        });
+    }
+
+    /// A local time on the configured date, or the local date and time of the stored instant (which may be on
+    /// another date, e.g. once the timezone changed) if there is none
+    fn local_now(&self, now: Option<NaiveTime>) -> NaiveDateTime {
+        return match now {
+            Some(now) => self.date.and_time(now),
+            None => self.instant.with_timezone(&self.utc_offset()).naive_local(),
+        };
+    }
 
-    // School of thought for jurisprudence
-    school: School,
+    fn rollover_datetime(&self, timing: &Timing, time: NaiveTime) -> DateTime<FixedOffset> {
+        return self.rollover_datetime_from(timing, time, self.dhuhr());
+    }
+
+    fn rollover_datetime_from(
+        &self,
+        timing: &Timing,
+        time: NaiveTime,
+        dhuhr: NaiveTime,
+    ) -> DateTime<FixedOffset> {
+        let date = self
+            .date
+            .checked_add_signed(Duration::days(rollover_days(timing, time, dhuhr)))
+            .expect("RangeError @ PrayerTime.datetime");
+        return self
+            .utc_offset()
+            .from_local_datetime(&date.and_time(time))
+            .single()
+            .expect("RangeError @ PrayerTime.datetime");
+    }
 }
 
-impl PrayerTimes {
-    // =================== Constructors ============================
-    /// Creates a new prayer time struct with default parameters
+/// Days from the calculation date to the date a timing falls on, given its clock time.
+///
+/// Times are clock times in [0, 24), so an event whose hour falls outside of the day wraps around.
+/// Evening timings before dhuhr are past midnight (`1`), and morning timings after dhuhr wrapped back
+/// before midnight (`-1`), which happens far west of the timezone's meridian (or with an odd longitude/offset pair).
+///
+/// ### Arguments
+/// * `timing` - The timing
+/// * `time` - The clock time of the timing
+/// * `dhuhr` - The clock time of dhuhr on the calculation date
+fn rollover_days(timing: &Timing, time: NaiveTime, dhuhr: NaiveTime) -> i64 {
+    let evening = matches!(
+        timing,
+        Timing::Asr | Timing::Maghrib | Timing::Isha | Timing::Midnight | Timing::LastThird
+    );
+    if evening && time < dhuhr {
+        return 1;
+    }
+    if !evening && *timing != Timing::Dhuhr && time > dhuhr {
+        return -1;
+    }
+    return 0;
+}
+
+/// Returns the time a fraction of the way through the night, from its start to its end (the next morning).
+/// Works on the times directly rather than fractional hours, so no precision is lost converting back and forth.
+///
+/// ### Arguments
+/// * `start` - The time the night starts (e.g. sunset)
+/// * `end` - The time the night ends (e.g. sunrise or fajr), can be earlier than `start`
+/// * `fraction` - Fraction of the night (e.g. `0.5` for the midpoint)
+/// * `rounding` - How the time is rounded
+fn night_point(
+    start: NaiveTime,
+    end: NaiveTime,
+    fraction: f64,
+    rounding: datetime::Rounding,
+) -> NaiveTime {
+    let night = (end - start).num_milliseconds().rem_euclid(86_400_000);
+    let offset = Duration::milliseconds((night as f64 * fraction).round() as i64);
+    return datetime::round_time(start + offset, rounding);
+}
+
+/// Wraps an accepted longitude (see `math::is_valid_longitude`) into [-180, 180]. Others are kept as is,
+/// so that `PrayerTimes::build` rejects them.
+fn accepted_longitude(lng: f64) -> f64 {
+    if math::is_valid_longitude(lng) {
+        return math::normalize_longitude(lng);
+    }
+    return lng;
+}
+
+/// Unadjusted fajr, sunrise and maghrib times of a date, or why the sun never reaches them
+struct SunEvents {
+    fajr: Result<NaiveTime, SalahError>,
+    sunrise: Result<NaiveTime, SalahError>,
+    maghrib: Result<NaiveTime, SalahError>,
+}
+
+/// Every timing for a single date (see `PrayerTimes::all`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Times {
+    pub imsak: NaiveTime,
+    pub fajr: NaiveTime,
+    pub sunrise: NaiveTime,
+    pub duha: NaiveTime,
+    pub dhuhr: NaiveTime,
+    pub asr: NaiveTime,
+    pub maghrib: NaiveTime,
+    pub isha: NaiveTime,
+    pub midnight: NaiveTime,
+    pub last_third: NaiveTime,
+}
+
+impl Times {
+    /// Returns the time of a single timing
     ///
     /// ### Arguments
-    /// * `lat` - The latitude value to calculate for
-    /// * `lng` - The longitude value to calculate for
+    /// * `timing` - The timing to get
+    pub fn get(&self, timing: &Timing) -> NaiveTime {
+        return match timing {
+            Timing::Imsak => self.imsak,
+            Timing::Fajr => self.fajr,
+            Timing::Sunrise => self.sunrise,
+            Timing::Duha => self.duha,
+            Timing::Dhuhr => self.dhuhr,
+            Timing::Asr => self.asr,
+            Timing::Maghrib => self.maghrib,
+            Timing::Isha => self.isha,
+            Timing::Midnight => self.midnight,
+            Timing::LastThird => self.last_third,
+        };
+    }
+
+    /// Returns the local date and time of a single timing.
+    /// Evening timings before dhuhr (e.g. midnight) are rolled over to the next day,
+    /// and morning timings after dhuhr (wrapped back before midnight) to the previous day.
     ///
-    /// ### Defaults
-    /// * `tz` - Timezone default is set to `America/Toronto`
-    /// * `date` - Date defaults to today's date
-    /// * `auth` - Calculation authority defaults to Islamic Society of North America (ISNA) (used for fajr and isha time)
-    /// * `school` - School of thought for jurisprudence (used for asr timing) defaults to Hanafi
-    pub fn new(lat: f64, lng: f64) -> PrayerTimes {
-        const DEFAULT_TZ: &str = "America/Toronto";
-        let tz: Tz = DEFAULT_TZ.parse().expect("Invalid time zone!");
-        let default_date = tz.from_utc_datetime(&Utc::now().naive_utc()).date_naive();
+    /// ### Arguments
+    /// * `date` - The date the timings were calculated for
+    /// * `timing` - The timing to get
+    pub fn datetime(&self, date: NaiveDate, timing: &Timing) -> NaiveDateTime {
+        let time = self.get(timing);
+        return date
+            .checked_add_signed(Duration::days(rollover_days(timing, time, self.dhuhr)))
+            .expect("RangeError @ Times.datetime")
+            .and_time(time);
+    }
 
-        return PrayerTimes {
-            tz,
-            lat,
-            lng,
-            date: default_date,
-            tz_offset: datetime::tz_offset(tz),
-            jd: astro::julian(default_date),
-            auth: Authority::ISNA,
-            school: School::Hanafi,
+    /// Returns the timings formatted one per line as `<name> <time>`, or just `<time>` without labels.
+    /// Times are formatted with their full date (see `datetime`), so `format` can include date specifiers.
+    ///
+    /// ### Arguments
+    /// * `date` - The date the timings were calculated for
+    /// * `offset` - The UTC offset of the times (see `PrayerTimes::utc_offset`), for `%z` and similar specifiers
+    /// * `timings` - The timings to include, in order (e.g. `Timing::list()`)
+    /// * `format` - strftime format string for the times, e.g. `%H:%M`
+    /// * `labels` - Whether each line starts with the name of the timing
+    pub fn pretty(
+        &self,
+        date: NaiveDate,
+        offset: &FixedOffset,
+        timings: &[Timing],
+        format: &str,
+        labels: bool,
+    ) -> String {
+        return CheckedTimes::from(*self).pretty(date, offset, timings, format, labels, "");
+    }
+}
+
+/// Every timing for a single date, `None` when it has no solution (see `PrayerTimes::all_checked`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CheckedTimes {
+    pub imsak: Option<NaiveTime>,
+    pub fajr: Option<NaiveTime>,
+    pub sunrise: Option<NaiveTime>,
+    pub duha: Option<NaiveTime>,
+    pub dhuhr: Option<NaiveTime>,
+    pub asr: Option<NaiveTime>,
+    pub maghrib: Option<NaiveTime>,
+    pub isha: Option<NaiveTime>,
+    pub midnight: Option<NaiveTime>,
+    pub last_third: Option<NaiveTime>,
+}
+
+impl CheckedTimes {
+    /// Returns the time of a single timing, `None` if it has no solution
+    ///
+    /// ### Arguments
+    /// * `timing` - The timing to get
+    pub fn get(&self, timing: &Timing) -> Option<NaiveTime> {
+        return match timing {
+            Timing::Imsak => self.imsak,
+            Timing::Fajr => self.fajr,
+            Timing::Sunrise => self.sunrise,
+            Timing::Duha => self.duha,
+            Timing::Dhuhr => self.dhuhr,
+            Timing::Asr => self.asr,
+            Timing::Maghrib => self.maghrib,
+            Timing::Isha => self.isha,
+            Timing::Midnight => self.midnight,
+            Timing::LastThird => self.last_third,
         };
     }
 
-    // =============== Setters =================
+    /// Returns the local date and time of a single timing like `Times::datetime`, `None` if it has no solution
+    ///
+    /// ### Arguments
+    /// * `date` - The date the timings were calculated for
+    /// * `timing` - The timing to get
+    pub fn datetime(&self, date: NaiveDate, timing: &Timing) -> Option<NaiveDateTime> {
+        let time = self.get(timing)?;
+        return date
+            .checked_add_signed(Duration::days(rollover_days(timing, time, self.dhuhr?)))
+            .map(|date| date.and_time(time));
+    }
+
+    /// Returns the timings formatted one per line like `Times::pretty`, with `missing` in place of the time of the
+    /// timings that have no solution (e.g. `N/A` near the poles)
+    ///
+    /// ### Arguments
+    /// * `date` - The date the timings were calculated for
+    /// * `offset` - The UTC offset of the times (see `PrayerTimes::utc_offset`), for `%z` and similar specifiers
+    /// * `timings` - The timings to include, in order (e.g. `Timing::list()`)
+    /// * `format` - strftime format string for the times, e.g. `%H:%M`
+    /// * `labels` - Whether each line starts with the name of the timing
+    /// * `missing` - Printed instead of the time of a timing with no solution
+    pub fn pretty(
+        &self,
+        date: NaiveDate,
+        offset: &FixedOffset,
+        timings: &[Timing],
+        format: &str,
+        labels: bool,
+        missing: &str,
+    ) -> String {
+        let lines: Vec<String> = timings
+            .iter()
+            .map(|timing| {
+                let time = match self.datetime(date, timing) {
+                    Some(datetime) => offset
+                        .from_local_datetime(&datetime)
+                        .single()
+                        .expect("RangeError @ CheckedTimes.pretty")
+                        .format(format)
+                        .to_string(),
+                    None => String::from(missing),
+                };
+                if labels {
+                    return format!("{} {}", timing.to_str(), time);
+                }
+                return time;
+            })
+            .collect();
+        return lines.join("\n");
+    }
+}
+
+impl From<Times> for CheckedTimes {
+    fn from(times: Times) -> Self {
+        return CheckedTimes {
+            imsak: Some(times.imsak),
+            fajr: Some(times.fajr),
+            sunrise: Some(times.sunrise),
+            duha: Some(times.duha),
+            dhuhr: Some(times.dhuhr),
+            asr: Some(times.asr),
+            maghrib: Some(times.maghrib),
+            isha: Some(times.isha),
+            midnight: Some(times.midnight),
+            last_third: Some(times.last_third),
+        };
+    }
+}
+
+/// Extremes of the timings across a year (see `PrayerTimes::year_extremes`).
+/// Each extreme is the first date it occurs on, with the time or the length of the fast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct YearSummary {
+    pub year: i32,
+
+    /// Number of days in the year
+    pub days: u32,
+
+    pub earliest_fajr: (NaiveDate, NaiveTime),
+    pub latest_fajr: (NaiveDate, NaiveTime),
+    pub earliest_isha: (NaiveDate, NaiveTime),
+    pub latest_isha: (NaiveDate, NaiveTime),
+
+    /// Longest span from fajr to maghrib
+    pub longest_fast: (NaiveDate, Duration),
+
+    /// Shortest span from fajr to maghrib
+    pub shortest_fast: (NaiveDate, Duration),
+}
+
+/// Iterator over the timings of consecutive dates (see `PrayerTimes::range`)
+#[derive(Debug, Clone)]
+pub struct TimesRange {
+    pt: PrayerTimes,
+    next: Option<NaiveDate>,
+    end: NaiveDate,
+}
+
+impl Iterator for TimesRange {
+    type Item = (NaiveDate, Times);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let date = self.next.filter(|date| *date <= self.end)?;
+        self.next = date.succ_opt();
+        self.pt = self.pt.clone().with_date(&date);
+        return Some((date, self.pt.all()));
+    }
+}
+
+/// Iterator over the timings of a date in chronological order (see `PrayerTimes::iter`)
+#[derive(Debug, Clone)]
+pub struct TimingsIter {
+    timings: std::vec::IntoIter<(Timing, NaiveTime)>,
+}
+
+impl Iterator for TimingsIter {
+    type Item = (Timing, NaiveTime);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.timings.next();
+    }
+}
+
+impl Default for PrayerTimes {
+    /// Prayer times at latitude 0 and longitude 0 with the defaults of `PrayerTimes::new`.
+    /// Set the location with `with_location` before computing any timings.
+    fn default() -> Self {
+        return PrayerTimes::new(0_f64, 0_f64);
+    }
+}
+
+/// Validating builder for `PrayerTimes` (see `PrayerTimes::builder`).
+/// Anything not set keeps the defaults of `PrayerTimes::new`.
+#[derive(Debug, Clone)]
+pub struct PrayerTimesBuilder {
+    lat: f64,
+    lng: f64,
+    date: Option<NaiveDate>,
+    tz: Option<Tz>,
+    offset_hours: Option<f64>,
+    auth: Option<Authority>,
+    school: Option<School>,
+    elevation: f64,
+}
+
+impl PrayerTimesBuilder {
     /// Sets the date to compute timings for
     pub fn with_date(mut self, date: &NaiveDate) -> Self {
-        self.date = *date;
-        self.jd = astro::julian(*date);
+        self.date = Some(*date);
         return self;
     }
 
     /// Sets the timezone
     pub fn with_timezone(mut self, tz: &Tz) -> Self {
-        self.tz = *tz;
-        self.tz_offset = datetime::tz_offset(*tz);
+        self.tz = Some(*tz);
+        return self;
+    }
+
+    /// Sets a fixed timezone offset from GMT in hours (see `PrayerTimes::with_offset_hours`)
+    pub fn with_offset_hours(mut self, hours: f64) -> Self {
+        self.offset_hours = Some(hours);
         return self;
     }
 
     /// Sets the calculation authority
     pub fn with_authority(mut self, auth: &Authority) -> Self {
-        self.auth = *auth;
+        self.auth = Some(*auth);
         return self;
     }
 
     /// Sets the school of thought
     pub fn with_school(mut self, school: &School) -> Self {
-        self.school = *school;
+        self.school = Some(*school);
         return self;
     }
 
-    // ================= Private Methods =======================
-    fn zenith(&self) -> f64 {
-        return astro::zenith(self.jd, self.lng, self.tz_offset);
+    /// Sets the height of the observer above sea level in meters
+    pub fn with_elevation(mut self, meters: f64) -> Self {
+        self.elevation = meters;
+        return self;
+    }
+
+    /// Validates the inputs and creates the `PrayerTimes`.
+    ///
+    /// Returns `SalahError::OutOfRange` if the coordinates, offset or elevation are out of range,
+    /// and `SalahError::InvalidDate` if the date is outside of years 1 to 9999.
+    pub fn build(self) -> Result<PrayerTimes, SalahError> {
+        if !(-90_f64..=90_f64).contains(&self.lat) {
+            return Err(SalahError::OutOfRange(format!(
+                "latitude = `{}` is not in [-90, 90]",
+                self.lat
+            )));
+        }
+        if !math::is_valid_longitude(self.lng) {
+            return Err(SalahError::OutOfRange(format!(
+                "longitude = `{}` is not in [-180, 180] or [0, 360)",
+                self.lng
+            )));
+        }
+        if !self.elevation.is_finite() {
+            return Err(SalahError::OutOfRange(format!(
+                "elevation = `{}` is not a number of meters",
+                self.elevation
+            )));
+        }
+        if let Some(date) = self.date {
+            if !(1..=9999).contains(&date.year()) {
+                return Err(SalahError::InvalidDate(format!(
+                    "`{}` is not in years 1 to 9999",
+                    date
+                )));
+            }
+        }
+        if let Some(hours) = self.offset_hours {
+            if !(-14_f64..=14_f64).contains(&hours) {
+                return Err(SalahError::OutOfRange(format!(
+                    "timezone offset = `{}` is not in [-14, 14]",
+                    hours
+                )));
+            }
+        }
+
+        let mut pt = PrayerTimes::new(self.lat, self.lng).with_elevation(self.elevation);
+        if let Some(tz) = self.tz {
+            pt = pt.with_timezone(&tz);
+        }
+        if let Some(hours) = self.offset_hours {
+            pt = pt.with_offset_hours(hours);
+        }
+        if let Some(date) = self.date {
+            pt = pt.with_date(&date);
+        }
+        if let Some(auth) = self.auth {
+            pt = pt.with_authority(&auth);
+        }
+        if let Some(school) = self.school {
+            pt = pt.with_school(&school);
+        }
+        return Ok(pt);
+    }
+}
+
+/// A computed timing with its wall clock time and fully qualified date and time
+#[derive(Debug, Clone, Serialize)]
+pub struct TimingResult {
+    /// The timing that was computed
+    pub timing: Timing,
+
+    /// The local wall clock time
+    pub time: NaiveTime,
+
+    /// The date and time with the GMT offset used for the calculations, rolled over to the next day when needed
+    pub datetime: DateTime<FixedOffset>,
+
+    /// The same date and time in the named timezone, `None` for a fixed offset or local mean time (see `to_local`)
+    pub local: Option<DateTime<Tz>>,
+}
+
+/// Every timing computed once from a validated `PrayerTimes` configuration (see `PrayerTimes::compute`)
+#[derive(Debug, Clone, Serialize)]
+pub struct BuiltTimes {
+    /// The date the timings were computed for
+    date: NaiveDate,
+
+    /// Every timing in `Timing::list()` order
+    times: Vec<TimingResult>,
+}
+
+impl BuiltTimes {
+    /// Returns the date the timings were computed for
+    pub fn date(&self) -> NaiveDate {
+        return self.date;
+    }
+
+    /// Returns every computed timing
+    pub fn all(&self) -> &[TimingResult] {
+        return &self.times;
+    }
+
+    /// Returns the computed result for a timing
+    pub fn get(&self, timing: &Timing) -> &TimingResult {
+        return self
+            .times
+            .iter()
+            .find(|t| t.timing == *timing)
+            .expect("BuiltTimes is missing a timing");
+    }
+
+    /// Returns the wall clock time of a timing
+    pub fn timing(&self, timing: &Timing) -> NaiveTime {
+        return self.get(timing).time;
+    }
+
+    /// Returns the full date and time of a timing
+    pub fn datetime(&self, timing: &Timing) -> DateTime<FixedOffset> {
+        return self.get(timing).datetime;
+    }
+
+    pub fn imsak(&self) -> NaiveTime {
+        return self.timing(&Timing::Imsak);
     }
 
-    // ================= Public Methods ========================
-    /// Returns the fajr (dusk) prayer time
     pub fn fajr(&self) -> NaiveTime {
-        let angle = self.auth.fajr_angle();
-        let hour = astro::horizon_hour_angle(
-            angle,
-            self.jd,
-            self.zenith(),
-            self.lat,
-            astro::HorizonDirection::Sunrise,
-        );
-        return datetime::hour2time(hour, true).expect("RangeError @ PrayerTime.fajr");
+        return self.timing(&Timing::Fajr);
+    }
+
+    pub fn sunrise(&self) -> NaiveTime {
+        return self.timing(&Timing::Sunrise);
+    }
+
+    pub fn duha(&self) -> NaiveTime {
+        return self.timing(&Timing::Duha);
     }
 
-    /// Returns the dhuhr (mid-day) prayer time
     pub fn dhuhr(&self) -> NaiveTime {
-        return datetime::hour2time(self.zenith(), true).expect("RangeError @ PrayerTime.dhuhr");
+        return self.timing(&Timing::Dhuhr);
     }
 
-    /// Returns the asr (evening) prayer time
     pub fn asr(&self) -> NaiveTime {
-        let hour = astro::shadow_length_hour(
-            self.school.shadow_length(),
-            self.jd,
-            self.zenith(),
-            self.lat,
-        );
-        return datetime::hour2time(hour, true).expect("RangeError @ PrayerTime.asr");
+        return self.timing(&Timing::Asr);
     }
 
-    /// Returns the maghrib (sunset) prayer time
     pub fn maghrib(&self) -> NaiveTime {
-        let hour = astro::horizon_hour_angle(
-            0.833,
-            self.jd,
-            self.zenith(),
-            self.lat,
-            astro::HorizonDirection::Sunset,
-        );
-        return datetime::hour2time(hour, true).expect("RangeError @ PrayerTime.maghrib");
+        return self.timing(&Timing::Maghrib);
     }
 
-    /// Returns the isha (night) prayer time
     pub fn isha(&self) -> NaiveTime {
-        let param = self.auth.isha_param();
-        return match param {
-            IshaParam::Angle(a) => {
-                let hour = astro::horizon_hour_angle(
-                    a,
-                    self.jd,
-                    self.zenith(),
-                    self.lat,
-                    astro::HorizonDirection::Sunset,
-                );
-                let time = datetime::hour2time(hour, true).expect("RangeError @ PrayerTime.isha");
-                time
-            }
-            IshaParam::Duration(d) => {
-                let maghrib = datetime::time2hour(self.maghrib());
-                let sunset =
-                    datetime::hour2time(maghrib, true).expect("RangeError @ PrayerTime.isha");
-                sunset + d
-            }
-        };
-    }
-
-    /// Returns the sunrise time
-    pub fn sunrise(&self) -> NaiveTime {
-        let hour = astro::horizon_hour_angle(
-            0.833,
-            self.jd,
-            self.zenith(),
-            self.lat,
-            astro::HorizonDirection::Sunrise,
-        );
-
-        return datetime::hour2time(hour, true).expect("RangeError @ PrayerTime.sunrise");
+        return self.timing(&Timing::Isha);
     }
 
-    /// Returns the midnight time
     pub fn midnight(&self) -> NaiveTime {
-        let sunrise = datetime::time2hour(self.sunrise());
-        let sunset = datetime::time2hour(self.maghrib());
-
-        let mid = sunset + math::time::normalize_hour(sunrise - sunset) / 2_f64;
-        return datetime::hour2time(mid, true).expect("RangeError @ PrayerTime.midnight");
+        return self.timing(&Timing::Midnight);
     }
 
-    pub fn timing(&self, timing: &Timing) -> NaiveTime {
-        match timing {
-            Timing::Fajr => self.fajr(),
-            Timing::Sunrise => self.sunrise(),
-            Timing::Dhuhr => self.dhuhr(),
-            Timing::Asr => self.asr(),
-            Timing::Maghrib => self.maghrib(),
-            Timing::Isha => self.isha(),
-            Timing::Midnight => self.midnight(),
-        }
+    pub fn last_third(&self) -> NaiveTime {
+        return self.timing(&Timing::LastThird);
     }
 }
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use super::{days_since_solstice, evening_twilight_minutes, morning_twilight_minutes};
+
+    #[test]
+    fn test_days_since_solstice() {
+        // Northern hemisphere counts from the december solstice (~10 days before the new year)
+        assert_eq!(days_since_solstice(1, 2023, 43.0), 11);
+        assert_eq!(days_since_solstice(356, 2023, 43.0), 1);
+        assert_eq!(days_since_solstice(355, 2023, 43.0), 0);
+        assert_eq!(days_since_solstice(356, 2024, 43.0), 0);
+        // Southern hemisphere counts from the june solstice
+        assert_eq!(days_since_solstice(173, 2023, -33.0), 1);
+        assert_eq!(days_since_solstice(1, 2023, -33.0), 194);
+        assert_eq!(days_since_solstice(1, 2024, -33.0), 194);
+    }
+
+    #[test]
+    fn test_twilight_minutes() {
+        // No seasonal variation at the equator
+        for days in [0, 45, 100, 150, 200, 250, 300, 364] {
+            assert_eq!(morning_twilight_minutes(0.0, days), 75.0);
+            assert_eq!(evening_twilight_minutes(0.0, days), 75.0);
+        }
+
+        // London (51.5N), at the winter solstice and at the summer solstice
+        let lat = 51.5;
+        let winter = morning_twilight_minutes(lat, 0);
+        let summer = morning_twilight_minutes(lat, 183);
+        assert!((winter - (75.0 + 28.65 / 55.0 * lat)).abs() < 1e-9);
+        assert!((summer - (75.0 + 48.10 / 55.0 * lat)).abs() < 1e-9);
+        assert!((evening_twilight_minutes(lat, 0) - (75.0 + 25.60 / 55.0 * lat)).abs() < 1e-9);
+        assert!((evening_twilight_minutes(lat, 137) - (75.0 - 9.21 / 55.0 * lat)).abs() < 1e-9);
+        assert!((morning_twilight_minutes(lat, 91) - (75.0 + 19.44 / 55.0 * lat)).abs() < 1e-9);
+
+        // Symmetric around the summer solstice
+        assert!(
+            (morning_twilight_minutes(lat, 160) - morning_twilight_minutes(lat, 206)).abs() < 1e-9
+        );
+    }
+}
+
+/// Returns the number of days since the winter solstice of the hemisphere
+///
+/// ### Arguments
+/// * `day_of_year` - The day of the year (1 - 366)
+/// * `year` - The year, used for leap years
+/// * `lat` - The latitude value
+pub fn days_since_solstice(day_of_year: u32, year: i32, lat: f64) -> u32 {
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days_in_year: i64 = if leap { 366 } else { 365 };
+    let day = day_of_year as i64;
+    let days = if lat >= 0_f64 {
+        (day + 10).rem_euclid(days_in_year)
+    } else {
+        let southern_offset = if leap { 173 } else { 172 };
+        (day - southern_offset).rem_euclid(days_in_year)
+    };
+    return days as u32;
+}
+
+/// Returns the minutes before sunrise for fajr as per the Moonsighting Committee Worldwide (Khalid Shaukat).
+/// The interval varies with latitude and season rather than following a fixed angle.
+///
+/// ### Arguments
+/// * `lat` - The latitude value
+/// * `days` - Days since the winter solstice (see `days_since_solstice`)
+pub fn morning_twilight_minutes(lat: f64, days: u32) -> f64 {
+    let lat = lat.abs();
+    return seasonal(
+        75_f64 + 28.65 / 55_f64 * lat,
+        75_f64 + 19.44 / 55_f64 * lat,
+        75_f64 + 32.74 / 55_f64 * lat,
+        75_f64 + 48.10 / 55_f64 * lat,
+        days,
+    );
+}
+
+/// Returns the minutes after sunset for isha (general shafaq) as per the Moonsighting Committee Worldwide
+///
+/// ### Arguments
+/// * `lat` - The latitude value
+/// * `days` - Days since the winter solstice (see `days_since_solstice`)
+pub fn evening_twilight_minutes(lat: f64, days: u32) -> f64 {
+    let lat = lat.abs();
+    return seasonal(
+        75_f64 + 25.60 / 55_f64 * lat,
+        75_f64 + 2.050 / 55_f64 * lat,
+        75_f64 - 9.210 / 55_f64 * lat,
+        75_f64 + 6.140 / 55_f64 * lat,
+        days,
+    );
+}
+
+/// Piecewise linear interpolation through the seasonal values a (winter solstice), b, c and d (summer solstice)
+fn seasonal(a: f64, b: f64, c: f64, d: f64, days: u32) -> f64 {
+    let dyy = days as f64;
+    if dyy < 91_f64 {
+        return a + (b - a) / 91_f64 * dyy;
+    } else if dyy < 137_f64 {
+        return b + (c - b) / 46_f64 * (dyy - 91_f64);
+    } else if dyy < 183_f64 {
+        return c + (d - c) / 46_f64 * (dyy - 137_f64);
+    } else if dyy < 229_f64 {
+        return d + (c - d) / 46_f64 * (dyy - 183_f64);
+    } else if dyy < 275_f64 {
+        return c + (b - c) / 46_f64 * (dyy - 229_f64);
+    }
+    return b + (a - b) / 91_f64 * (dyy - 275_f64);
+}
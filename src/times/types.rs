@@ -1,19 +1,474 @@
-#[derive(Debug, Copy, Clone)]
+use crate::datetime::Rounding;
+use crate::error::SalahError;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Adjustments, AngleSet, Authority, CalculationParameters, IshaParam, MethodParams,
+        MidnightMethod, School, Timing, Twilight,
+    };
+    use crate::datetime::Rounding;
+
+    #[test]
+    fn test_school_from_str() {
+        for school in School::list() {
+            assert_eq!(School::from_str(school.to_str()).expect("Error!"), school);
+        }
+        assert_eq!(School::from_str("Standard").expect("Error!"), School::Shafi);
+        assert_eq!(
+            School::from_str("HANBALI").expect("Error!"),
+            School::Hanbali
+        );
+        assert!(School::from_str("zahiri").is_err());
+
+        assert_eq!(
+            School::Maliki.shadow_length(),
+            School::Shafi.shadow_length()
+        );
+        assert_eq!(
+            School::Hanbali.shadow_length(),
+            School::Shafi.shadow_length()
+        );
+        assert_eq!(School::Hanafi.shadow_length(), 2_f64);
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        use crate::error::SalahError;
+
+        assert_eq!(Timing::Isha.to_string(), "isha");
+        assert_eq!(Timing::LastThird.to_string(), "last-third");
+        assert_eq!(format!("{} at", Timing::Fajr), "fajr at");
+        assert_eq!(Authority::ISNA.to_string(), "ISNA");
+        assert_eq!(
+            format!("{:>6}|{:<8}|", Authority::MWL, Timing::Asr),
+            "   MWL|asr     |"
+        );
+
+        assert_eq!("isha".parse::<Timing>(), Ok(Timing::Isha));
+        assert_eq!("Tahajjud".parse::<Timing>(), Ok(Timing::LastThird));
+        assert_eq!(
+            "brunch".parse::<Timing>(),
+            Err(SalahError::InvalidTiming(String::from("brunch")))
+        );
+        assert_eq!("makkah".parse::<Authority>(), Ok(Authority::Makkah));
+        assert!(matches!(
+            "nasa".parse::<Authority>(),
+            Err(SalahError::InvalidAuthority(_))
+        ));
+
+        for timing in Timing::list() {
+            assert_eq!(timing.to_string().parse::<Timing>(), Ok(timing));
+        }
+        for auth in Authority::list() {
+            assert_eq!(auth.to_string().parse::<Authority>(), Ok(auth));
+        }
+    }
+
+    #[test]
+    fn test_params() {
+        let params: MethodParams = Authority::MWL.into();
+        assert_eq!(params.fajr_angle, 18_f64);
+        assert_eq!(params.isha, IshaParam::Angle(17_f64));
+
+        let custom = Authority::Custom {
+            fajr_angle: 16.5,
+            isha: IshaParam::Angle(15_f64),
+        };
+        assert_eq!(custom.params().fajr_angle, 16.5);
+    }
+
+    #[test]
+    fn test_angles() {
+        assert_eq!(
+            Authority::MWL.angles(),
+            AngleSet {
+                fajr: 18_f64,
+                isha: Some(17_f64),
+                isha_minutes: None
+            }
+        );
+        assert_eq!(
+            Authority::Makkah.angles(),
+            AngleSet {
+                fajr: 18.5,
+                isha: None,
+                isha_minutes: Some(90)
+            }
+        );
+        for auth in Authority::list() {
+            let angles = auth.angles();
+            assert_eq!(angles.fajr, auth.fajr_angle());
+            assert!(angles.isha.is_some() != angles.isha_minutes.is_some());
+        }
+    }
+
+    #[test]
+    fn test_makkah_isha_duration() {
+        match Authority::Makkah.isha_param() {
+            IshaParam::Duration(d) => assert_eq!(d.as_secs(), 5400),
+            IshaParam::Angle(_) => panic!("Makkah isha should be a duration"),
+        }
+    }
+
+    #[test]
+    fn test_isha_param_with_ramadan() {
+        match Authority::Makkah.isha_param_with_ramadan(true) {
+            IshaParam::Duration(d) => assert_eq!(d.as_secs(), 7200),
+            IshaParam::Angle(_) => panic!("Makkah isha should be a duration"),
+        }
+        assert_eq!(
+            Authority::Makkah.isha_param_with_ramadan(false),
+            Authority::Makkah.isha_param()
+        );
+        for auth in Authority::list() {
+            if auth != Authority::Makkah {
+                assert_eq!(auth.isha_param_with_ramadan(true), auth.isha_param());
+            }
+        }
+    }
+
+    #[test]
+    fn test_regional_authorities() {
+        let minutes = |m: u64| IshaParam::Duration(std::time::Duration::from_secs(m * 60));
+        let expected = [
+            (Authority::Diyanet, 18.0, IshaParam::Angle(17.0)),
+            (Authority::Gulf, 19.5, minutes(90)),
+            (Authority::Kuwait, 18.0, IshaParam::Angle(17.5)),
+            (Authority::Qatar, 18.0, minutes(90)),
+            (Authority::Singapore, 20.0, IshaParam::Angle(18.0)),
+            (Authority::UOIF, 12.0, IshaParam::Angle(12.0)),
+        ];
+        for (auth, fajr_angle, isha) in expected {
+            assert_eq!(auth.fajr_angle(), fajr_angle, "{}", auth.to_str());
+            assert_eq!(auth.isha_param(), isha, "{}", auth.to_str());
+            assert_eq!(Authority::from_str(auth.to_str()), Ok(auth));
+            assert!(Authority::list().contains(&auth));
+        }
+    }
+
+    #[test]
+    fn test_maghrib_delay() {
+        let four_minutes = std::time::Duration::from_secs(4 * 60);
+        assert_eq!(Authority::Tehran.maghrib_delay(), four_minutes);
+        assert_eq!(Authority::Jafari.maghrib_delay(), four_minutes);
+        for auth in Authority::list() {
+            if auth != Authority::Tehran && auth != Authority::Jafari {
+                assert!(auth.maghrib_delay().is_zero(), "{}", auth.to_str());
+            }
+        }
+    }
+
+    #[test]
+    fn test_midnight_method() {
+        assert_eq!(Authority::Jafari.midnight_method(), MidnightMethod::Jafari);
+        for auth in Authority::list() {
+            if auth != Authority::Jafari {
+                assert_eq!(auth.midnight_method(), MidnightMethod::Standard);
+            }
+        }
+        assert_eq!(
+            MidnightMethod::from_str("Jafari"),
+            Ok(MidnightMethod::Jafari)
+        );
+        assert_eq!(MidnightMethod::Standard.to_str(), "standard");
+    }
+
+    #[test]
+    fn test_adjustments() {
+        let adjustments = Adjustments {
+            fajr: 2,
+            isha: -3,
+            ..Default::default()
+        };
+        assert_eq!(adjustments.minutes(&Timing::Fajr), 2);
+        assert_eq!(adjustments.minutes(&Timing::Isha), -3);
+        assert_eq!(adjustments.minutes(&Timing::Dhuhr), 0);
+        assert_eq!(adjustments.minutes(&Timing::Imsak), 0);
+
+        // Missing fields default to no offset
+        let parsed: Adjustments = serde_json::from_str(r#"{"fajr":2,"isha":-3}"#).expect("Error!");
+        assert_eq!(parsed, adjustments);
+    }
+
+    #[test]
+    fn test_serde() {
+        for auth in Authority::list() {
+            let json = serde_json::to_string(&auth).expect("Error!");
+            assert_eq!(json, format!("\"{}\"", auth.to_str().to_lowercase()));
+            assert_eq!(
+                serde_json::from_str::<Authority>(&json).expect("Error!"),
+                auth
+            );
+        }
+        let custom = Authority::Custom {
+            fajr_angle: 16.5,
+            isha: IshaParam::Duration(std::time::Duration::from_secs(75 * 60)),
+        };
+        let json = serde_json::to_string(&custom).expect("Error!");
+        assert_eq!(
+            serde_json::from_str::<Authority>(&json).expect("Error!"),
+            custom
+        );
+        let angles: Authority =
+            serde_json::from_str(r#"{"custom":{"fajr_angle":16.5,"isha":{"angle":15.0}}}"#)
+                .expect("Error!");
+        assert_eq!(
+            angles,
+            Authority::Custom {
+                fajr_angle: 16.5,
+                isha: IshaParam::Angle(15.0)
+            }
+        );
+        // Names are case insensitive, as with `from_str`
+        assert_eq!(
+            serde_json::from_str::<Authority>("\"ISNA\"").expect("Error!"),
+            Authority::ISNA
+        );
+        assert!(serde_json::from_str::<Authority>("\"unknown\"").is_err());
+
+        for timing in Timing::list() {
+            let json = serde_json::to_string(&timing).expect("Error!");
+            assert_eq!(json, format!("\"{}\"", timing.to_str()));
+            assert_eq!(
+                serde_json::from_str::<Timing>(&json).expect("Error!"),
+                timing
+            );
+        }
+
+        for school in School::list() {
+            let json = serde_json::to_string(&school).expect("Error!");
+            assert_eq!(
+                serde_json::from_str::<School>(&json).expect("Error!"),
+                school
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&School::Hanafi).expect("Error!"),
+            "\"hanafi\""
+        );
+
+        assert_eq!(
+            serde_json::to_string(&School::Maliki).expect("Error!"),
+            "\"maliki\""
+        );
+
+        for method in [MidnightMethod::Standard, MidnightMethod::Jafari] {
+            let json = serde_json::to_string(&method).expect("Error!");
+            assert_eq!(json, format!("\"{}\"", method.to_str()));
+            assert_eq!(
+                serde_json::from_str::<MidnightMethod>(&json).expect("Error!"),
+                method
+            );
+        }
+    }
+
+    #[test]
+    fn test_calculation_parameters_serde() {
+        let params = CalculationParameters {
+            authority: Authority::Custom {
+                fajr_angle: 16.5,
+                isha: IshaParam::Duration(std::time::Duration::from_secs(75 * 60)),
+            },
+            school: School::Shafi,
+            night_fraction_clamp: Some(1_f64 / 7_f64),
+            adjustments: Adjustments {
+                fajr: 2,
+                ..Default::default()
+            },
+            rounding: Rounding::Seconds,
+        };
+        let json = serde_json::to_string(&params).expect("Error!");
+        assert_eq!(
+            serde_json::from_str::<CalculationParameters>(&json).expect("Error!"),
+            params
+        );
+
+        // Missing fields keep the defaults
+        let parsed: CalculationParameters =
+            serde_json::from_str(r#"{"authority":"mwl","rounding":"floor"}"#).expect("Error!");
+        assert_eq!(
+            parsed,
+            CalculationParameters {
+                authority: Authority::MWL,
+                rounding: Rounding::Floor,
+                ..Default::default()
+            }
+        );
+        assert_eq!(parsed.school, School::Hanafi);
+    }
+
+    #[test]
+    fn test_twilight() {
+        for twilight in Twilight::list() {
+            assert_eq!(
+                Twilight::from_str(twilight.to_str()).expect("Error!"),
+                twilight
+            );
+        }
+        assert_eq!(Twilight::Civil.angle(), 6_f64);
+        assert_eq!(
+            Twilight::from_str("Nautical").expect("Error!").angle(),
+            12_f64
+        );
+        assert_eq!(Twilight::Astronomical.angle(), 18_f64);
+        assert!(Twilight::from_str("golden").is_err());
+    }
+
+    #[test]
+    fn test_from_angles() {
+        assert_eq!(
+            Authority::from_angles(18.0, IshaParam::Angle(17.0)),
+            Authority::MWL
+        );
+        assert_eq!(
+            Authority::from_angles(
+                18.5,
+                IshaParam::Duration(std::time::Duration::from_secs(90 * 60))
+            ),
+            Authority::Makkah
+        );
+        assert_eq!(
+            Authority::from_angles(16.5, IshaParam::Angle(15.0)),
+            Authority::Custom {
+                fajr_angle: 16.5,
+                isha: IshaParam::Angle(15.0)
+            }
+        );
+
+        // Every named authority round trips through its params, except those sharing
+        // the angles of an authority listed before them (the first listed authority wins)
+        for auth in Authority::list() {
+            let expected = match auth {
+                Authority::Diyanet => Authority::MWL,
+                Authority::MoonsightingCommittee => Authority::Karachi,
+                _ => auth,
+            };
+            assert_eq!(Authority::from(auth.params()), expected);
+        }
+    }
+}
+
+/// Named twilights by the depression angle of the sun below the horizon, as an alternative to numeric fajr/isha angles
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Twilight {
+    /// 6 degrees, bright enough to see outdoors without lighting
+    Civil,
+    /// 12 degrees, the horizon is still visible at sea
+    Nautical,
+    /// 18 degrees, the sky is fully dark (the traditional true dawn and end of dusk)
+    Astronomical,
+}
+
+impl Twilight {
+    pub fn from_str(name: &str) -> Result<Self, SalahError> {
+        let lowercase = name.to_lowercase();
+        match lowercase.as_str() {
+            "civil" => Ok(Self::Civil),
+            "nautical" => Ok(Self::Nautical),
+            "astronomical" => Ok(Self::Astronomical),
+            _ => Err(SalahError::InvalidTwilight(name.to_owned())),
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Civil => "civil",
+            Self::Nautical => "nautical",
+            Self::Astronomical => "astronomical",
+        }
+    }
+
+    pub fn list() -> [Self; 3] {
+        return [Self::Civil, Self::Nautical, Self::Astronomical];
+    }
+
+    /// Returns the angle of the sun below the horizon in degrees
+    pub fn angle(&self) -> f64 {
+        match self {
+            Self::Civil => 6_f64,
+            Self::Nautical => 12_f64,
+            Self::Astronomical => 18_f64,
+        }
+    }
+}
+
+/// How the night is measured for midnight
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MidnightMethod {
+    /// Midpoint between sunset and sunrise
+    Standard,
+    /// Midpoint between sunset and fajr (Shia Ithna Ashari)
+    Jafari,
+}
+
+impl MidnightMethod {
+    pub fn from_str(name: &str) -> Result<Self, SalahError> {
+        let lowercase = name.to_lowercase();
+        match lowercase.as_str() {
+            "standard" => Ok(Self::Standard),
+            "jafari" => Ok(Self::Jafari),
+            _ => Err(SalahError::InvalidMidnightMethod(name.to_owned())),
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Standard => "standard",
+            Self::Jafari => "jafari",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum School {
     Hanafi,
     Shafi,
+    Maliki,
+    Hanbali,
 }
 
 impl School {
+    pub fn from_str(name: &str) -> Result<Self, SalahError> {
+        let lowercase = name.to_lowercase();
+        match lowercase.as_str() {
+            "hanafi" => Ok(Self::Hanafi),
+            "shafi" | "shafii" | "standard" => Ok(Self::Shafi),
+            "maliki" => Ok(Self::Maliki),
+            "hanbali" => Ok(Self::Hanbali),
+            _ => Err(SalahError::InvalidSchool(name.to_owned())),
+        }
+    }
+
+    pub fn to_str(&self) -> &str {
+        match self {
+            Self::Hanafi => "hanafi",
+            Self::Shafi => "shafi",
+            Self::Maliki => "maliki",
+            Self::Hanbali => "hanbali",
+        }
+    }
+
+    pub fn list() -> [Self; 4] {
+        return [Self::Hanafi, Self::Shafi, Self::Maliki, Self::Hanbali];
+    }
+
+    /// Length of the shadow (in object lengths) for asr. Only the Hanafi school uses twice the length.
     pub fn shadow_length(&self) -> f64 {
         match self {
             Self::Hanafi => 2_f64,
-            Self::Shafi => 1_f64,
+            Self::Shafi | Self::Maliki | Self::Hanbali => 1_f64,
         }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+/// Serialized as the lowercase name (e.g. `"mwl"`), or `{"custom": {"fajr_angle": .., "isha": ..}}` for custom parameters
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "AuthorityRepr", into = "AuthorityRepr")]
 pub enum Authority {
     MWL,
     ISNA,
@@ -22,26 +477,141 @@ pub enum Authority {
     Karachi,
     Tehran,
     Jafari,
+    Diyanet,
+    Gulf,
+    Kuwait,
+    Qatar,
+    Singapore,
+    UOIF,
+    /// 18 degrees bounded by seasonal intervals from sunrise/sunset (see `times::moonsighting`)
+    MoonsightingCommittee,
+    /// User specified fajr angle and isha angle/interval
+    Custom {
+        fajr_angle: f64,
+        isha: IshaParam,
+    },
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum IshaParam {
     Angle(f64),
     Duration(std::time::Duration),
 }
 
+/// The numeric parameters that define a calculation method
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MethodParams {
+    /// Angle of the sun below the horizon for fajr, in degrees
+    pub fajr_angle: f64,
+
+    /// Angle of the sun below the horizon or interval after maghrib for isha
+    pub isha: IshaParam,
+}
+
+/// The fajr and isha parameters of a calculation method as plain fields, e.g. for display.
+/// Exactly one of `isha` and `isha_minutes` is set.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize)]
+pub struct AngleSet {
+    /// Angle of the sun below the horizon for fajr, in degrees
+    pub fajr: f64,
+
+    /// Angle of the sun below the horizon for isha, in degrees
+    pub isha: Option<f64>,
+
+    /// Interval after maghrib for isha, in minutes
+    pub isha_minutes: Option<u32>,
+}
+
+impl AngleSet {
+    /// Flattens a fajr angle and isha parameter into an `AngleSet`
+    ///
+    /// ### Arguments
+    /// * `fajr` - Angle of the sun below the horizon for fajr, in degrees
+    /// * `isha` - Angle or interval after maghrib for isha
+    pub fn from_params(fajr: f64, isha: IshaParam) -> AngleSet {
+        let (isha, isha_minutes) = match isha {
+            IshaParam::Angle(angle) => (Some(angle), None),
+            IshaParam::Duration(d) => (None, Some((d.as_secs() / 60) as u32)),
+        };
+        return AngleSet {
+            fajr,
+            isha,
+            isha_minutes,
+        };
+    }
+}
+
+/// Serialized form of `Authority`
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum AuthorityRepr {
+    Name(String),
+    Custom { custom: MethodParams },
+}
+
+impl From<Authority> for AuthorityRepr {
+    fn from(auth: Authority) -> Self {
+        match auth {
+            Authority::Custom { fajr_angle, isha } => {
+                return AuthorityRepr::Custom {
+                    custom: MethodParams { fajr_angle, isha },
+                }
+            }
+            _ => return AuthorityRepr::Name(auth.to_str().to_lowercase()),
+        }
+    }
+}
+
+impl TryFrom<AuthorityRepr> for Authority {
+    type Error = String;
+
+    fn try_from(repr: AuthorityRepr) -> Result<Self, Self::Error> {
+        match repr {
+            AuthorityRepr::Name(name) => {
+                return Authority::from_str(&name).map_err(|e| e.to_string())
+            }
+            AuthorityRepr::Custom { custom } => {
+                return Ok(Authority::Custom {
+                    fajr_angle: custom.fajr_angle,
+                    isha: custom.isha,
+                })
+            }
+        }
+    }
+}
+
+impl From<Authority> for MethodParams {
+    fn from(auth: Authority) -> Self {
+        return auth.params();
+    }
+}
+
+impl From<MethodParams> for Authority {
+    fn from(params: MethodParams) -> Self {
+        return Authority::from_angles(params.fajr_angle, params.isha);
+    }
+}
+
 impl Authority {
-    pub fn from_str(name: &str) -> Option<Self> {
+    pub fn from_str(name: &str) -> Result<Self, SalahError> {
         let lowercase = name.to_lowercase();
         match lowercase.as_str() {
-            "mwl" => Some(Self::MWL),
-            "isna" => Some(Self::ISNA),
-            "egypt" => Some(Self::Egypt),
-            "makkah" => Some(Self::Makkah),
-            "karachi" => Some(Self::Karachi),
-            "tehran" => Some(Self::Tehran),
-            "jafari" => Some(Self::Jafari),
-            _ => None,
+            "mwl" => Ok(Self::MWL),
+            "isna" => Ok(Self::ISNA),
+            "egypt" => Ok(Self::Egypt),
+            "makkah" => Ok(Self::Makkah),
+            "karachi" => Ok(Self::Karachi),
+            "tehran" => Ok(Self::Tehran),
+            "jafari" => Ok(Self::Jafari),
+            "diyanet" => Ok(Self::Diyanet),
+            "gulf" => Ok(Self::Gulf),
+            "kuwait" => Ok(Self::Kuwait),
+            "qatar" => Ok(Self::Qatar),
+            "singapore" => Ok(Self::Singapore),
+            "uoif" => Ok(Self::UOIF),
+            "moonsighting" | "moonsightingcommittee" => Ok(Self::MoonsightingCommittee),
+            _ => Err(SalahError::InvalidAuthority(name.to_owned())),
         }
     }
     pub fn fajr_angle(&self) -> f64 {
@@ -53,6 +623,14 @@ impl Authority {
             Self::Karachi => 18_f64,
             Self::Tehran => 17.7_f64,
             Self::Jafari => 16_f64,
+            Self::Diyanet => 18_f64,
+            Self::Gulf => 19.5_f64,
+            Self::Kuwait => 18_f64,
+            Self::Qatar => 18_f64,
+            Self::Singapore => 20_f64,
+            Self::UOIF => 12_f64,
+            Self::MoonsightingCommittee => 18_f64,
+            Self::Custom { fajr_angle, .. } => *fajr_angle,
         }
     }
 
@@ -65,9 +643,80 @@ impl Authority {
             Self::Karachi => IshaParam::Angle(18_f64),
             Self::Tehran => IshaParam::Angle(14_f64),
             Self::Jafari => IshaParam::Angle(14_f64),
+            Self::Diyanet => IshaParam::Angle(17_f64),
+            Self::Gulf => IshaParam::Duration(std::time::Duration::from_secs(90 * 60)),
+            Self::Kuwait => IshaParam::Angle(17.5_f64),
+            Self::Qatar => IshaParam::Duration(std::time::Duration::from_secs(90 * 60)),
+            Self::Singapore => IshaParam::Angle(18_f64),
+            Self::UOIF => IshaParam::Angle(12_f64),
+            Self::MoonsightingCommittee => IshaParam::Angle(18_f64),
+            Self::Custom { isha, .. } => *isha,
+        }
+    }
+
+    /// Returns the isha parameter, taking into account authorities that change it during Ramadan
+    /// (Umm al-Qura, Makkah uses 120 minutes after maghrib instead of 90 during Ramadan)
+    ///
+    /// ### Arguments
+    /// * `ramadan` - Whether the date is in Ramadan
+    pub fn isha_param_with_ramadan(&self, ramadan: bool) -> IshaParam {
+        match self {
+            Self::Makkah if ramadan => {
+                IshaParam::Duration(std::time::Duration::from_secs(120 * 60))
+            }
+            _ => self.isha_param(),
+        }
+    }
+
+    /// Returns the delay after sunset (the sun's upper limb at the 0.833 degree horizon) for maghrib.
+    /// Tehran and Jafari wait about 4 minutes for the sun to fully clear the horizon, the others pray at sunset.
+    pub fn maghrib_delay(&self) -> std::time::Duration {
+        match self {
+            Self::Tehran | Self::Jafari => std::time::Duration::from_secs(4 * 60),
+            _ => std::time::Duration::ZERO,
+        }
+    }
+
+    /// Returns how the authority measures the night for midnight
+    pub fn midnight_method(&self) -> MidnightMethod {
+        match self {
+            Self::Jafari => MidnightMethod::Jafari,
+            _ => MidnightMethod::Standard,
         }
     }
 
+    /// Returns the fajr/isha parameters of the authority flattened into plain fields
+    pub fn angles(&self) -> AngleSet {
+        return AngleSet::from_params(self.fajr_angle(), self.isha_param());
+    }
+
+    /// Returns the numeric fajr/isha parameters of the authority
+    pub fn params(&self) -> MethodParams {
+        return MethodParams {
+            fajr_angle: self.fajr_angle(),
+            isha: self.isha_param(),
+        };
+    }
+
+    /// Finds the named authority matching the given fajr angle and isha parameter,
+    /// falling back to `Authority::Custom` when none match
+    ///
+    /// ### Arguments
+    /// * `fajr_angle` - Angle of the sun below the horizon for fajr, in degrees
+    /// * `isha` - Angle of the sun below the horizon or interval after maghrib for isha
+    pub fn from_angles(fajr_angle: f64, isha: IshaParam) -> Self {
+        const TOLERANCE: f64 = 1e-6;
+        let found = Self::list().into_iter().find(|auth| {
+            let isha_matches = match (auth.isha_param(), isha) {
+                (IshaParam::Angle(a), IshaParam::Angle(b)) => (a - b).abs() < TOLERANCE,
+                (IshaParam::Duration(a), IshaParam::Duration(b)) => a == b,
+                _ => false,
+            };
+            return (auth.fajr_angle() - fajr_angle).abs() < TOLERANCE && isha_matches;
+        });
+        return found.unwrap_or(Self::Custom { fajr_angle, isha });
+    }
+
     pub fn name(&self) -> &str {
         match self {
             Self::MWL => "Muslim World League",
@@ -77,6 +726,14 @@ impl Authority {
             Self::Karachi => "University of Islamic Sciences, Karachi",
             Self::Tehran => "Institute of Geophysics, University of Tehran",
             Self::Jafari => "Shia Ithna Ashari, Leva Research Institute, Qum",
+            Self::Diyanet => "Presidency of Religious Affairs (Diyanet), Turkey",
+            Self::Gulf => "Gulf Region",
+            Self::Kuwait => "Ministry of Awqaf and Islamic Affairs, Kuwait",
+            Self::Qatar => "Ministry of Awqaf and Islamic Affairs, Qatar",
+            Self::Singapore => "Majlis Ugama Islam Singapura (MUIS), Singapore",
+            Self::UOIF => "Union des Organisations Islamiques de France",
+            Self::MoonsightingCommittee => "Moonsighting Committee Worldwide",
+            Self::Custom { .. } => "Custom",
         }
     }
 
@@ -89,22 +746,40 @@ impl Authority {
             Self::Karachi => "Karachi",
             Self::Tehran => "Tehran",
             Self::Jafari => "Jafari",
+            Self::Diyanet => "Diyanet",
+            Self::Gulf => "Gulf",
+            Self::Kuwait => "Kuwait",
+            Self::Qatar => "Qatar",
+            Self::Singapore => "Singapore",
+            Self::UOIF => "UOIF",
+            Self::MoonsightingCommittee => "Moonsighting",
+            Self::Custom { .. } => "Custom",
         }
     }
 
     pub fn desc(&self) -> &str {
         match self {
             Self::MWL => "Fajr at 18 degrees, Isha at 17 degrees.",
-            Self::ISNA => "Fajr at 15 degrees, Isha at 18 degrees.",
+            Self::ISNA => "Fajr at 15 degrees, Isha at 15 degrees.",
             Self::Egypt => "Fajr at 19.5 degrees, Isha at 17.5 degrees",
-            Self::Makkah => "Fajr at 18.5 degrees, Isha 90 min after Maghrib.",
+            Self::Makkah => "Fajr at 18.5 degrees, Isha 90 min after Maghrib (120 min in Ramadan).",
             Self::Karachi => "Fajr at 18 degrees, Isha at 18 degrees.",
-            Self::Tehran => "Fajr at 17.7 degrees, Isha at 14 degrees.",
-            Self::Jafari => "Fajr at 16 degrees, Isha at 14 degrees.",
+            Self::Tehran => "Fajr at 17.7 degrees, Isha at 14 degrees, Maghrib 4 min after sunset.",
+            Self::Jafari => "Fajr at 16 degrees, Isha at 14 degrees, Maghrib 4 min after sunset.",
+            Self::Diyanet => "Fajr at 18 degrees, Isha at 17 degrees.",
+            Self::Gulf => "Fajr at 19.5 degrees, Isha 90 min after Maghrib.",
+            Self::Kuwait => "Fajr at 18 degrees, Isha at 17.5 degrees.",
+            Self::Qatar => "Fajr at 18 degrees, Isha 90 min after Maghrib.",
+            Self::Singapore => "Fajr at 20 degrees, Isha at 18 degrees.",
+            Self::UOIF => "Fajr at 12 degrees, Isha at 12 degrees.",
+            Self::MoonsightingCommittee => {
+                "Fajr at 18 degrees, Isha at 18 degrees, within seasonal limits by latitude."
+            }
+            Self::Custom { .. } => "User specified Fajr angle and Isha angle or interval.",
         }
     }
 
-    pub fn list() -> [Self; 7] {
+    pub fn list() -> [Self; 14] {
         return [
             Authority::MWL,
             Authority::ISNA,
@@ -113,69 +788,188 @@ impl Authority {
             Authority::Karachi,
             Authority::Tehran,
             Authority::Jafari,
+            Authority::Diyanet,
+            Authority::Gulf,
+            Authority::Kuwait,
+            Authority::Qatar,
+            Authority::Singapore,
+            Authority::UOIF,
+            Authority::MoonsightingCommittee,
         ];
     }
 }
 
-#[derive(Debug)]
+impl std::fmt::Display for Authority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f.pad(self.to_str());
+    }
+}
+
+impl std::str::FromStr for Authority {
+    type Err = SalahError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return Authority::from_str(name);
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Timing {
+    Imsak,
     Fajr,
     Sunrise,
+    Duha,
     Dhuhr,
     Asr,
     Maghrib,
     Isha,
     Midnight,
+    #[serde(rename = "last-third")]
+    LastThird,
 }
 
 impl Timing {
-    pub fn from_str(name: &str) -> Option<Self> {
+    pub fn from_str(name: &str) -> Result<Self, SalahError> {
         let lowercase = name.to_lowercase();
         match lowercase.as_str() {
-            "fajr" => Some(Self::Fajr),
-            "sunrise" => Some(Self::Sunrise),
-            "dhuhr" => Some(Self::Dhuhr),
-            "asr" => Some(Self::Asr),
-            "maghrib" => Some(Self::Maghrib),
-            "isha" => Some(Self::Isha),
-            "midnight" => Some(Self::Midnight),
-            _ => None,
+            "imsak" => Ok(Self::Imsak),
+            "fajr" => Ok(Self::Fajr),
+            "sunrise" => Ok(Self::Sunrise),
+            "duha" | "dhuha" => Ok(Self::Duha),
+            "dhuhr" => Ok(Self::Dhuhr),
+            "asr" => Ok(Self::Asr),
+            "maghrib" => Ok(Self::Maghrib),
+            "isha" => Ok(Self::Isha),
+            "midnight" => Ok(Self::Midnight),
+            "last-third" | "lastthird" | "tahajjud" => Ok(Self::LastThird),
+            _ => Err(SalahError::InvalidTiming(name.to_owned())),
         }
     }
 
     pub fn to_str(&self) -> &str {
         match self {
+            Self::Imsak => "imsak",
             Self::Fajr => "fajr",
             Self::Sunrise => "sunrise",
+            Self::Duha => "duha",
             Self::Dhuhr => "dhuhr",
             Self::Asr => "asr",
             Self::Maghrib => "maghrib",
             Self::Isha => "isha",
             Self::Midnight => "midnight",
+            Self::LastThird => "last-third",
         }
     }
 
     pub fn desc(&self) -> &str {
         match self {
+      Self::Imsak => "The time to stop eating before a fast. 10 minutes before Fajr by default.",
       Self::Fajr => "The dawn prayer time. Dependent on angle determined by authority (see salah authority)",
       Self::Sunrise => "Sunrise time. Fajr time ends at sunrise.",
+      Self::Duha => "The forenoon prayer time. 20 minutes after sunrise by default.",
       Self::Dhuhr => "The mid-day prayer time.",
       Self::Asr => "The evening prayer time. Dependent on school of thought (Hanafi vs Others).",
       Self::Maghrib => "The sunset prayer time.",
       Self::Isha => "The night prayer time. Dependent on angle determined by authority (see salah authority)",
-      Self::Midnight => "The Islamic midnight time. Isha time ends at midnight."
+      Self::Midnight => "The Islamic midnight time. Isha time ends at midnight.",
+      Self::LastThird => "Start of the last third of the night (sunset to sunrise). Preferred time for Tahajjud."
+    }
     }
+
+    /// Returns the 5 obligatory (fardh) prayers
+    pub fn fardh_list() -> [Self; 5] {
+        return [
+            Timing::Fajr,
+            Timing::Dhuhr,
+            Timing::Asr,
+            Timing::Maghrib,
+            Timing::Isha,
+        ];
     }
 
-    pub fn list() -> [Self; 7] {
+    pub fn list() -> [Self; 10] {
         return [
+            Timing::Imsak,
             Timing::Fajr,
             Timing::Sunrise,
+            Timing::Duha,
             Timing::Dhuhr,
             Timing::Asr,
             Timing::Maghrib,
             Timing::Isha,
             Timing::Midnight,
+            Timing::LastThird,
         ];
     }
 }
+
+impl std::fmt::Display for Timing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f.pad(self.to_str());
+    }
+}
+
+impl std::str::FromStr for Timing {
+    type Err = SalahError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        return Timing::from_str(name);
+    }
+}
+
+/// Signed offsets in minutes applied to the computed timings, e.g. to match a local mosque's timetable
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Adjustments {
+    pub fajr: i32,
+    pub sunrise: i32,
+    pub dhuhr: i32,
+    pub asr: i32,
+    pub maghrib: i32,
+    pub isha: i32,
+}
+
+impl Adjustments {
+    /// Returns the offset in minutes for a timing. Timings derived from others (imsak, midnight, last third) have none.
+    pub fn minutes(&self, timing: &Timing) -> i32 {
+        match timing {
+            Timing::Fajr => self.fajr,
+            Timing::Sunrise => self.sunrise,
+            Timing::Dhuhr => self.dhuhr,
+            Timing::Asr => self.asr,
+            Timing::Maghrib => self.maghrib,
+            Timing::Isha => self.isha,
+            Timing::Imsak | Timing::Duha | Timing::Midnight | Timing::LastThird => 0,
+        }
+    }
+}
+
+/// The calculation settings of `PrayerTimes` as a single config, e.g. to persist a user's settings as JSON
+/// and restore them with `PrayerTimes::with_parameters`. Missing fields take the defaults of `PrayerTimes::new`.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalculationParameters {
+    /// Calculation authority (fajr and isha)
+    pub authority: Authority,
+    /// School of thought for jurisprudence (asr)
+    pub school: School,
+    /// High latitude rule: minimum fraction of the night kept between fajr and sunrise (and sunset and isha)
+    pub night_fraction_clamp: Option<f64>,
+    /// Minute offsets applied to the computed timings
+    pub adjustments: Adjustments,
+    /// How the computed times are rounded
+    pub rounding: Rounding,
+}
+
+impl Default for CalculationParameters {
+    fn default() -> Self {
+        return CalculationParameters {
+            authority: Authority::ISNA,
+            school: School::Hanafi,
+            night_fraction_clamp: None,
+            adjustments: Adjustments::default(),
+            rounding: Rounding::default(),
+        };
+    }
+}
@@ -0,0 +1,403 @@
+use std::process::Command;
+
+fn salah(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_salah"))
+        .args(args)
+        .output()
+        .expect("Error!");
+    assert!(
+        output.status.success(),
+        "salah {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    return String::from_utf8(output.stdout).expect("Error!");
+}
+
+#[test]
+fn test_coord_defaults_to_fardh() {
+    let stdout = salah(&[
+        "coord",
+        "--lat",
+        "43.6534817",
+        "--lng=-79.3839347",
+        "--date",
+        "2024-02-11",
+        "--format",
+        "%H:%M",
+    ]);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "fajr 06:03",
+            "dhuhr 12:32",
+            "asr 15:16",
+            "maghrib 17:41",
+            "isha 19:01"
+        ]
+    );
+}
+
+#[test]
+fn test_coord_timings() {
+    let stdout = salah(&[
+        "coord",
+        "--lat",
+        "43.6534817",
+        "--lng=-79.3839347",
+        "--date",
+        "2024-02-11",
+        "--format",
+        "%H:%M",
+        "--hanafi",
+        "asr",
+        "sunrise",
+    ]);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, ["sunrise 07:23", "asr 15:57"]);
+}
+
+#[test]
+fn test_negative_coordinates_after_a_space() {
+    let stdout = salah(&[
+        "coord",
+        "--lat",
+        "43.6534817",
+        "--lng",
+        "-79.3839347",
+        "--date",
+        "2024-02-11",
+        "--format",
+        "%H:%M",
+        "fajr",
+    ]);
+    assert_eq!(stdout.trim(), "fajr 06:03");
+
+    // Santiago, Chile: south and west
+    let stdout = salah(&["qibla", "--lat", "-33.4489", "--lng", "-70.6693"]);
+    assert!(stdout.starts_with("qibla "), "{}", stdout);
+}
+
+#[test]
+fn test_diff() {
+    let args = [
+        "diff",
+        "--lat",
+        "43.6534817",
+        "--lng",
+        "-79.3839347",
+        "--date",
+        "2024-02-11",
+        "--timezone",
+        "America/Toronto",
+        "--auth",
+        "mwl",
+        "--auth",
+        "isna",
+    ];
+    let line = |stdout: &str, name: &str| {
+        return stdout
+            .lines()
+            .find(|line| line.starts_with(name))
+            .expect("Error!")
+            .split_whitespace()
+            .map(String::from)
+            .collect::<Vec<String>>();
+    };
+
+    // Seconds by default like the other commands
+    let stdout = salah(&args);
+    assert_eq!(line(&stdout, "asr"), ["asr", "15:16:00", "15:16:00", "0"]);
+
+    let stdout = salah(&[&args[..], &["--madhab", "hanafi", "--precision", "high"]].concat());
+    assert_eq!(line(&stdout, "asr")[1..3], ["15:57:00", "15:57:00"]);
+}
+
+#[test]
+fn test_coord_locale() {
+    let stdout = salah(&[
+        "coord",
+        "--lat",
+        "43.6534817",
+        "--lng=-79.3839347",
+        "--date",
+        "2024-02-11",
+        "--format",
+        "%H:%M",
+        "--locale",
+        "ar",
+        "fajr",
+    ]);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, ["الفجر 06:03"]);
+}
+
+#[test]
+fn test_coord_sort() {
+    let args = [
+        "coord",
+        "--lat",
+        "43.6534817",
+        "--lng=-79.3839347",
+        "--date",
+        "2024-02-11",
+        "--format",
+        "%H:%M",
+        "isha",
+        "fajr",
+        "dhuhr",
+    ];
+    let sorted = |sort: &str| {
+        let stdout = salah(&[&args[..], &["--sort", sort]].concat());
+        return stdout
+            .lines()
+            .map(|line| line.split(' ').next().expect("Error!").to_string())
+            .collect::<Vec<String>>();
+    };
+    assert_eq!(sorted("time"), ["fajr", "dhuhr", "isha"]);
+    assert_eq!(sorted("name"), ["dhuhr", "fajr", "isha"]);
+    assert_eq!(sorted("input"), ["isha", "fajr", "dhuhr"]);
+}
+
+#[test]
+fn test_json() {
+    let stdout = salah(&[
+        "coord",
+        "--lat",
+        "43.6534817",
+        "--lng=-79.3839347",
+        "--date",
+        "2024-02-11",
+        "--json",
+        "fajr",
+        "asr",
+        "isha",
+    ]);
+    let parsed: serde_json::Value = serde_json::from_str(stdout.trim()).expect("Error!");
+    let keys: Vec<&String> = parsed.as_object().expect("Error!").keys().collect();
+    assert_eq!(keys.len(), 4);
+    for key in ["fajr", "asr", "isha"] {
+        assert!(parsed[key].is_string(), "missing `{}` in {}", key, stdout);
+    }
+    assert_eq!(parsed["meta"]["version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn test_authority_list() {
+    let stdout = salah(&["authority"]);
+    for name in [
+        "MWL",
+        "Diyanet",
+        "Gulf",
+        "Kuwait",
+        "Qatar",
+        "Singapore",
+        "UOIF",
+    ] {
+        assert!(
+            stdout.lines().any(|l| l.trim_start().starts_with(name)),
+            "`{}` is not listed",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_coords_file() {
+    let dir = std::env::temp_dir().join(format!("salah-test-coords-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("Error!");
+    let path = dir.join("mosques.csv");
+    std::fs::write(
+        &path,
+        "name,lat,lng\nToronto,43.6534817,-79.3839347\nMakkah,21.4225,39.8262\n",
+    )
+    .expect("Error!");
+
+    let stdout = salah(&[
+        "coord",
+        "--coords-file",
+        path.to_str().expect("Error!"),
+        "--date",
+        "2024-02-11",
+        "--timezone",
+        "America/Toronto",
+        "--format",
+        "%H:%M",
+        "fajr",
+    ]);
+    let _ = std::fs::remove_dir_all(&dir);
+    let lines: Vec<&str> = stdout.lines().collect();
+    // One labeled block per row, separated by a blank line
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0], "[Toronto]");
+    assert_eq!(lines[1], "fajr 06:03");
+    assert_eq!(lines[2], "");
+    assert_eq!(lines[3], "[Makkah]");
+    assert!(lines[4].starts_with("fajr "));
+}
+
+#[test]
+fn test_warnings_go_to_stderr() {
+    // A longitude east of 180 is wrapped with a warning
+    let args = [
+        "coord",
+        "--lat",
+        "35",
+        "--lng=220",
+        "--timezone",
+        "Pacific/Honolulu",
+        "--date",
+        "2024-02-11",
+        "--json",
+        "fajr",
+    ];
+    let output = Command::new(env!("CARGO_BIN_EXE_salah"))
+        .args(args)
+        .output()
+        .expect("Error!");
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).expect("Error!");
+    assert!(stderr.starts_with("warning: "), "{}", stderr);
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is not valid JSON");
+    assert!(json["fajr"].is_string());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_salah"))
+        .args(args)
+        .arg("--quiet")
+        .output()
+        .expect("Error!");
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    assert!(serde_json::from_slice::<serde_json::Value>(&output.stdout).is_ok());
+}
+
+#[test]
+fn test_location_bundled_city() {
+    // Bundled cities need no network call (also run with `--no-default-features`)
+    let stdout = salah(&[
+        "location",
+        "--city",
+        "toronto",
+        "--country",
+        "CA",
+        "--date",
+        "2024-02-11",
+        "--format",
+        "%H:%M",
+        "fajr",
+    ]);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, ["fajr 06:03"]);
+
+    let stdout = salah(&[
+        "location",
+        "--city",
+        "toronto",
+        "--country",
+        "CA",
+        "--date",
+        "2024-02-11",
+        "--format",
+        "%H:%M",
+        "--hijri",
+        "fajr",
+    ]);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, ["hijri 1 Sha'ban 1445 AH", "fajr 06:03"]);
+}
+
+#[test]
+fn test_show_angles() {
+    let stdout = salah(&[
+        "coord",
+        "--lat",
+        "43.6534817",
+        "--lng=-79.3839347",
+        "--date",
+        "2024-02-11",
+        "--fajr-angle-override",
+        "16.5",
+        "--show-angles",
+        "fajr",
+    ]);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines.contains(&"authority ISNA"), "{}", stdout);
+    assert!(lines.contains(&"fajr-angle 16.5°"), "{}", stdout);
+    assert!(lines.contains(&"isha-angle 15°"), "{}", stdout);
+}
+
+#[test]
+fn test_lmt_rejects_civil_time_outputs() {
+    let coord = ["--lat", "43.6534817", "--lng=-79.3839347", "--lmt"];
+    for (command, extra) in [
+        ("coord", "--ics"),
+        ("coord", "--watch"),
+        ("coord", "--compare-now"),
+        ("next", ""),
+    ] {
+        let mut args = vec![command];
+        args.extend(coord);
+        if !extra.is_empty() {
+            args.push(extra);
+        }
+        let output = Command::new(env!("CARGO_BIN_EXE_salah"))
+            .args(&args)
+            .output()
+            .expect("Error!");
+        assert!(!output.status.success(), "salah {:?} succeeded", args);
+    }
+}
+
+#[test]
+fn test_next_rejects_other_outputs() {
+    // The arguments, and the option named in the error
+    let cases: [(&[&str], &str); 6] = [
+        (&["--json"], "--json"),
+        (&["--watch"], "--watch"),
+        (&["--csv"], "--csv"),
+        (&["-d", "2020-01-01"], "--date"),
+        (&["fajr"], "[TIMINGS]"),
+        (&["--lmt"], "--lmt"),
+    ];
+    for (args, name) in cases {
+        let output = Command::new(env!("CARGO_BIN_EXE_salah"))
+            .args(["next", "--lat", "43.6534817", "--lng=-79.3839347"])
+            .args(["--timezone", "America/Toronto"])
+            .args(args)
+            .output()
+            .expect("Error!");
+        assert!(!output.status.success(), "salah next {:?} succeeded", args);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(name), "{}", stderr);
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_salah"))
+        .args(["next", "--lat", "43.6534817", "--lng=-79.3839347"])
+        .args(["--timezone", "America/Toronto", "--date", "today"])
+        .output()
+        .expect("Error!");
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_calendar_rejects_other_outputs() {
+    for flag in ["--json", "--ics", "--tabular", "--watch", "--compare-now"] {
+        let args = [
+            "calendar",
+            "--lat",
+            "43.6534817",
+            "--lng=-79.3839347",
+            "--timezone",
+            "America/Toronto",
+            flag,
+        ];
+        let output = Command::new(env!("CARGO_BIN_EXE_salah"))
+            .args(args)
+            .output()
+            .expect("Error!");
+        assert!(!output.status.success(), "salah {:?} succeeded", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains(flag));
+    }
+}